@@ -0,0 +1,131 @@
+use crate::pattern::Pattern;
+use serde::{Deserialize, Serialize};
+
+// Every abbreviation the instructions use, with what it means.
+pub const GLOSSARY: [(&str, &str); 22] = [
+    ("k", "knit"),
+    ("p", "purl"),
+    ("st", "stitches"),
+    ("inc", "increase one stitch"),
+    ("dec", "decrease one stitch"),
+    ("k2tog", "knit two together"),
+    ("p2tog", "purl two together"),
+    ("yo", "yarn over"),
+    ("rep", "repeat"),
+    ("MB", "make bobble"),
+    ("WS", "wrong side"),
+    (
+        "C4B",
+        "slip 2 stitches to a cable needle, hold at back, k2, then k2 from the cable needle",
+    ),
+    (
+        "C4F",
+        "slip 2 stitches to a cable needle, hold at front, k2, then k2 from the cable needle",
+    ),
+    (
+        "brk1",
+        "brioche knit: knit the stitch together with its yarn over",
+    ),
+    (
+        "brp1",
+        "brioche purl: purl the stitch together with its yarn over",
+    ),
+    ("sl1yo", "slip one purlwise with a yarn over"),
+    ("sc", "single crochet"),
+    ("hdc", "half double crochet"),
+    ("dc", "double crochet"),
+    ("sc2tog", "single crochet two together"),
+    ("hdc2tog", "half double crochet two together"),
+    ("dc2tog", "double crochet two together"),
+];
+
+// The knitter's own abbreviations, as (usual abbreviation, replacement), for patterns written the
+// way they are used to reading them.
+#[derive(Clone, Default, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Abbreviations(pub Vec<(String, String)>);
+
+impl Abbreviations {
+    // What `abbreviation` is written as.
+    pub fn get<'a>(&'a self, abbreviation: &'a str) -> &'a str {
+        self.0
+            .iter()
+            .find(|(from, to)| from == abbreviation && !to.trim().is_empty())
+            .map_or(abbreviation, |(_, to)| to.trim())
+    }
+
+    // With `abbreviation` written as `replacement`, or as usual when it is left empty.
+    pub fn with(&self, abbreviation: &str, replacement: String) -> Abbreviations {
+        let mut overrides: Vec<(String, String)> = self
+            .0
+            .iter()
+            .filter(|(from, _)| from != abbreviation)
+            .cloned()
+            .collect();
+        if !replacement.trim().is_empty() {
+            overrides.push((abbreviation.to_string(), replacement));
+        }
+        Abbreviations(overrides)
+    }
+
+    // `text` with each abbreviation replaced, including those followed by a count, as "k12".
+    pub fn apply(&self, text: &str) -> String {
+        if self.0.is_empty() {
+            return text.to_string();
+        }
+        let mut written = String::new();
+        let mut word = String::new();
+        for c in text.chars() {
+            if c.is_ascii_alphanumeric() {
+                word.push(c);
+            } else {
+                written += &self.word(&word);
+                word.clear();
+                written.push(c);
+            }
+        }
+        written + &self.word(&word)
+    }
+
+    fn word(&self, word: &str) -> String {
+        match known(word) {
+            Some((abbreviation, count)) => format!("{}{}", self.get(abbreviation), count),
+            None => word.to_string(),
+        }
+    }
+
+    // The abbreviations `pattern` uses, as they are written in it, with their meanings.
+    pub fn glossary(&self, pattern: &Pattern) -> Vec<String> {
+        let usual = Pattern {
+            abbreviations: Abbreviations::default(),
+            ..pattern.clone()
+        };
+        let text: String = (0..usual.rows.len())
+            .map(|i| usual.written_row(i) + " ")
+            .collect();
+        let used: Vec<&str> = text
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter_map(known)
+            .map(|(abbreviation, _)| abbreviation)
+            .collect();
+        GLOSSARY
+            .iter()
+            .filter(|(abbreviation, _)| used.contains(abbreviation))
+            .map(|(abbreviation, meaning)| format!("{}: {}", self.get(abbreviation), meaning))
+            .collect()
+    }
+}
+
+// The abbreviation `word` is, with any count written after it.
+fn known(word: &str) -> Option<(&'static str, &str)> {
+    let stem = word.trim_end_matches(|c: char| c.is_ascii_digit());
+    GLOSSARY
+        .iter()
+        .find(|(abbreviation, _)| *abbreviation == word)
+        .map(|(abbreviation, _)| (*abbreviation, ""))
+        .or_else(|| {
+            GLOSSARY
+                .iter()
+                .find(|(abbreviation, _)| !stem.is_empty() && *abbreviation == stem)
+                .map(|(abbreviation, _)| (*abbreviation, &word[stem.len()..]))
+        })
+}
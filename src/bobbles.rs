@@ -0,0 +1,81 @@
+use crate::pattern::{Pattern, Row, Shaping};
+use rand::prelude::*;
+
+// Bobbles are kept at least this many stitches from shaping and from each other, so they don't
+// crowd an increase or sit on top of the bobble below.
+const CLEARANCE: usize = 1;
+
+// Stitches of a row, counted from 0 in the finished row, made by its increases or decreases; or
+// None when bobbles can't go in the row at all.
+fn shaping_stitches(row: &Row) -> Option<Vec<usize>> {
+    if row.wrong_side {
+        return None;
+    }
+    let every = |first: usize, step: usize, times: usize| -> Vec<usize> {
+        (0..times).map(|i| first + i * step).collect()
+    };
+    match row.shaping {
+        Shaping::Plain => Some(Vec::new()),
+        Shaping::Texture { stitch, .. } => stitch.background.then(Vec::new),
+        Shaping::Single | Shaping::Spaced { .. } | Shaping::Even { .. } => {
+            Some(row.shaping.increase_stitches(row.stitches))
+        }
+        Shaping::Decrease { dec, block } | Shaping::DecreaseSpaced { dec, block, .. } => {
+            Some(every(block as usize, block as usize + 1, dec as usize))
+        }
+        Shaping::DecreaseAt { ref knits } => Some(
+            knits
+                .iter()
+                .scan(0, |stitch, knit| {
+                    *stitch += *knit as usize + 1;
+                    Some(*stitch - 1)
+                })
+                .collect(),
+        ),
+        Shaping::CastOn
+        | Shaping::Purl
+        | Shaping::Bordered { .. }
+        | Shaping::Alternate { .. }
+        | Shaping::Ring { .. }
+        | Shaping::Amigurumi { .. }
+        | Shaping::Brioche { .. }
+        | Shaping::Eyelet
+        | Shaping::Cable { .. } => None,
+    }
+}
+
+// Scatters about `per_100` bobbles per hundred stitches over the right side rows, at places
+// picked by `seed`, keeping clear of the row's shaping and the bobbles in the row below.
+pub fn place(pattern: &mut Pattern, per_100: f64, seed: u64) {
+    if per_100 <= 0.0 || per_100.is_nan() {
+        return;
+    }
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut below: Vec<usize> = Vec::new();
+    for row in &mut pattern.rows {
+        let Some(shaping) = shaping_stitches(row) else {
+            below.clear();
+            continue;
+        };
+        let count = row.stitches.max(0) as usize;
+        // Never more than the row has stitches, however dense they are asked for:
+        let wanted = (f64::from(row.stitches) * per_100 / 100.0).min(count as f64);
+        // A fraction of a bobble is a chance of one:
+        let wanted = wanted.floor() as usize + usize::from(rng.gen_bool(wanted.fract()));
+        let clear =
+            |taken: &[usize], stitch: usize| taken.iter().all(|t| t.abs_diff(stitch) > CLEARANCE);
+        let mut bobbles: Vec<usize> = Vec::new();
+        for _ in 0..wanted.saturating_mul(4) {
+            if bobbles.len() == wanted || count == 0 {
+                break;
+            }
+            let stitch = rng.gen_range(0..count);
+            if clear(&shaping, stitch) && clear(&below, stitch) && clear(&bobbles, stitch) {
+                bobbles.push(stitch);
+            }
+        }
+        bobbles.sort();
+        below = bobbles.clone();
+        row.bobbles = bobbles;
+    }
+}
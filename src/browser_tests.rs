@@ -0,0 +1,110 @@
+// The app mounted in a real page, driven through its inputs the way a knitter would, to cover the
+// event handling and parsing between the form and the written pattern. Run in a browser with
+// `wasm-pack test --headless --firefox`.
+use crate::App;
+use std::time::Duration;
+use wasm_bindgen_test::*;
+use web_sys::wasm_bindgen::JsCast;
+use web_sys::{Element, Event, EventInit, HtmlInputElement};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+// A fresh copy of the app in its own element, with nothing remembered from an earlier test.
+async fn mount() -> Element {
+    let window = web_sys::window().unwrap();
+    if let Ok(Some(storage)) = window.local_storage() {
+        let _ = storage.clear();
+    }
+    let _ = window.location().set_hash("/");
+    let document = window.document().unwrap();
+    let root = document.create_element("div").unwrap();
+    document.body().unwrap().append_child(&root).unwrap();
+    yew::Renderer::<App>::with_root(root.clone()).render();
+    settle().await;
+    root
+}
+
+// Lets the app render whatever the last event changed.
+async fn settle() {
+    yew::platform::time::sleep(Duration::from_millis(50)).await;
+}
+
+// Types `value` into the text field with `placeholder`.
+async fn type_into(root: &Element, placeholder: &str, value: &str) {
+    let selector = format!("input[placeholder=\"{}\"]", placeholder);
+    let input: HtmlInputElement = root
+        .query_selector(&selector)
+        .unwrap()
+        .unwrap_or_else(|| panic!("no field {}", selector))
+        .unchecked_into();
+    input.set_value(value);
+    let init = EventInit::new();
+    init.set_bubbles(true);
+    let event = Event::new_with_event_init_dict("input", &init).unwrap();
+    input.dispatch_event(&event).unwrap();
+    settle().await;
+}
+
+// The written rows of the pattern, in order.
+fn rows(root: &Element) -> Vec<String> {
+    let labels = root.query_selector_all(".row-tracker label").unwrap();
+    (0..labels.length())
+        .filter_map(|i| labels.item(i)?.text_content())
+        .collect()
+}
+
+fn errors(root: &Element) -> Vec<String> {
+    let errors = root.query_selector_all(".field-error").unwrap();
+    (0..errors.length())
+        .filter_map(|i| errors.item(i)?.text_content())
+        .collect()
+}
+
+async fn ball(root: &Element, stitches: &str, rows: &str, diameter: &str) {
+    type_into(root, "Stitch count", stitches).await;
+    type_into(root, "Row count", rows).await;
+    type_into(root, "Diameter of sphere", diameter).await;
+}
+
+#[wasm_bindgen_test]
+async fn typing_a_gauge_and_size_writes_the_pattern() {
+    let root = mount().await;
+    assert!(rows(&root).is_empty());
+    ball(&root, "5", "7", "4").await;
+    let written = rows(&root);
+    assert!(written.len() > 6, "{:?}", written);
+    assert!(written[0].starts_with("Round 1: Cast on"), "{}", written[0]);
+    assert!(written.iter().any(|row| row.contains("inc")));
+    assert!(written.iter().any(|row| row.contains("k2tog")));
+}
+
+#[wasm_bindgen_test]
+async fn a_larger_ball_takes_more_rounds() {
+    let root = mount().await;
+    ball(&root, "5", "7", "4").await;
+    let small = rows(&root).len();
+    type_into(&root, "Diameter of sphere", "8").await;
+    assert!(rows(&root).len() > small);
+}
+
+#[wasm_bindgen_test]
+async fn fractions_and_decimal_commas_are_read_as_numbers() {
+    let root = mount().await;
+    ball(&root, "5", "7", "3 1/2").await;
+    let fraction = rows(&root);
+    type_into(&root, "Diameter of sphere", "3.5").await;
+    assert_eq!(rows(&root), fraction);
+    type_into(&root, "Diameter of sphere", "3,5").await;
+    assert_eq!(rows(&root), fraction);
+}
+
+#[wasm_bindgen_test]
+async fn a_size_that_isnt_a_number_is_pointed_out() {
+    let root = mount().await;
+    ball(&root, "5", "7", "four").await;
+    assert!(rows(&root).is_empty());
+    assert!(!errors(&root).is_empty());
+    type_into(&root, "Diameter of sphere", "4").await;
+    assert!(errors(&root).is_empty(), "{:?}", errors(&root));
+    assert!(!rows(&root).is_empty());
+}
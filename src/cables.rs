@@ -0,0 +1,131 @@
+use crate::params::Params;
+use crate::pattern::{Pattern, Shaping};
+use crate::stitchlib::{self, StitchPattern};
+use serde::{Deserialize, Serialize};
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+
+// Cables from a small library, worked as a panel running up a tube between a purl stitch on
+// each side.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Cable {
+    #[default]
+    None,
+    RightTwist,
+    LeftTwist,
+    Rope,
+    Braid,
+    Horseshoe,
+}
+
+impl Cable {
+    pub const ALL: [Cable; 6] = [
+        Cable::None,
+        Cable::RightTwist,
+        Cable::LeftTwist,
+        Cable::Rope,
+        Cable::Braid,
+        Cable::Horseshoe,
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Cable::None => "none",
+            Cable::RightTwist => "c4b",
+            Cable::LeftTwist => "c4f",
+            Cable::Rope => "rope",
+            Cable::Braid => "braid",
+            Cable::Horseshoe => "horseshoe",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.stitch().map_or("No cable", |stitch| stitch.name)
+    }
+
+    fn stitch(&self) -> Option<&'static StitchPattern> {
+        stitchlib::find(self.key())
+    }
+
+    pub fn from_key(key: &str) -> Option<Cable> {
+        Cable::ALL.into_iter().find(|c| c.key() == key)
+    }
+
+    // Stitches the cable itself takes up, not counting the purl stitch either side.
+    pub fn width(&self) -> i32 {
+        self.stitch().map_or(0, |stitch| stitch.width)
+    }
+
+    // What to do over the cable's stitches on each round of its repeat.
+    pub fn rounds(&self) -> &'static [&'static str] {
+        self.stitch().map_or(&[], |stitch| stitch.rows)
+    }
+}
+
+// Runs the cable up the body of a tube: from the first plain round after any brim up to the first
+// round that shapes or ribs again.
+pub fn apply(pattern: &mut Pattern, cable: Cable) {
+    let repeat = cable.rounds().len();
+    if repeat == 0 {
+        return;
+    }
+    let body = pattern
+        .rows
+        .iter_mut()
+        .skip(1)
+        .skip_while(
+            |row| matches!(row.shaping, Shaping::Texture { stitch, .. } if !stitch.background),
+        )
+        .take_while(|row| row.shaping == Shaping::Plain)
+        .filter(|row| row.stitches >= cable.width() + 2);
+    for (round, row) in body.enumerate() {
+        row.shaping = Shaping::Cable {
+            cable,
+            round: round % repeat,
+        };
+    }
+}
+
+// The cable's repeat drawn a character per stitch, last round at the top: '-' purl, '|' knit,
+// '/' and '\' the stitches of a right or left cross.
+pub fn chart_view(cable: Cable) -> Html {
+    let lines = cable.rounds().iter().rev().map(|round| {
+        let stitches: String = round
+            .split(", ")
+            .map(|step| {
+                let (kind, count) = step.split_at(1);
+                let count: usize = count.trim_end_matches(['B', 'F']).parse().unwrap_or(0);
+                let mark = match (kind, step.ends_with('B')) {
+                    ("C", true) => "/",
+                    ("C", false) => "\\",
+                    _ => "|",
+                };
+                mark.repeat(count)
+            })
+            .collect();
+        format!("-{}-", stitches)
+    });
+    html! {
+        <pre class="cable-chart">{ lines.collect::<Vec<_>>().join("\n") }</pre>
+    }
+}
+
+pub fn form(params: &Params, on_change: Callback<Cable>) -> Html {
+    let on_cable_change = Callback::from(move |e: Event| {
+        let select_el: HtmlSelectElement = e.target_unchecked_into();
+        if let Some(cable) = Cable::from_key(&select_el.value()) {
+            on_change.emit(cable);
+        }
+    });
+    let cables = Cable::ALL.iter().map(|cable| {
+        html! {
+            <option value={cable.key()} selected={*cable == params.cable}>{cable.name()}</option>
+        }
+    });
+    html! {
+        <span>
+            <label>{"Cable panel: "}</label>
+            <select aria-label="Cable panel" onchange={on_cable_change}>{ for cables }</select>
+        </span>
+    }
+}
@@ -0,0 +1,55 @@
+use crate::form::parse_count;
+use crate::gauge::Gauge;
+use crate::generator::GeneratorMessage;
+use crate::params::Params;
+use crate::parse::parse_length;
+use crate::pattern::Pattern;
+use crate::shapes::Shape;
+use yew::prelude::*;
+
+// When the widest row has more stitches than the needles hold, the two ways to fit it: a smaller
+// piece at the same gauge, or the same piece at a looser gauge. Every shape's width grows in step
+// with its size and its gauge, so both are worked out in proportion.
+pub fn advice(
+    shape: Shape,
+    params: &Params,
+    pattern: &Pattern,
+    gauge: Gauge,
+    on_message: &Callback<GeneratorMessage>,
+) -> Html {
+    let Some(limit) = parse_count(&params.max_stitches).filter(|l| *l > 0) else {
+        return html! {};
+    };
+    let limit = limit as i32;
+    let widest = pattern.rows.iter().map(|r| r.stitches).max().unwrap_or(0);
+    let Ok(size) = parse_length(shape.size(params), params.units(), params.locale) else {
+        return html! {};
+    };
+    if widest <= limit {
+        return html! {};
+    }
+    let scale = f64::from(limit) / f64::from(widest);
+    // Rounded down, so both suggestions stay within the limit.
+    let smaller = params.locale.short((size * scale * 10.0).floor() / 10.0);
+    let looser = params
+        .locale
+        .short((gauge.stitches_per_unit * scale * 10.0).floor() / 10.0);
+    let on_use_size = {
+        let smaller = smaller.clone();
+        on_message.reform(move |_| GeneratorMessage::UseSize(smaller.clone()))
+    };
+    let on_use_gauge = {
+        let looser = looser.clone();
+        on_message.reform(move |_| GeneratorMessage::SetStitchesPerUnit(looser.clone()))
+    };
+    html! {
+        <span class="field-note">
+            {format!(
+                "The widest row has {} stitches, more than the {} the needles hold. Make it {} {} {} at this gauge, or keep the size and knit {} stitches per {} in thicker yarn, which gives a heavier fabric and changes the row gauge too. ",
+                widest, limit, shape.size_name().to_lowercase(), smaller, params.units, looser, params.units
+            )}
+            <button type="button" onclick={on_use_size}>{format!("Use {} {}", smaller, params.units)}</button>
+            <button type="button" onclick={on_use_gauge}>{format!("Use {} st per {}", looser, params.units)}</button>
+        </span>
+    }
+}
@@ -0,0 +1,437 @@
+use crate::export;
+use crate::palette::Palette;
+use crate::pattern::{color_name, Pattern};
+use crate::png;
+use std::iter::zip;
+use web_sys::CanvasRenderingContext2d;
+use yew::prelude::*;
+
+// Size of one stitch in the chart, in pixels.
+const CELL: usize = 6;
+
+// How yarn colors A, B, C... are drawn when they weren't picked from a picture.
+pub const YARN_COLORS: [&str; 6] = [
+    "#f5f0e6", "#1a5fb4", "#c01c28", "#2ec27e", "#f6d32d", "#813d9c",
+];
+
+// A color for every stitch of every row of a pattern, with the yarn colors as CSS colors.
+#[derive(Clone, PartialEq)]
+pub struct Chart {
+    pub rows: Vec<Vec<usize>>,
+    pub palette: Vec<String>,
+}
+
+impl Chart {
+    // The colors the pattern is already worked in, each row from the left as seen from the right
+    // side.
+    pub fn from_pattern(pattern: &Pattern) -> Chart {
+        let flat = pattern.is_flat();
+        let rows = pattern
+            .rows
+            .iter()
+            .map(|row| {
+                let mut colors: Vec<usize> = row
+                    .runs()
+                    .iter()
+                    .flat_map(|&(color, stitches)| vec![color; stitches])
+                    .collect();
+                // Right side rows of flat pieces are worked from the right.
+                if flat && !row.wrong_side {
+                    colors.reverse();
+                }
+                colors
+            })
+            .collect();
+        Chart {
+            rows,
+            palette: YARN_COLORS.iter().map(|c| c.to_string()).collect(),
+        }
+    }
+
+    // Lays a picture over the chart in `color`. The picture is given as lines of text from the top,
+    // '#' marking a stitch to color; its bottom line goes on row index `bottom` and it is centered
+    // `around` (0 to 1) of the way along each row, wrapping past the end of the round.
+    pub fn stamp(&mut self, picture: &[String], bottom: usize, around: f64, color: usize) {
+        for (k, line) in picture.iter().rev().enumerate() {
+            let Some(row) = self.rows.get_mut(bottom + k) else {
+                break;
+            };
+            let stitches = row.len() as i64;
+            if stitches == 0 {
+                continue;
+            }
+            let start = (around * stitches as f64).round() as i64 - line.len() as i64 / 2;
+            for (c, mark) in line.chars().enumerate() {
+                if mark == '#' {
+                    row[(start + c as i64).rem_euclid(stitches) as usize] = color;
+                }
+            }
+        }
+    }
+}
+
+// Runs of the same color along a row, as (color, stitches).
+pub fn runs(row: &[usize]) -> Vec<(usize, usize)> {
+    let mut runs: Vec<(usize, usize)> = Vec::new();
+    for &color in row {
+        match runs.last_mut() {
+            Some((last, count)) if *last == color => *count += 1,
+            _ => runs.push((color, 1)),
+        }
+    }
+    runs
+}
+
+// A row of colorwork written out, e.g. "3A, 2B, 3A".
+pub fn written_runs(row: &[usize]) -> String {
+    runs(row)
+        .iter()
+        .map(|(color, count)| format!("{}{}", count, color_name(*color)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// The colorwork instructions for the rows of `pattern` that use more than one color.
+pub fn colorwork_instructions(pattern: &Pattern, chart: &Chart) -> Html {
+    let rows = pattern
+        .rows
+        .iter()
+        .zip(&chart.rows)
+        .enumerate()
+        .filter(|(_, (_, colors))| runs(colors).len() > 1)
+        .map(|(i, (_, colors))| {
+            html! {<li>{format!("{}: {}", pattern.label(i), written_runs(colors))}</li>}
+        });
+    html! {
+        <>
+            <p>{"Rows not listed are worked in their usual color; carry the unused color loosely across the back."}</p>
+            <ul>{ for rows }</ul>
+        </>
+    }
+}
+
+// Where to embroider the stitches that differ from `base` in duplicate stitch over the finished
+// piece, counting stitches from the left as seen from the right side.
+pub fn duplicate_stitch_instructions(pattern: &Pattern, base: &Chart, chart: &Chart) -> Html {
+    let rows = pattern
+        .rows
+        .iter()
+        .zip(zip(&base.rows, &chart.rows))
+        .enumerate()
+        .filter_map(|(i, (_, (before, after)))| {
+            let stitches: Vec<String> = zip(before, after)
+                .enumerate()
+                .filter(|(_, (b, a))| b != a)
+                .map(|(i, (_, a))| format!("{}{}", i + 1, color_name(*a)))
+                .collect();
+            (!stitches.is_empty())
+                .then(|| html! {<li>{format!("{}: stitches {}", pattern.label(i), stitches.join(", "))}</li>})
+        });
+    html! {
+        <>
+            <p>{"Knit the piece in its usual colors, then cover these stitches in duplicate stitch:"}</p>
+            <ul>{ for rows }</ul>
+        </>
+    }
+}
+
+// Room either side of the chart for row numbers.
+const MARGIN: usize = 4 * CELL;
+// Stitches are drawn bigger when they hold symbols, so the symbols can be read.
+const SYMBOL_CELL: usize = 12;
+
+// What a chart is drawn with: a square per stitch, filled and marked as `palette` says, and the
+// row numbers.
+struct Layout {
+    width: usize,
+    height: usize,
+    cell: usize,
+    // (x, y, fill, symbol)
+    cells: Vec<(usize, usize, String, String)>,
+    // (x, y, text-anchor, number)
+    numbers: Vec<(usize, usize, &'static str, usize)>,
+}
+
+impl Layout {
+    // The chart read from the bottom up like a printed chart, with each row centered so the
+    // shaping shows. Row 1 and every fifth row are numbered at the edge they are read from, as
+    // `pattern` numbers them.
+    fn new(chart: &Chart, pattern: &Pattern, palette: &Palette) -> Layout {
+        let cell = if palette.show_symbols {
+            SYMBOL_CELL
+        } else {
+            CELL
+        };
+        let margin = MARGIN * cell / CELL;
+        let widest = chart.rows.iter().map(Vec::len).max().unwrap_or(0);
+        let (width, height) = (widest * cell + 2 * margin, chart.rows.len() * cell);
+        let numbering = pattern.numbering;
+        let rows = pattern.rows.iter().take(chart.rows.len());
+        let numbers = rows
+            .enumerate()
+            .filter_map(|(i, row)| {
+                let shown = numbering
+                    .shown(row.number)
+                    .filter(|n| *n == 1 || n % 5 == 0)?;
+                let y = height - i * cell;
+                let (x, anchor) = match numbering.right_to_left(row.wrong_side) {
+                    true => (width - margin + cell / 2, "start"),
+                    false => (margin - cell / 2, "end"),
+                };
+                Some((x, y, anchor, shown))
+            })
+            .collect();
+        let fills: Vec<String> = (0..chart.palette.len())
+            .map(|yarn| palette.color(yarn, &chart.palette))
+            .collect();
+        let cells = chart
+            .rows
+            .iter()
+            .enumerate()
+            .flat_map(|(i, row)| {
+                let y = height - (i + 1) * cell;
+                let offset = margin + (widest - row.len()) * cell / 2;
+                let fills = &fills;
+                row.iter().enumerate().map(move |(j, yarn)| {
+                    let symbol = match palette.show_symbols {
+                        true => palette.symbol(*yarn),
+                        false => String::new(),
+                    };
+                    (offset + j * cell, y, fills[*yarn].clone(), symbol)
+                })
+            })
+            .collect();
+        Layout {
+            width,
+            height,
+            cell,
+            cells,
+            numbers,
+        }
+    }
+}
+
+// Dark symbols on light stitches and light ones on dark, for colors given as #rrggbb.
+fn ink(fill: &str) -> &'static str {
+    let channel = |i: usize| {
+        fill.get(i..i + 2)
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            .map_or(255, u32::from)
+    };
+    let light = fill.len() != 7 || 299 * channel(1) + 587 * channel(3) + 114 * channel(5) > 128_000;
+    if light {
+        "#000000"
+    } else {
+        "#ffffff"
+    }
+}
+
+fn reading(pattern: &Pattern) -> &'static str {
+    match (pattern.is_flat(), pattern.numbering.charts_right_to_left) {
+        (true, false) => "Read right side rows from right to left and wrong side rows from left to right, starting at the numbered edge.",
+        _ => "Read every row from right to left.",
+    }
+}
+
+// The size of a chart drawn on its own, with a key to the yarns below it.
+fn picture_size(chart: &Chart, layout: &Layout) -> (usize, usize) {
+    let key_height = 2 * layout.cell * chart.palette.len().div_ceil(3) + layout.cell;
+    (layout.width.max(60 * CELL), layout.height + key_height)
+}
+
+// The key below a chart drawn on its own, three yarns to a line: (x, y, fill, symbol, name).
+fn key(
+    chart: &Chart,
+    layout: &Layout,
+    palette: &Palette,
+) -> Vec<(usize, usize, String, String, String)> {
+    (0..chart.palette.len())
+        .map(|yarn| {
+            let x = CELL + (yarn % 3) * 20 * CELL;
+            let y = layout.height + layout.cell + (yarn / 3) * 2 * layout.cell;
+            let symbol = if palette.show_symbols {
+                palette.symbol(yarn)
+            } else {
+                String::new()
+            };
+            (
+                x,
+                y,
+                palette.color(yarn, &chart.palette),
+                symbol,
+                color_name(yarn),
+            )
+        })
+        .collect()
+}
+
+// The chart as a standalone SVG file, in the colors and symbols of `palette`, with a key to the
+// yarns below it.
+pub fn chart_svg(chart: &Chart, pattern: &Pattern, palette: &Palette) -> String {
+    let layout = Layout::new(chart, pattern, palette);
+    let (width, height) = picture_size(chart, &layout);
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" font-family=\"sans-serif\" font-size=\"{f}\">\n<rect width=\"{w}\" height=\"{h}\" fill=\"#ffffff\"/>\n",
+        w = width,
+        h = height,
+        f = layout.cell * 3 / 4 + 2,
+    );
+    let square = |x: usize, y: usize, fill: &str, symbol: &str| {
+        let mut shape = format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{c}\" height=\"{c}\" fill=\"{}\" stroke=\"#808080\" stroke-width=\"0.25\"/>\n",
+            x,
+            y,
+            fill,
+            c = layout.cell
+        );
+        if !symbol.is_empty() {
+            shape += &format!(
+                "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\" fill=\"{}\">{}</text>\n",
+                x + layout.cell / 2,
+                y + layout.cell / 2,
+                ink(fill),
+                symbol.replace('&', "&amp;").replace('<', "&lt;")
+            );
+        }
+        shape
+    };
+    for (x, y, fill, symbol) in &layout.cells {
+        svg += &square(*x, *y, fill, symbol);
+    }
+    for (x, y, anchor, number) in &layout.numbers {
+        svg += &format!(
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"{}\">{}</text>\n",
+            x, y, anchor, number
+        );
+    }
+    for (x, y, fill, symbol, name) in key(chart, &layout, palette) {
+        svg += &square(x, y, &fill, &symbol);
+        svg += &format!(
+            "<text x=\"{}\" y=\"{}\" dominant-baseline=\"central\">{}</text>\n",
+            x + layout.cell * 3 / 2,
+            y + layout.cell / 2,
+            name
+        );
+    }
+    svg + "</svg>\n"
+}
+
+// The same picture as `chart_svg`, drawn straight onto a canvas for a PNG.
+fn draw_chart(
+    context: &CanvasRenderingContext2d,
+    chart: &Chart,
+    layout: &Layout,
+    palette: &Palette,
+) {
+    let cell = layout.cell as f64;
+    context.set_font(&format!("{}px sans-serif", layout.cell * 3 / 4 + 2));
+    context.set_line_width(0.25);
+    context.set_stroke_style_str("#808080");
+    let square = |x: usize, y: usize, fill: &str, symbol: &str| {
+        let (x, y) = (x as f64, y as f64);
+        context.set_fill_style_str(fill);
+        context.fill_rect(x, y, cell, cell);
+        context.stroke_rect(x, y, cell, cell);
+        if !symbol.is_empty() {
+            context.set_fill_style_str(ink(fill));
+            context.set_text_align("center");
+            context.set_text_baseline("middle");
+            let _ = context.fill_text(symbol, x + cell / 2.0, y + cell / 2.0);
+        }
+    };
+    for (x, y, fill, symbol) in &layout.cells {
+        square(*x, *y, fill, symbol);
+    }
+    context.set_fill_style_str("#000000");
+    context.set_text_baseline("alphabetic");
+    for (x, y, anchor, number) in &layout.numbers {
+        context.set_text_align(match *anchor {
+            "middle" => "center",
+            anchor => anchor,
+        });
+        let _ = context.fill_text(&number.to_string(), *x as f64, *y as f64);
+    }
+    for (x, y, fill, symbol, name) in key(chart, layout, palette) {
+        square(x, y, &fill, &symbol);
+        context.set_fill_style_str("#000000");
+        context.set_text_align("start");
+        context.set_text_baseline("middle");
+        let _ = context.fill_text(
+            &name,
+            (x + layout.cell * 3 / 2) as f64,
+            y as f64 + cell / 2.0,
+        );
+    }
+}
+
+// The chart drawn on the page, with how to read it, a key to the yarns and a download of it as
+// SVG.
+pub fn chart_view(chart: &Chart, pattern: &Pattern, palette: &Palette) -> Html {
+    let layout = Layout::new(chart, pattern, palette);
+    let (width, height, cell) = (layout.width, layout.height, layout.cell);
+    let cells = layout.cells.iter().map(|(x, y, fill, symbol)| {
+        html! {
+            <>
+                <rect
+                    x={x.to_string()}
+                    y={y.to_string()}
+                    width={cell.to_string()}
+                    height={cell.to_string()}
+                    fill={fill.clone()}
+                />
+                if !symbol.is_empty() {
+                    <text
+                        class="symbol"
+                        x={(x + cell / 2).to_string()}
+                        y={(y + cell / 2).to_string()}
+                        style={format!("fill: {}", ink(fill))}
+                    >{symbol}</text>
+                }
+            </>
+        }
+    });
+    let numbers = layout.numbers.iter().map(|(x, y, anchor, number)| {
+        html! {
+            <text x={x.to_string()} y={y.to_string()} text-anchor={*anchor}>{number}</text>
+        }
+    });
+    let legend = (0..chart.palette.len()).map(|yarn| {
+        let css = palette.color(yarn, &chart.palette);
+        let symbol = if palette.show_symbols { palette.symbol(yarn) } else { String::new() };
+        html! {
+            <span>
+                <span class="swatch" style={format!("background: {}; color: {}", css, ink(&css))}>{symbol}</span>
+                {format!(" {}", color_name(yarn))}
+            </span>
+        }
+    });
+    let on_download = {
+        let svg = chart_svg(chart, pattern, palette);
+        Callback::from(move |_: MouseEvent| export::download("chart.svg", "image/svg+xml", &svg))
+    };
+    let on_download_png = {
+        let (chart, pattern, palette) = (chart.clone(), pattern.clone(), palette.clone());
+        Callback::from(move |_: MouseEvent| {
+            let layout = Layout::new(&chart, &pattern, &palette);
+            let (width, height) = picture_size(&chart, &layout);
+            png::download("chart.png", width as f64, height as f64, |context| {
+                draw_chart(context, &chart, &layout, &palette)
+            });
+        })
+    };
+
+    html! {
+        <div class="colorwork-chart">
+            <svg width={width.to_string()} height={height.to_string()} viewBox={format!("0 0 {} {}", width, height)}>
+                { for cells }
+                { for numbers }
+            </svg>
+            <p>{reading(pattern)}</p>
+            <p>{ for legend }</p>
+            <p>
+                <button type="button" onclick={on_download}>{"Download chart (SVG)"}</button>
+                <button type="button" onclick={on_download_png}>{"Download chart (PNG)"}</button>
+            </p>
+        </div>
+    }
+}
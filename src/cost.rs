@@ -0,0 +1,25 @@
+use crate::locale::Locale;
+
+// Skeins can't be shared between colors, so each color's skein count is rounded up separately.
+pub fn skeins_needed(lengths_by_color: &[f64], skein_length: f64) -> u32 {
+    lengths_by_color
+        .iter()
+        .map(|length| (length / skein_length).ceil() as u32)
+        .sum()
+}
+
+pub fn describe(
+    lengths_by_color: &[f64],
+    skein_length: f64,
+    price_per_skein: f64,
+    locale: Locale,
+) -> String {
+    let skeins = skeins_needed(lengths_by_color, skein_length);
+    format!(
+        "{} skein{} at {} each, {} in total",
+        skeins,
+        if skeins == 1 { "" } else { "s" },
+        locale.decimal(price_per_skein, 2),
+        locale.decimal(f64::from(skeins) * price_per_skein, 2)
+    )
+}
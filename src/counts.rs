@@ -0,0 +1,201 @@
+use serde::{Deserialize, Serialize};
+
+// How a row's length in stitches is made a whole number of stitches. Rounding changes the finished
+// size a little and the rhythm of the increases a lot.
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum Rounding {
+    #[default]
+    Nearest,
+    Down,
+    Up,
+    // To the nearest, with halves going to the even number.
+    HalfEven,
+    // To the nearest multiple of a chosen number of stitches.
+    Multiple,
+}
+
+impl Rounding {
+    pub const ALL: [Rounding; 5] = [
+        Rounding::Nearest,
+        Rounding::Down,
+        Rounding::Up,
+        Rounding::HalfEven,
+        Rounding::Multiple,
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Rounding::Nearest => "nearest",
+            Rounding::Down => "down",
+            Rounding::Up => "up",
+            Rounding::HalfEven => "even",
+            Rounding::Multiple => "multiple",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Rounding::Nearest => "Nearest",
+            Rounding::Down => "Down",
+            Rounding::Up => "Up",
+            Rounding::HalfEven => "Nearest, halves to even",
+            Rounding::Multiple => "Nearest multiple of",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Rounding> {
+        Rounding::ALL.into_iter().find(|r| r.key() == key)
+    }
+
+    // `multiple` is only used by `Multiple`.
+    pub fn apply(&self, stitches: f64, multiple: i32) -> i32 {
+        match self {
+            Rounding::Nearest => stitches.round() as i32,
+            Rounding::Down => stitches.floor() as i32,
+            Rounding::Up => stitches.ceil() as i32,
+            Rounding::HalfEven => stitches.round_ties_even() as i32,
+            Rounding::Multiple => {
+                let multiple = multiple.max(1);
+                (stitches / f64::from(multiple)).round() as i32 * multiple
+            }
+        }
+    }
+}
+
+// Passes over a sequence of stitch counts, row by row from the cast on, that reshape it to suit
+// the knitting without changing where it starts.
+
+// Flattens the one-stitch wobbles rounding leaves, such as 30, 31, 30, 32, by giving a row that
+// changes by one stitch against the rows either side the count of the row before it. The first and
+// last counts are never changed, so neither is the size.
+pub fn smooth(counts: &[i32]) -> Vec<i32> {
+    let mut smoothed = counts.to_vec();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for i in 1..smoothed.len().saturating_sub(1) {
+            let (before, after) = (smoothed[i] - smoothed[i - 1], smoothed[i + 1] - smoothed[i]);
+            if before.abs() == 1 && before * after < 0 {
+                smoothed[i] = smoothed[i - 1];
+                changed = true;
+            }
+        }
+    }
+    smoothed
+}
+
+// Starts with at least `least` stitches, however small the first rows come out: any row with
+// fewer is worked on `least` instead, as plain rows until the shaping catches up.
+pub fn raise_start(counts: &[i32], least: i32) -> Vec<i32> {
+    counts.iter().map(|count| (*count).max(least)).collect()
+}
+
+// Caps the increases in each row at one for every `per` stitches of the row below, and at least
+// one. What a row can't take is carried into the rows after it, and each stretch that had to
+// catch up is then evened out so it doesn't end in a burst of increases.
+pub fn cap_increases(counts: &[i32], per: i32) -> Vec<i32> {
+    let cap = |below: i32| (below / per.max(1)).max(1);
+    let clamp = |targets: &[i32], start: i32| -> Vec<i32> {
+        let mut previous = start;
+        targets
+            .iter()
+            .map(|target| {
+                previous = (*target).min(previous + cap(previous));
+                previous
+            })
+            .collect()
+    };
+    let Some((&first, rest)) = counts.split_first() else {
+        return Vec::new();
+    };
+    let mut capped = vec![first];
+    capped.extend(clamp(rest, first));
+    let mut i = 1;
+    while i < counts.len() {
+        if capped[i] == counts[i] {
+            i += 1;
+            continue;
+        }
+        // Rows `i` up to and including `end` fall short, then the counts meet again at `end + 1`,
+        // or never do.
+        let end = (i..counts.len())
+            .find(|j| capped[*j] == counts[*j])
+            .unwrap_or(counts.len());
+        let last = end.min(counts.len() - 1);
+        let (from, to) = (capped[i - 1], capped[last]);
+        let steps = (last + 1 - i) as f64;
+        let even: Vec<i32> = (i..=last)
+            .map(|j| from + ((to - from) as f64 * (j + 1 - i) as f64 / steps).round() as i32)
+            .collect();
+        let smoothed = clamp(&even, from);
+        capped[i..=last].copy_from_slice(&smoothed);
+        i = last + 1;
+    }
+    // Evening out can leave a stretch a little short where it meets the next, so check the cap once
+    // more all the way through.
+    clamp(&capped, first)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capping_nothing_or_one_count_keeps_it() {
+        assert!(cap_increases(&[], 2).is_empty());
+        assert_eq!(cap_increases(&[10], 2), [10]);
+    }
+
+    #[test]
+    fn capping_equal_counts_keeps_them() {
+        assert_eq!(cap_increases(&[12, 12, 12, 12], 2), [12, 12, 12, 12]);
+    }
+
+    #[test]
+    fn capping_leaves_gentle_shaping_and_decreases_alone() {
+        assert_eq!(cap_increases(&[6, 9, 12, 12], 2), [6, 9, 12, 12]);
+        assert_eq!(cap_increases(&[24, 12, 6], 2), [24, 12, 6]);
+    }
+
+    #[test]
+    fn steep_step_is_carried_into_later_rows() {
+        let counts = [6, 12, 24, 24, 24, 24, 24];
+        let capped = cap_increases(&counts, 2);
+        assert_eq!(capped.len(), counts.len());
+        assert_eq!(capped[0], 6);
+        assert_eq!(capped.last(), Some(&24));
+        for pair in capped.windows(2) {
+            assert!(pair[1] - pair[0] <= (pair[0] / 2).max(1), "{:?}", capped);
+        }
+    }
+
+    #[test]
+    fn stretch_that_never_catches_up_stays_capped() {
+        let capped = cap_increases(&[4, 40], 2);
+        assert_eq!(capped, [4, 6]);
+    }
+
+    #[test]
+    fn smoothing_nothing_or_one_count_keeps_it() {
+        assert!(smooth(&[]).is_empty());
+        assert_eq!(smooth(&[30]), [30]);
+    }
+
+    #[test]
+    fn smoothing_equal_counts_keeps_them() {
+        assert_eq!(smooth(&[30, 30, 30]), [30, 30, 30]);
+    }
+
+    #[test]
+    fn one_stitch_wobbles_are_flattened() {
+        assert_eq!(smooth(&[30, 31, 30, 32]), [30, 30, 30, 32]);
+        assert_eq!(smooth(&[30, 29, 30, 31, 30, 30]), [30, 30, 30, 30, 30, 30]);
+    }
+
+    #[test]
+    fn smoothing_keeps_the_ends_and_real_shaping() {
+        assert_eq!(smooth(&[30, 31]), [30, 31]);
+        assert_eq!(smooth(&[6, 12, 18, 12, 6]), [6, 12, 18, 12, 6]);
+        assert_eq!(smooth(&[30, 31, 32, 33]), [30, 31, 32, 33]);
+    }
+}
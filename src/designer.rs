@@ -0,0 +1,82 @@
+use crate::generator::GeneratorMessage;
+use crate::numbering::Numbering;
+use crate::validate::{count_label, Field, Validation};
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+// Reads the stitch counts typed into the designer table, checking they make a workable pattern.
+// Rows are named as `numbering` names them on a piece worked `flat` or not.
+pub fn parse_counts(texts: &[String], numbering: Numbering, flat: bool) -> (Vec<i32>, Validation) {
+    let mut validation = Validation::default();
+    let mut counts = Vec::new();
+    for (i, text) in texts.iter().enumerate() {
+        match text.trim().parse::<i32>() {
+            Ok(count) => counts.push(count),
+            Err(_) => validation.error(
+                Field::Pattern,
+                format!(
+                    "{}: \"{}\" isn't a whole number of stitches",
+                    count_label(numbering, i + 1, flat),
+                    text.trim()
+                ),
+            ),
+        }
+    }
+    if !validation.has_errors() {
+        validation.check_counts(&counts, numbering, flat);
+    }
+    (counts, validation)
+}
+
+// Every row's stitch count as an editable table. Changes regenerate the instructions from the counts.
+pub fn designer_table(
+    texts: &[String],
+    numbering: Numbering,
+    flat: bool,
+    on_message: &Callback<GeneratorMessage>,
+) -> Html {
+    let rows = texts.iter().enumerate().map(|(i, text)| {
+        let on_input = on_message.reform(move |e: InputEvent| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            GeneratorMessage::SetCount(i, input_el.value())
+        });
+        let change = match (i.checked_sub(1).and_then(|p| texts.get(p)), text) {
+            (Some(previous), text) => {
+                match (previous.trim().parse::<i32>(), text.trim().parse::<i32>()) {
+                    (Ok(previous), Ok(count)) if count != previous => {
+                        format!("{:+}", count - previous)
+                    }
+                    _ => String::new(),
+                }
+            }
+            (None, _) => String::new(),
+        };
+        html! {
+            <tr>
+                <td>{numbering.shown(i + 1).map_or(String::new(), |shown| shown.to_string())}</td>
+                <td><input aria-label={format!("Stitches in {}", count_label(numbering, i + 1, flat).to_lowercase())} type="number" min="1" value={text.clone()} oninput={on_input}/></td>
+                <td>{change}</td>
+            </tr>
+        }
+    });
+
+    html! {
+        <div class="designer">
+            <table class="stitch-table">
+                <thead>
+                    <tr>
+                        <th>{numbering.word(flat)}</th>
+                        <th>{"Stitches"}</th>
+                        <th>{"Change"}</th>
+                    </tr>
+                </thead>
+                <tbody>{ for rows }</tbody>
+            </table>
+            <p>
+                <button type="button" onclick={on_message.reform(|_| GeneratorMessage::AddRow)}>{"Add row"}</button>
+                <button type="button" onclick={on_message.reform(|_| GeneratorMessage::RemoveRow)} disabled={texts.len() <= 1}>{"Remove last row"}</button>
+                <button type="button" onclick={on_message.reform(|_| GeneratorMessage::StopDesigning)}>{"Back to the generated counts"}</button>
+            </p>
+        </div>
+    }
+}
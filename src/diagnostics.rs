@@ -0,0 +1,137 @@
+use crate::locale::Locale;
+use crate::pattern::{Pattern, Shaping};
+use yew::prelude::*;
+
+// One shaping row of a shape worked out from its geometry: how far round from the pole it is, its
+// radius and length around in the form's units, and its stitches before and after rounding.
+#[derive(Clone, Copy, PartialEq)]
+pub struct Sample {
+    // In radians.
+    pub angle: f64,
+    pub radius: f64,
+    pub circumference: f64,
+    pub raw: f64,
+    pub rounded: i32,
+}
+
+// How a shaping row divides its stitches, and how many stitches that comes to, to check against
+// the row's count. None for rows that aren't worked in blocks.
+fn block_math(shaping: &Shaping) -> Option<(String, i32)> {
+    match *shaping {
+        Shaping::Spaced {
+            inc,
+            before,
+            block,
+            repeats,
+            after,
+        } => Some((
+            format!(
+                "{} + inc + {} × ({} + inc) + {}, {} inc",
+                before, repeats, block, after, inc
+            ),
+            before + 1 + repeats * (block + 1) + after,
+        )),
+        Shaping::Decrease { dec, block } => {
+            Some((format!("{} × ({} + k2tog)", dec, block), dec * (block + 1)))
+        }
+        Shaping::DecreaseSpaced { dec, block, after } => Some((
+            format!("{} × ({} + k2tog) + {}", dec, block, after),
+            dec * (block + 1) + after,
+        )),
+        _ => None,
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct DiagnosticsProps {
+    pub pattern: Pattern,
+    pub profile: Vec<Sample>,
+    pub unit_label: String,
+    pub locale: Locale,
+}
+
+// What the generator worked out on the way to the pattern, for tracking down an instruction that
+// looks wrong: each shaping row's geometry before rounding, and how each row's stitches are split
+// into blocks. Only worked out while the panel is open.
+#[function_component(DiagnosticsPanel)]
+pub fn diagnostics_panel(props: &DiagnosticsProps) -> Html {
+    let open = use_state(|| false);
+    let on_toggle = {
+        let open = open.clone();
+        Callback::from(move |_: Event| open.set(!*open))
+    };
+    let body = match *open {
+        false => html! {},
+        true => {
+            let (locale, units) = (props.locale, &props.unit_label);
+            let profile = props.profile.iter().enumerate().map(|(i, sample)| {
+                html! {
+                    <tr>
+                        <td>{i + 1}</td>
+                        <td>{format!("{}°", locale.decimal(sample.angle.to_degrees(), 1))}</td>
+                        <td>{locale.decimal(sample.radius, 2)}</td>
+                        <td>{locale.decimal(sample.circumference, 2)}</td>
+                        <td>{locale.decimal(sample.raw, 2)}</td>
+                        <td>{sample.rounded}</td>
+                    </tr>
+                }
+            });
+            let pattern = &props.pattern;
+            let rows = pattern.rows.iter().enumerate().map(|(i, row)| {
+                let below = i.checked_sub(1).map(|b| pattern.rows[b].stitches);
+                let change =
+                    below.map_or(String::new(), |below| format!("{:+}", row.stitches - below));
+                let (math, check) = match block_math(&row.shaping) {
+                    Some((math, made)) if made == row.stitches => (math, String::from("✓")),
+                    Some((math, made)) => (math, format!("✗ {}", made)),
+                    None => (String::new(), String::new()),
+                };
+                html! {
+                    <tr>
+                        <td>{pattern.label(i)}</td>
+                        <td>{row.stitches}</td>
+                        <td>{change}</td>
+                        <td>{math}</td>
+                        <td>{check}</td>
+                    </tr>
+                }
+            });
+            html! {
+                <>
+                    if !props.profile.is_empty() {
+                        <h4>{"Geometry of the shaping rows"}</h4>
+                        <table class="diagnostics">
+                            <tr>
+                                <th>{"#"}</th>
+                                <th>{"Angle from pole"}</th>
+                                <th>{format!("Radius ({})", units)}</th>
+                                <th>{format!("Circumference ({})", units)}</th>
+                                <th>{"Raw stitches"}</th>
+                                <th>{"Rounded"}</th>
+                            </tr>
+                            { for profile }
+                        </table>
+                    }
+                    <h4>{"Rows"}</h4>
+                    <table class="diagnostics">
+                        <tr>
+                            <th>{"Row"}</th>
+                            <th>{"Stitches"}</th>
+                            <th>{"Change"}</th>
+                            <th>{"Blocks"}</th>
+                            <th>{"Adds up"}</th>
+                        </tr>
+                        { for rows }
+                    </table>
+                </>
+            }
+        }
+    };
+
+    html! {
+        <details ontoggle={on_toggle}>
+            <summary>{"Generation internals, for debugging"}</summary>
+            { body }
+        </details>
+    }
+}
@@ -0,0 +1,90 @@
+use crate::pattern::{Pattern, Row};
+use std::iter::zip;
+use yew::prelude::*;
+
+// Compares a pinned pattern with the current one row by row, highlighting the rows whose
+// stitch counts or shaping differ.
+pub fn pattern_diff(pinned: &Pattern, current: &Pattern) -> Html {
+    let length = pinned.rows.len().max(current.rows.len());
+    let changed = (0..length)
+        .filter(|i| pinned.rows.get(*i) != current.rows.get(*i))
+        .count();
+    let rows = (0..length).map(|i| {
+        let (before, after) = (pinned.rows.get(i), current.rows.get(i));
+        let class = if before == after { "" } else { "changed-row" };
+        let stitches =
+            |row: Option<&Row>| row.map_or(String::from("—"), |r| r.stitches.to_string());
+        let change = match (before, after) {
+            (Some(b), Some(a)) if a.stitches != b.stitches => {
+                format!("{:+}", a.stitches - b.stitches)
+            }
+            _ => String::new(),
+        };
+        html! {
+            <tr {class}>
+                <td>{current.numbering.shown(i + 1)}</td>
+                <td>{stitches(before)}</td>
+                <td>{stitches(after)}</td>
+                <td>{change}</td>
+                <td>{match after {
+                    Some(_) => current.instruction(i),
+                    None => pinned.instruction(i),
+                }}</td>
+            </tr>
+        }
+    });
+
+    html! {
+        <div class="pattern-diff">
+            <p>{format!(
+                "Pinned: {} rows, {} stitches. Current: {} rows, {} stitches. {} rows differ.",
+                pinned.rows.len(), pinned.total_stitches(),
+                current.rows.len(), current.total_stitches(),
+                changed
+            )}</p>
+            <table class="stitch-table">
+                <thead>
+                    <tr>
+                        <th>{current.word()}</th>
+                        <th>{"Pinned"}</th>
+                        <th>{"Current"}</th>
+                        <th>{"Change"}</th>
+                        <th>{"Current instruction"}</th>
+                    </tr>
+                </thead>
+                <tbody>{ for rows }</tbody>
+            </table>
+        </div>
+    }
+}
+
+// What changed between two versions of a pattern, such as "rows 24→28", for a changelog. Empty
+// when they are the same row for row.
+pub fn changelog(before: &Pattern, after: &Pattern) -> Vec<String> {
+    let stats = |pattern: &Pattern| {
+        [
+            pattern.rows.len() as i64,
+            pattern.rows.first().map_or(0, |row| row.stitches) as i64,
+            pattern
+                .rows
+                .iter()
+                .map(|row| row.stitches)
+                .max()
+                .unwrap_or(0) as i64,
+            pattern.total_stitches(),
+        ]
+    };
+    let names = ["rows", "cast on", "most stitches", "stitches worked"];
+    let mut changes: Vec<String> = zip(names, zip(stats(before), stats(after)))
+        .filter(|(_, (b, a))| b != a)
+        .map(|(name, (b, a))| format!("{} {}→{}", name, b, a))
+        .collect();
+    let length = before.rows.len().max(after.rows.len());
+    let differ = (0..length)
+        .filter(|i| before.rows.get(*i) != after.rows.get(*i))
+        .count();
+    if differ > 0 {
+        changes.push(format!("{} of {} rows rewritten", differ, length));
+    }
+    changes
+}
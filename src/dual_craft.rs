@@ -0,0 +1,186 @@
+use crate::export;
+use crate::form::issue_message;
+use crate::gauge::{Fabric, Gauge};
+use crate::params::Params;
+use crate::pattern_view::striped_pattern;
+use crate::shapes::{Design, Shape};
+use crate::validate::validate_gauge;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct DualCraftProps {
+    pub shape: Shape,
+    pub params: Params,
+    pub design: Design,
+    pub gauge: Gauge,
+}
+
+fn craft(fabric: Fabric) -> &'static str {
+    match fabric.crochet() {
+        Some(_) => "Crochet",
+        None => "Knit",
+    }
+}
+
+// The form's params for the same shape and size in `fabric` at another gauge. The row gauge is
+// estimated from the stitch gauge when left blank.
+fn other_craft(params: &Params, fabric: Fabric, stitches: &str, rows: &str) -> Params {
+    Params {
+        fabric,
+        stitches_per_unit: stitches.to_string(),
+        rows_per_unit: rows.to_string(),
+        estimate_rows: rows.trim().is_empty(),
+        ..params.clone()
+    }
+}
+
+// Both versions as one plain text document, the form's first.
+fn dual_text(shape: Shape, versions: &[(&Design, &Params, Gauge)]) -> String {
+    versions
+        .iter()
+        .map(|(design, params, gauge)| {
+            let heading = format!("{} VERSION", craft(params.fabric).to_uppercase());
+            let text = export::pattern_text(shape, design, params, *gauge);
+            format!("{}\n{}\n\n{}", heading, "=".repeat(heading.len()), text)
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn rows_column(design: &Design, params: &Params, gauge: Gauge) -> Html {
+    let pattern = striped_pattern(design, params);
+    html! {
+        <div>
+            <h4>{format!("{} version", craft(params.fabric))}</h4>
+            <p class="field-note">{format!(
+                "{}: {} stitches and {} {}s per {}",
+                params.fabric.name(),
+                params.locale.decimal(gauge.stitches_per_unit, 1),
+                params.locale.decimal(gauge.rows_per_unit, 1),
+                pattern.word().to_lowercase(),
+                &params.units
+            )}</p>
+            <ul>{ for (0..pattern.rows.len()).map(|i| html! {<li>{pattern.written_row(i)}</li>}) }</ul>
+        </div>
+    }
+}
+
+// The same shape and size worked in the other craft, knitted beside crocheted, for designers who
+// publish both. Both come from the same shape's geometry, each at its own craft's gauge.
+#[function_component(DualCraft)]
+pub fn dual_craft(props: &DualCraftProps) -> Html {
+    let (shape, params) = (props.shape, &props.params);
+    let crochet = params.fabric.crochet().is_some();
+    let choices: Vec<Fabric> = Fabric::ALL
+        .into_iter()
+        .filter(|fabric| fabric.crochet().is_some() != crochet)
+        .collect();
+    let fabric = use_state(|| choices[0]);
+    let stitches = use_state(String::new);
+    let rows = use_state(String::new);
+    let on_input = |state: &UseStateHandle<String>| {
+        let state = state.clone();
+        Callback::from(move |e: InputEvent| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            state.set(input_el.value());
+        })
+    };
+    let on_fabric = {
+        let fabric = fabric.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            if let Some(chosen) = Fabric::from_key(&select.value()) {
+                fabric.set(chosen);
+            }
+        })
+    };
+    // The form's craft may have changed since a fabric was picked:
+    let other_fabric = match choices.contains(&fabric) {
+        true => *fabric,
+        false => choices[0],
+    };
+
+    // Until the other gauge is entered, it is estimated from the form's for the same yarn:
+    let estimate = props.gauge.convert(params.fabric, other_fabric);
+    let estimated = params.locale.decimal(estimate.stitches_per_unit, 1);
+    let other_stitches = match stitches.trim() {
+        "" => estimated.as_str(),
+        stitches => stitches,
+    };
+    let other = other_craft(params, other_fabric, other_stitches, &rows);
+    let mut validation = validate_gauge(
+        other.stitches_per_unit().ok(),
+        other.rows_per_unit().ok(),
+        &other.units,
+        other.locale,
+    );
+    let other_gauge = other.gauge().filter(|_| !validation.has_errors());
+    if let Some(gauge) = other_gauge {
+        validation.merge(shape.validate(&other, Some(gauge)));
+    }
+    let other_design = other_gauge
+        .filter(|_| !validation.has_errors())
+        .and_then(|gauge| Some((shape.design(&other, gauge)?, gauge)));
+
+    let estimate_note = stitches.trim().is_empty().then(|| {
+        format!(
+            "The {} gauge is estimated from the {} gauge for the same yarn. Swatch to confirm it.",
+            craft(other_fabric).to_lowercase(),
+            craft(params.fabric).to_lowercase()
+        )
+    });
+    let body = match &other_design {
+        None if validation.issues.is_empty() => html! {
+            <p class="field-error">{"That gauge isn't a number."}</p>
+        },
+        None => html! {
+            <p>{ for validation.issues.iter().map(issue_message) }</p>
+        },
+        Some((design, gauge)) => {
+            let on_download = {
+                let (this, that) = (props.design.clone(), design.clone());
+                let (this_params, that_params) = (params.clone(), other.clone());
+                let (this_gauge, that_gauge) = (props.gauge, *gauge);
+                Callback::from(move |_: MouseEvent| {
+                    let text = dual_text(
+                        shape,
+                        &[
+                            (&this, &this_params, this_gauge),
+                            (&that, &that_params, that_gauge),
+                        ],
+                    );
+                    export::download("pattern-knit-and-crochet.txt", "text/plain", &text);
+                })
+            };
+            html! {
+                <>
+                    <div class="dual-craft">
+                        { rows_column(&props.design, params, props.gauge) }
+                        { rows_column(design, &other, *gauge) }
+                    </div>
+                    <button type="button" onclick={on_download}>{"Download both as text"}</button>
+                </>
+            }
+        }
+    };
+
+    html! {
+        <div>
+            <span>
+                <label>{format!("{} in: ", craft(other_fabric))}</label>
+                <select aria-label="Other craft's fabric" onchange={on_fabric}>
+                    { for choices.iter().map(|choice| html! {
+                        <option value={choice.key()} selected={*choice == other_fabric}>{choice.name()}</option>
+                    }) }
+                </select>
+                <label>{format!(" stitches per {}: ", &params.units)}</label>
+                <input aria-label={format!("Other craft, stitches per {}", &params.units)} type="text" size="4" placeholder={estimated.clone()} value={(*stitches).clone()} oninput={on_input(&stitches)}/>
+                <label>{" rows: "}</label>
+                <input aria-label={format!("Other craft, rows per {}", &params.units)} type="text" size="4" placeholder="Estimate" value={(*rows).clone()} oninput={on_input(&rows)}/>
+            </span>
+            { for estimate_note.map(|note| html! {<p class="field-note">{note}</p>}) }
+            {body}
+        </div>
+    }
+}
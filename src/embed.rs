@@ -0,0 +1,79 @@
+use std::cell::Cell;
+use web_sys::js_sys::{Object, Reflect};
+use web_sys::wasm_bindgen::closure::Closure;
+use web_sys::wasm_bindgen::{JsCast, JsValue};
+use web_sys::{Element, ResizeObserver};
+use yew::prelude::*;
+
+// The `type` of the messages sent to the page embedding the app.
+const HEIGHT_MESSAGE: &str = "knitting-pattern-generator:height";
+
+// Whether the app was opened to embed in another site, with "embed" in the page's own query
+// (before the "#"), as "?embed#/hat".
+pub fn requested() -> bool {
+    let search = web_sys::window().and_then(|window| window.location().search().ok());
+    search.is_some_and(|search| {
+        search
+            .trim_start_matches('?')
+            .split('&')
+            .any(|param| param == "embed" || param.starts_with("embed="))
+    })
+}
+
+// The same page outside the frame, for a link to the full app.
+pub fn full_app_url() -> Option<String> {
+    let location = web_sys::window()?.location();
+    let (origin, path, hash) = (
+        location.origin().ok()?,
+        location.pathname().ok()?,
+        location.hash().ok()?,
+    );
+    Some(format!("{}{}{}", origin, path, hash))
+}
+
+// The element holding the whole app, whose height is the app's own however tall the frame is.
+fn app_root() -> Option<Element> {
+    web_sys::window()?.document()?.query_selector("main").ok()?
+}
+
+fn post_height(height: f64) -> Option<()> {
+    let parent = web_sys::window()?.parent().ok()??;
+    let message = Object::new();
+    let _ = Reflect::set(&message, &"type".into(), &HEIGHT_MESSAGE.into());
+    let _ = Reflect::set(&message, &"height".into(), &JsValue::from_f64(height));
+    parent.post_message(&message, "*").ok()
+}
+
+// Tells the embedding page how tall the app is whenever that changes, so it can size its iframe to
+// fit without a scroll bar of its own. The page listens for messages like
+// `{type: "knitting-pattern-generator:height", height: 1234}`.
+#[function_component(HeightReporter)]
+pub fn height_reporter() -> Html {
+    use_effect_with_deps(
+        |_| {
+            let reported = Cell::new(0.0);
+            let on_resize = Closure::<dyn Fn()>::new(move || {
+                let height = app_root().map(|root| root.get_bounding_client_rect().height().ceil());
+                let Some(height) = height.filter(|height| *height != reported.get()) else {
+                    return;
+                };
+                if post_height(height).is_some() {
+                    reported.set(height);
+                }
+            });
+            // Observing starts with a first report, then one whenever the app's size changes:
+            let observer = ResizeObserver::new(on_resize.as_ref().unchecked_ref()).ok();
+            if let (Some(observer), Some(root)) = (&observer, app_root()) {
+                observer.observe(&root);
+            }
+            move || {
+                if let Some(observer) = observer {
+                    observer.disconnect();
+                }
+                drop(on_resize);
+            }
+        },
+        (),
+    );
+    html! {}
+}
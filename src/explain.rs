@@ -0,0 +1,103 @@
+use crate::gauge::Gauge;
+use crate::locale::Locale;
+use crate::pattern::{Pattern, Shaping};
+
+// A short reason for the shaping in row `index`, worked out from the pattern's numbers, for
+// knitters learning how a shape comes about.
+pub fn explain_row(
+    pattern: &Pattern,
+    index: usize,
+    gauge: Gauge,
+    unit_label: &str,
+    locale: Locale,
+) -> Option<String> {
+    let row = &pattern.rows[index];
+    let width = |stitches: i32| f64::from(stitches) / gauge.stitches_per_unit;
+    if let Shaping::CastOn = row.shaping {
+        let flat = pattern.is_flat();
+        return Some(format!(
+            "{} stitches at {} st/{} make the first {} {} {} {}.",
+            row.stitches,
+            locale.number(gauge.stitches_per_unit),
+            unit_label,
+            if flat { "row" } else { "round" },
+            locale.decimal(width(row.stitches), 1),
+            unit_label,
+            if flat { "across" } else { "around" }
+        ));
+    }
+    let previous = &pattern.rows[index.checked_sub(1)?];
+    let change = row.stitches - previous.stitches;
+    if change == 0 {
+        return None;
+    }
+    // Rows worked since the last change in stitch count, which this shaping spreads across; they
+    // count from the row that made the previous count, or from the cast-on:
+    let run_start = pattern.rows[..index]
+        .iter()
+        .rposition(|r| r.stitches != previous.stitches)
+        .map_or(0, |before| before + 1);
+    let rows_since = row.number - pattern.rows[run_start].number;
+    let height = rows_since as f64 / gauge.rows_per_unit;
+    let (verb, direction) = if change > 0 {
+        ("increase", "grows")
+    } else {
+        ("decrease", "shrinks")
+    };
+    Some(format!(
+        "We {} {} stitches here because the circumference {} by about {} {} over the last {} {} of height ({} to {} {}).",
+        verb,
+        change.abs(),
+        direction,
+        locale.decimal(width(change.abs()), 1),
+        unit_label,
+        locale.decimal(height, 1),
+        unit_label,
+        if change > 0 { "up" } else { "down" },
+        locale.decimal(width(row.stitches), 1),
+        unit_label
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pattern::Distribution;
+
+    const GAUGE: Gauge = Gauge {
+        stitches_per_unit: 2.0,
+        rows_per_unit: 5.0,
+    };
+
+    fn explain(counts: &[i32], index: usize) -> Option<String> {
+        let pattern = Pattern::from_counts(counts, 0, Distribution::default());
+        explain_row(&pattern, index, GAUGE, "cm", Locale::default())
+    }
+
+    #[test]
+    fn plain_row_after_a_change_has_no_reason() {
+        assert_eq!(explain(&[6, 12, 12], 2), None);
+    }
+
+    #[test]
+    fn change_is_spread_over_the_rows_since_the_last_one() {
+        let reason = explain(&[6, 12, 12, 12, 18], 4).unwrap();
+        assert!(reason.starts_with("We increase 6 stitches"), "{}", reason);
+        assert!(reason.contains("over the last 0.6 cm"), "{}", reason);
+    }
+
+    #[test]
+    fn shaping_every_other_row_is_spread_over_two_rows() {
+        let counts = [6, 6, 12, 12, 18, 18, 24];
+        for index in [2, 4, 6] {
+            let reason = explain(&counts, index).unwrap();
+            assert!(reason.contains("over the last 0.4 cm"), "{}", reason);
+        }
+    }
+
+    #[test]
+    fn first_change_counts_from_the_cast_on() {
+        let reason = explain(&[6, 6, 6, 12], 3).unwrap();
+        assert!(reason.contains("over the last 0.6 cm"), "{}", reason);
+    }
+}
@@ -0,0 +1,128 @@
+use crate::gauge::Gauge;
+use crate::pairs;
+use crate::params::Params;
+use crate::pattern_view::{striped_pattern, Materials};
+use crate::shapes::{Design, Shape};
+use crate::skill;
+use crate::split;
+use crate::storage;
+use crate::timing;
+use crate::validate::Validation;
+use web_sys::js_sys::encode_uri_component;
+use web_sys::wasm_bindgen::JsCast;
+use web_sys::HtmlElement;
+
+// The whole pattern as plain text, including any notes added to its rows.
+pub fn pattern_text(shape: Shape, design: &Design, params: &Params, gauge: Gauge) -> String {
+    let pattern = striped_pattern(design, params);
+    let materials = Materials::new(design, &pattern, params, gauge);
+    let notes = storage::load_notes(&pattern.id());
+    let heading = params.metadata.heading();
+    let mut lines: Vec<String> = heading.iter().flatten().cloned().collect();
+    if heading.is_some() {
+        lines.push(String::new());
+    }
+    lines.push(shape.summary(params));
+    lines.push(skill::describe(&pattern));
+    lines.push(timing::summary(&pattern, params.fabric, params.locale));
+    // Anything the generator warned about, so it travels with the pattern:
+    let mut checks = Validation::default();
+    checks.check_shaping(&pattern, &params.units);
+    lines.extend(
+        checks
+            .issues
+            .iter()
+            .map(|issue| format!("Note: {}", issue.message)),
+    );
+    lines.extend([
+        String::new(),
+        String::from("MATERIALS"),
+        format!("- {}", materials.yarn),
+    ]);
+    lines.extend(materials.set.iter().map(|line| format!("- {}", line)));
+    lines.extend(
+        materials
+            .held_double
+            .iter()
+            .map(|line| format!("- {}", line)),
+    );
+    lines.extend(
+        materials
+            .by_color
+            .iter()
+            .map(|line| format!("  - {}", line)),
+    );
+    lines.extend(materials.cost.iter().map(|line| format!("- {}", line)));
+    lines.extend(materials.needles.iter().map(|line| format!("- {}", line)));
+    lines.push(String::new());
+    lines.push(String::from("ABBREVIATIONS"));
+    lines.extend(
+        pattern
+            .abbreviations
+            .glossary(&pattern)
+            .iter()
+            .map(|line| format!("- {}", line)),
+    );
+    lines.push(String::new());
+    lines.push(String::from("INSTRUCTIONS"));
+    if pattern.two_at_a_time {
+        lines.extend(
+            pairs::setup(&pattern)
+                .iter()
+                .map(|line| format!("- {}", line)),
+        );
+    }
+    let chunks = split::chunks(&pattern, params.knitters());
+    for (i, row) in pattern.rows.iter().enumerate() {
+        // Each knitter's section starts under its own heading:
+        if let Some(k) = chunks
+            .iter()
+            .position(|chunk| chunk.start == i && chunks.len() > 1)
+        {
+            lines.push(String::new());
+            lines.push(split::header(&pattern, &chunks, k + 1).to_uppercase());
+        }
+        lines.push(params.tally.text(i, pattern.rows.len()) + &pattern.written_row(i));
+        if let Some(note) = notes.get(&row.number) {
+            lines.push(format!("    Note: {}", note));
+        }
+    }
+    lines.push(String::new());
+    lines.push(String::from("FINISHING"));
+    if chunks.len() > 1 {
+        lines.push(format!("- {}", split::joining(&pattern)));
+    }
+    lines.extend(design.finishing.iter().map(|note| format!("- {}", note)));
+    lines.extend(materials.stuffing.iter().map(|line| format!("- {}", line)));
+    // The terms again at the end, where a reader who printed only part of it will still find them.
+    if let Some(terms) = heading.and_then(|heading| heading.last().cloned()) {
+        lines.push(String::new());
+        lines.push(terms);
+    }
+    lines.join("\n") + "\n"
+}
+
+// Saves `contents` as a file through the browser's usual download.
+pub fn download(filename: &str, mime: &str, contents: &str) {
+    let href = format!(
+        "data:{};charset=utf-8,{}",
+        mime,
+        encode_uri_component(contents)
+    );
+    download_url(filename, &href);
+}
+
+// Saves whatever `href` points to, such as a data URL, as `filename`.
+pub fn download_url(filename: &str, href: &str) {
+    let Some(document) = web_sys::window().and_then(|w| w.document()) else {
+        return;
+    };
+    let Ok(link) = document.create_element("a") else {
+        return;
+    };
+    let _ = link.set_attribute("href", href);
+    let _ = link.set_attribute("download", filename);
+    if let Ok(link) = link.dyn_into::<HtmlElement>() {
+        link.click();
+    }
+}
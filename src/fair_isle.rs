@@ -0,0 +1,209 @@
+use crate::colorwork::{chart_view, colorwork_instructions, Chart};
+use crate::form::parse_count;
+use crate::palette::Palette;
+use crate::pattern::Pattern;
+use gloo_events::EventListener;
+use std::iter::zip;
+use std::rc::Rc;
+use web_sys::wasm_bindgen::JsCast;
+use web_sys::{
+    CanvasRenderingContext2d, HtmlCanvasElement, HtmlImageElement, HtmlInputElement, Url,
+};
+use yew::prelude::*;
+
+// Pictures are shrunk to at most this many pixels across and down before charting; a knitted
+// chart has far fewer stitches than a photo has pixels.
+const MAX_PIXELS: u32 = 64;
+const MAX_COLORS: usize = 6;
+// Rounds of refining the palette.
+const ITERATIONS: usize = 8;
+
+// A decoded picture, as RGB pixels from the top left, row by row.
+#[derive(PartialEq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<[u8; 3]>,
+}
+
+fn distance(a: [u8; 3], b: [u8; 3]) -> u32 {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| (i32::from(*x) - i32::from(y)).pow(2) as u32)
+        .sum()
+}
+
+fn nearest(palette: &[[u8; 3]], pixel: [u8; 3]) -> usize {
+    (0..palette.len())
+        .min_by_key(|&i| distance(palette[i], pixel))
+        .unwrap_or(0)
+}
+
+// Picks `colors` colors that best represent the picture (k-means, starting from pixels spread
+// from darkest to lightest), with the color of each pixel. The palette is ordered by how often
+// each color is used, so the main color is the commonest.
+fn quantize(image: &Image, colors: usize) -> (Vec<[u8; 3]>, Vec<usize>) {
+    let mut by_brightness = image.pixels.clone();
+    by_brightness.sort_by_key(|p| p.iter().map(|c| u32::from(*c)).sum::<u32>());
+    let mut palette: Vec<[u8; 3]> = (0..colors)
+        .map(|i| by_brightness[(2 * i + 1) * by_brightness.len() / (2 * colors)])
+        .collect();
+    let mut indices = Vec::new();
+    for _ in 0..ITERATIONS {
+        indices = image.pixels.iter().map(|p| nearest(&palette, *p)).collect();
+        for (color, entry) in palette.iter_mut().enumerate() {
+            let members: Vec<&[u8; 3]> = zip(&image.pixels, &indices)
+                .filter(|(_, i)| **i == color)
+                .map(|(p, _)| p)
+                .collect();
+            if members.is_empty() {
+                continue;
+            }
+            for channel in 0..3 {
+                let total: usize = members.iter().map(|p| usize::from(p[channel])).sum();
+                entry[channel] = (total / members.len()) as u8;
+            }
+        }
+    }
+    let mut order: Vec<usize> = (0..colors).collect();
+    order.sort_by_key(|&color| std::cmp::Reverse(indices.iter().filter(|i| **i == color).count()));
+    let palette = order.iter().map(|&color| palette[color]).collect();
+    let indices = indices
+        .iter()
+        .map(|i| order.iter().position(|color| color == i).unwrap_or(0))
+        .collect();
+    (palette, indices)
+}
+
+// Wraps the picture around the pattern: each row takes a line of the picture from the bottom up,
+// and each stitch samples that line at its share of the way around, so rows with fewer stitches
+// near the poles take the picture more coarsely.
+fn map_onto(pattern: &Pattern, image: &Image, colors: usize) -> Chart {
+    let (palette, indices) = quantize(image, colors);
+    let total = pattern.rows.len();
+    let rows = pattern
+        .rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let y = image.height - 1 - i * image.height / total;
+            let stitches = row.stitches.max(0) as usize;
+            (0..stitches)
+                .map(|j| indices[y * image.width + j * image.width / stitches])
+                .collect()
+        })
+        .collect();
+    Chart {
+        rows,
+        palette: palette
+            .iter()
+            .map(|[r, g, b]| format!("#{:02x}{:02x}{:02x}", r, g, b))
+            .collect(),
+    }
+}
+
+// Draws a loaded picture small on a canvas to read its pixels back.
+fn decode(img: &HtmlImageElement) -> Option<Image> {
+    let scale = f64::from(MAX_PIXELS) / f64::from(img.natural_width().max(img.natural_height()));
+    let width = ((f64::from(img.natural_width()) * scale.min(1.0)).round() as u32).max(1);
+    let height = ((f64::from(img.natural_height()) * scale.min(1.0)).round() as u32).max(1);
+    let document = web_sys::window().and_then(|w| w.document())?;
+    let canvas: HtmlCanvasElement = document.create_element("canvas").ok()?.dyn_into().ok()?;
+    canvas.set_width(width);
+    canvas.set_height(height);
+    let context: CanvasRenderingContext2d = canvas.get_context("2d").ok()??.dyn_into().ok()?;
+    context
+        .draw_image_with_html_image_element_and_dw_and_dh(
+            img,
+            0.0,
+            0.0,
+            width.into(),
+            height.into(),
+        )
+        .ok()?;
+    let data = context
+        .get_image_data(0, 0, width as i32, height as i32)
+        .ok()?
+        .data()
+        .0;
+    Some(Image {
+        width: width as usize,
+        height: height as usize,
+        pixels: data.chunks(4).map(|p| [p[0], p[1], p[2]]).collect(),
+    })
+}
+
+#[derive(Properties, PartialEq)]
+pub struct FairIsleProps {
+    pub pattern: Pattern,
+    pub palette: Palette,
+}
+
+// Turns an uploaded picture into a Fair Isle chart for the pattern.
+#[function_component(FairIsle)]
+pub fn fair_isle(props: &FairIsleProps) -> Html {
+    let image = use_state(|| None::<Rc<Image>>);
+    let colors = use_state(|| String::from("2"));
+    let failed = use_state(|| false);
+
+    let on_file_change = {
+        let (image, failed) = (image.clone(), failed.clone());
+        Callback::from(move |e: Event| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            let Some(file) = input_el.files().and_then(|files| files.get(0)) else {
+                return;
+            };
+            let (Ok(url), Ok(img)) = (
+                Url::create_object_url_with_blob(&file),
+                HtmlImageElement::new(),
+            ) else {
+                failed.set(true);
+                return;
+            };
+            let (image, on_error) = (image.clone(), failed.clone());
+            let (failed, loaded) = (failed.clone(), img.clone());
+            let on_load = move || {
+                let _ = Url::revoke_object_url(&loaded.src());
+                let decoded = decode(&loaded);
+                failed.set(decoded.is_none());
+                image.set(decoded.map(Rc::new));
+            };
+            EventListener::once(&img, "load", move |_| on_load()).forget();
+            EventListener::once(&img, "error", move |_| on_error.set(true)).forget();
+            img.set_src(&url);
+        })
+    };
+    let on_colors_input = {
+        let colors = colors.clone();
+        Callback::from(move |e: InputEvent| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            colors.set(input_el.value());
+        })
+    };
+    let count = parse_count(&colors).unwrap_or(2).clamp(2, MAX_COLORS);
+    let chart = image
+        .as_ref()
+        .filter(|_| !props.pattern.rows.is_empty())
+        .map(|image| map_onto(&props.pattern, image, count.min(image.pixels.len())));
+
+    html! {
+        <div class="fair-isle">
+            <p>
+                <label>{"Picture: "}</label>
+                <input aria-label="Picture" type="file" accept="image/*" onchange={on_file_change}/>
+                <label>{" Colors: "}</label>
+                <input aria-label="Colors" type="number" min="2" max={MAX_COLORS.to_string()} value={(*colors).clone()} oninput={on_colors_input}/>
+            </p>
+            if *failed {
+                <p class="field-error">{"That picture couldn't be read."}</p>
+            }
+            if let Some(chart) = chart {
+                { chart_view(&chart, &props.pattern, &props.palette) }
+                <details>
+                    <summary>{"Colorwork row by row"}</summary>
+                    { colorwork_instructions(&props.pattern, &chart) }
+                </details>
+            }
+        </div>
+    }
+}
@@ -0,0 +1,108 @@
+use crate::locale::Locale;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Fiber {
+    Wool,
+    SuperwashWool,
+    Cotton,
+    Acrylic,
+    Alpaca,
+}
+
+impl Fiber {
+    pub const ALL: [Fiber; 5] = [
+        Fiber::Wool,
+        Fiber::SuperwashWool,
+        Fiber::Cotton,
+        Fiber::Acrylic,
+        Fiber::Alpaca,
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Fiber::Wool => "wool",
+            Fiber::SuperwashWool => "superwash",
+            Fiber::Cotton => "cotton",
+            Fiber::Acrylic => "acrylic",
+            Fiber::Alpaca => "alpaca",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Fiber::Wool => "Wool",
+            Fiber::SuperwashWool => "Superwash wool",
+            Fiber::Cotton => "Cotton",
+            Fiber::Acrylic => "Acrylic",
+            Fiber::Alpaca => "Alpaca",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Fiber> {
+        Fiber::ALL.into_iter().find(|f| f.key() == key)
+    }
+
+    fn how_to_block(&self) -> &'static str {
+        match self {
+            Fiber::Wool => "Wet block: soak for 20 minutes in lukewarm water with a little wool wash, squeeze out the water without wringing and roll in a towel",
+            Fiber::SuperwashWool => "Wet block gently: superwash wool grows when wet, so soak briefly, squeeze out and never let it hang",
+            Fiber::Cotton => "Wet block: cotton has little memory, so shape it carefully while damp and let it dry completely",
+            Fiber::Acrylic => "Steam block: acrylic won't respond to water, so hold a steam iron just above the fabric without touching it",
+            Fiber::Alpaca => "Wet block gently: alpaca has lots of drape and stretches, so support its weight and don't pin it out too far",
+        }
+    }
+}
+
+// What the finished piece is shaped over or pinned out to while it dries.
+pub enum BlockingForm {
+    Ball { diameter: f64 },
+    Tube { circumference: f64, length: f64 },
+    Hat { circumference: f64 },
+    Flat { width: f64, length: f64 },
+    // Left at the size it was knitted, to be measured.
+    Swatch,
+}
+
+pub fn notes(fiber: Fiber, form: BlockingForm, units: &str, locale: Locale) -> Vec<String> {
+    let mut notes = vec![format!("{}.", fiber.how_to_block())];
+    match form {
+        BlockingForm::Ball { diameter } => {
+            notes.push(format!(
+                "Shape over a ball or balloon {} {} across ({} {} around) and leave until completely dry.",
+                locale.decimal(diameter, 1),
+                units,
+                locale.decimal(PI * diameter, 1),
+                units
+            ));
+            notes.push(String::from(
+                "Stuff only once dry so the fiberfill doesn't stay damp.",
+            ));
+        }
+        BlockingForm::Tube {
+            circumference,
+            length,
+        } => notes.push(format!(
+            "Lay flat and pin to {} × {} {} (half the circumference by the length) and leave until completely dry.",
+            locale.decimal(circumference / 2.0, 1),
+            locale.decimal(length, 1),
+            units
+        )),
+        BlockingForm::Hat { circumference } => notes.push(format!(
+            "Dry over a balloon or head form about {} {} around, without stretching the brim.",
+            locale.decimal(circumference, 1),
+            units
+        )),
+        BlockingForm::Flat { width, length } => notes.push(format!(
+            "Pin out flat to {} × {} {} and leave until completely dry.",
+            locale.decimal(width, 1),
+            locale.decimal(length, 1),
+            units
+        )),
+        BlockingForm::Swatch => notes.push(String::from(
+            "Lay flat to dry without pinning or stretching, so the swatch shows your work as it is.",
+        )),
+    }
+    notes
+}
@@ -0,0 +1,60 @@
+use crate::form::parse_count;
+use crate::gauge::Gauge;
+use crate::params::Params;
+use crate::shapes::Shape;
+
+fn gcd(a: i32, b: i32) -> i32 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+// The stitch count a shape's rounds must be a multiple of, for its own structure (`base`, such as
+// the rib repeat or the crown wedges) and for the stitch pattern repeat entered.
+pub fn multiple(params: &Params, base: i32) -> i32 {
+    let base = base.max(1);
+    let repeat = parse_count(&params.repeat)
+        .filter(|r| *r > 0)
+        .and_then(|r| i32::try_from(r).ok());
+    // Repeats too wide to fit are rejected by validation; until then they are left out:
+    repeat
+        .and_then(|repeat| (base / gcd(base, repeat)).checked_mul(repeat))
+        .unwrap_or(base)
+}
+
+// The whole number of `multiple`s nearest `stitches`, and at least `least` stitches.
+pub fn nearest(stitches: f64, multiple: i32, least: i32) -> i32 {
+    let repeats = (stitches / f64::from(multiple)).round() as i32;
+    let least = least.saturating_add(multiple - 1) / multiple;
+    repeats.max(least).max(1).saturating_mul(multiple)
+}
+
+// How the cast on was fitted to the stitch pattern repeat and what that does to the size, for the
+// shapes whose width is set by the cast on.
+pub fn report(shape: Shape, params: &Params, gauge: Gauge) -> Option<String> {
+    let repeat = parse_count(&params.repeat)
+        .filter(|r| *r > 1)
+        .and_then(|r| i32::try_from(r).ok())?;
+    let width = shape.cast_on_width(params)?;
+    let stitches = shape.cast_on(params, gauge)?;
+    let change = f64::from(stitches) / gauge.stitches_per_unit - width;
+    let size = if change.abs() < 0.05 {
+        String::from("the size is unchanged")
+    } else {
+        format!(
+            "{} {} {} than entered",
+            params.locale.decimal(change.abs(), 1),
+            params.units,
+            if change > 0.0 { "wider" } else { "narrower" }
+        )
+    };
+    Some(format!(
+        "Cast on {} stitches, {} repeats of {}: {}.",
+        stitches,
+        stitches / repeat,
+        repeat,
+        size
+    ))
+}
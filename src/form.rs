@@ -0,0 +1,49 @@
+use crate::locale::Locale;
+use crate::parse::{parse_number, ParseError};
+use crate::validate::{Field, Issue, Severity, Validation};
+use yew::prelude::*;
+
+// Shows why a field couldn't be read, or what a length with units/fractions was read as,
+// followed by any validation problems with the value.
+pub fn field_message<T: std::fmt::Display>(
+    input: &str,
+    parsed: &Result<f64, ParseError>,
+    units: T,
+    locale: Locale,
+    validation: &Validation,
+    field: Field,
+) -> Html {
+    let parse_message = match parsed {
+        Err(ParseError::Empty) => html! {},
+        Err(err) => html! {<span class="field-error" role="alert">{err.to_string()}</span>},
+        // Shown when the input was a fraction or had units, not for a plain number either way:
+        Ok(value) if input.trim().replacen(',', ".", 1).parse::<f64>().is_err() => {
+            html! {<span class="field-note">{format!("= {} {}", locale.decimal(*value, 2), units)}</span>}
+        }
+        Ok(_) => html! {},
+    };
+    html! {
+        <>
+            { parse_message }
+            { for validation.for_field(field).map(issue_message) }
+        </>
+    }
+}
+
+// Errors are announced by screen readers as they appear, warnings when the reader is next idle.
+pub fn issue_message(issue: &Issue) -> Html {
+    let (class, role) = match issue.severity {
+        Severity::Error => ("field-error", "alert"),
+        Severity::Warning => ("field-warning", "status"),
+    };
+    html! {<span {class} {role}>{&issue.message}</span>}
+}
+
+// Reads a whole number of things, such as colors or rows.
+pub fn parse_count(input: &str) -> Option<usize> {
+    // Whole numbers have no decimal separator, so read them the same everywhere:
+    parse_number(input, Locale::default())
+        .ok()
+        .filter(|count| *count >= 0.0)
+        .map(|count| count.round() as usize)
+}
@@ -0,0 +1,120 @@
+use crate::stitchlib::{self, StitchPattern};
+use serde::{Deserialize, Serialize};
+
+// Common fabrics, with their ratios of stitch gauge to row gauge from the stitch library. A
+// fabric with a ratio of 0.75 has 3 stitches for every 4 rows in the same distance.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Fabric {
+    Stockinette,
+    Garter,
+    Seed,
+    Brioche,
+    SingleCrochet,
+    HalfDoubleCrochet,
+    DoubleCrochet,
+}
+
+impl Fabric {
+    pub const ALL: [Fabric; 7] = [
+        Fabric::Stockinette,
+        Fabric::Garter,
+        Fabric::Seed,
+        Fabric::Brioche,
+        Fabric::SingleCrochet,
+        Fabric::HalfDoubleCrochet,
+        Fabric::DoubleCrochet,
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Fabric::Stockinette => "stockinette",
+            Fabric::Garter => "garter",
+            Fabric::Seed => "seed",
+            Fabric::Brioche => "brioche",
+            Fabric::SingleCrochet => "sc",
+            Fabric::HalfDoubleCrochet => "hdc",
+            Fabric::DoubleCrochet => "dc",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Fabric::Stockinette => "Stockinette",
+            Fabric::Garter => "Garter",
+            Fabric::Seed => "Seed (moss)",
+            Fabric::Brioche => "Two-color brioche",
+            Fabric::SingleCrochet => "Single crochet",
+            Fabric::HalfDoubleCrochet => "Half double crochet",
+            Fabric::DoubleCrochet => "Double crochet",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Fabric> {
+        Fabric::ALL.into_iter().find(|f| f.key() == key)
+    }
+
+    // The abbreviation of the crochet stitch every stitch is worked in, or None for knitting.
+    pub fn crochet(&self) -> Option<&'static str> {
+        match self {
+            Fabric::SingleCrochet | Fabric::HalfDoubleCrochet | Fabric::DoubleCrochet => {
+                Some(self.key())
+            }
+            _ => None,
+        }
+    }
+
+    // A typical pace in stitches a minute, for estimates before the knitter has timed themselves.
+    // Textured and taller stitches take longer; brioche counts both passes.
+    pub fn stitches_per_minute(&self) -> f64 {
+        match self {
+            Fabric::Stockinette | Fabric::Garter | Fabric::SingleCrochet => 20.0,
+            Fabric::Seed | Fabric::Brioche => 15.0,
+            Fabric::HalfDoubleCrochet => 16.0,
+            Fabric::DoubleCrochet => 13.0,
+        }
+    }
+
+    // The fabric's entry in the stitch library, under the same key.
+    pub fn stitch(&self) -> &'static StitchPattern {
+        stitchlib::find(self.key()).expect("every fabric is in the stitch library")
+    }
+
+    pub fn ratio(&self) -> f64 {
+        self.stitch().ratio
+    }
+
+    pub fn estimate_rows_per_unit(&self, stitches_per_unit: f64) -> f64 {
+        stitches_per_unit / self.ratio()
+    }
+
+    // Stitches of the fabric to a stitch of stockinette in the same yarn, each worked with the
+    // needle or hook usual for it. The Craft Yarn Council's ranges for each yarn weight come to
+    // about three crochet stitches for every four knitted.
+    fn stitch_width(&self) -> f64 {
+        match self.crochet() {
+            Some(_) => 0.75,
+            None => 1.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub struct Gauge {
+    pub stitches_per_unit: f64,
+    pub rows_per_unit: f64,
+}
+
+impl Gauge {
+    // An estimate of the gauge in `to` for the same yarn as this gauge in `from`, for moving a
+    // pattern between knitting and crochet. Within one craft the gauge is kept.
+    pub fn convert(&self, from: Fabric, to: Fabric) -> Gauge {
+        if from.crochet().is_some() == to.crochet().is_some() {
+            return *self;
+        }
+        let stitches_per_unit = self.stitches_per_unit * to.stitch_width() / from.stitch_width();
+        Gauge {
+            stitches_per_unit,
+            rows_per_unit: to.estimate_rows_per_unit(stitches_per_unit),
+        }
+    }
+}
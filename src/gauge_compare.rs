@@ -0,0 +1,148 @@
+use crate::gauge::Gauge;
+use crate::grading::sized_pattern;
+use crate::params::Params;
+use crate::parse::parse_number;
+use crate::pattern::Pattern;
+use crate::shapes::Shape;
+use crate::yarn::YarnEstimate;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct GaugeComparisonProps {
+    pub shape: Shape,
+    pub params: Params,
+    pub gauge: Gauge,
+}
+
+// The yarn a pattern takes at `gauge`, from the length of yarn per 100 g if given.
+fn yarn(pattern: &Pattern, gauge: Gauge, params: &Params, per_100g: &str) -> String {
+    YarnEstimate::new(
+        pattern.total_stitches(),
+        gauge.stitches_per_unit,
+        gauge.rows_per_unit,
+        &params.units,
+        parse_number(per_100g, params.locale).ok(),
+    )
+    .describe(params.locale)
+}
+
+// The same shape and size at the form's gauge and at another, side by side, for choosing between
+// two yarns. The other yarn's row gauge is estimated from its stitch gauge when left blank, and
+// its length per 100 g taken from the form.
+#[function_component(GaugeComparison)]
+pub fn gauge_comparison(props: &GaugeComparisonProps) -> Html {
+    let (shape, params) = (props.shape, &props.params);
+    let stitches = use_state(String::new);
+    let rows = use_state(String::new);
+    let per_100g = use_state(String::new);
+    let on_input = |state: &UseStateHandle<String>| {
+        let state = state.clone();
+        Callback::from(move |e: InputEvent| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            state.set(input_el.value());
+        })
+    };
+
+    let other = parse_number(&stitches, params.locale)
+        .ok()
+        .filter(|st| *st > 0.0)
+        .map(|stitches_per_unit| Gauge {
+            stitches_per_unit,
+            rows_per_unit: parse_number(&rows, params.locale)
+                .ok()
+                .filter(|rows| *rows > 0.0)
+                .unwrap_or_else(|| params.fabric.estimate_rows_per_unit(stitches_per_unit)),
+        });
+    let other_per_100g = match per_100g.trim() {
+        "" => params.yarn_per_100g.as_str(),
+        per_100g => per_100g,
+    };
+    let size = shape.size(params);
+    let column = |gauge: Gauge, per_100g: &str| {
+        let described = format!(
+            "{} st, {} rows",
+            params.locale.decimal(gauge.stitches_per_unit, 1),
+            params.locale.decimal(gauge.rows_per_unit, 1)
+        );
+        match sized_pattern(shape, params, gauge, size) {
+            Ok(pattern) => [
+                described,
+                pattern
+                    .rows
+                    .first()
+                    .map_or(0, |row| row.stitches)
+                    .to_string(),
+                pattern
+                    .rows
+                    .iter()
+                    .map(|row| row.stitches)
+                    .max()
+                    .unwrap_or(0)
+                    .to_string(),
+                pattern.rows.len().to_string(),
+                yarn(&pattern, gauge, params, per_100g),
+                String::new(),
+            ],
+            Err(message) => {
+                let none = String::from("—");
+                [
+                    described,
+                    none.clone(),
+                    none.clone(),
+                    none.clone(),
+                    none,
+                    message,
+                ]
+            }
+        }
+    };
+    let table = match other {
+        None => html! {<p class="field-note">{"Enter the other yarn's stitch gauge."}</p>},
+        Some(other) => {
+            let (this, that) = (
+                column(props.gauge, &params.yarn_per_100g),
+                column(other, other_per_100g),
+            );
+            let headings = [
+                format!("Gauge per {}", &params.units),
+                String::from("Cast on"),
+                String::from("Most stitches"),
+                String::from("Rows"),
+                String::from("Yarn"),
+                String::from("Problems"),
+            ];
+            let lines = headings
+                .iter()
+                .zip(this.iter().zip(that.iter()))
+                .filter(|(_, (this, that))| !this.is_empty() || !that.is_empty())
+                .map(|(heading, (this, that))| {
+                    html! {
+                        <tr><th>{heading}</th><td>{this}</td><td>{that}</td></tr>
+                    }
+                });
+            html! {
+                <table class="stitch-table">
+                    <thead>
+                        <tr><th></th><th>{"This gauge"}</th><th>{"Other gauge"}</th></tr>
+                    </thead>
+                    <tbody>{ for lines }</tbody>
+                </table>
+            }
+        }
+    };
+
+    html! {
+        <div class="gauge-comparison">
+            <span>
+                <label>{format!("Other yarn, stitches per {}: ", &params.units)}</label>
+                <input aria-label={format!("Other yarn, stitches per {}", &params.units)} type="text" size="4" value={(*stitches).clone()} oninput={on_input(&stitches)}/>
+                <label>{" rows: "}</label>
+                <input aria-label={format!("Other yarn, rows per {}", &params.units)} type="text" size="4" placeholder="Estimate" value={(*rows).clone()} oninput={on_input(&rows)}/>
+                <label>{" length per 100 g: "}</label>
+                <input aria-label="Other yarn, length per 100 g" type="text" size="4" placeholder={params.yarn_per_100g.clone()} value={(*per_100g).clone()} oninput={on_input(&per_100g)}/>
+            </span>
+            {table}
+        </div>
+    }
+}
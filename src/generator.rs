@@ -0,0 +1,1052 @@
+use crate::abbreviations::Abbreviations;
+use crate::cables::{self, Cable};
+use crate::capacity;
+use crate::counts::{self, Rounding};
+use crate::designer::{designer_table, parse_counts};
+use crate::diff::pattern_diff;
+use crate::finishing::Fiber;
+use crate::fit;
+use crate::form::issue_message;
+use crate::gauge::{Fabric, Gauge};
+use crate::grading::graded_view;
+use crate::held_double;
+use crate::history::History;
+use crate::locale::Locale;
+use crate::metadata::Metadata;
+use crate::needle_inventory::{NeedleInventoryPanel, OwnedNeedle};
+use crate::needles::NeedleStyle;
+use crate::numbering::{Numbering, RowWord};
+use crate::palette::Palette;
+use crate::panels::{
+    AbbreviationsPanel, ChartPalettePanel, GaugePanel, PhrasingPanel, PublishingPanel,
+    StripesPanel, YarnPanel,
+};
+use crate::params::Params;
+use crate::pattern::{Distribution, Pattern, Row};
+use crate::pattern_view::pattern_view;
+use crate::phrasing::Phrasing;
+use crate::presets::{Preset, PresetsPanel};
+use crate::projects::ProjectsPanel;
+use crate::recent::RecentPanel;
+use crate::resume::ResumeForm;
+use crate::ribbing::{self, RibParams};
+use crate::scale::ScaleControl;
+use crate::shapes::cylinder::{self, CylinderParams};
+use crate::shapes::dome::{self, DomeParams};
+use crate::shapes::hat::{self, HatParams};
+use crate::shapes::panel::{self, PanelParams};
+use crate::shapes::sphere::{self, SphereParams};
+use crate::shapes::swatch::{self, SwatchParams};
+use crate::shapes::{Route, Shape};
+use crate::share::ShareQuery;
+use crate::stats::StatsPanel;
+use crate::storage::{self, Project, Recent, Session};
+use crate::stripes::{Stripe, MAX_COLORS};
+use crate::tally::Tally;
+use crate::tension::TensionWizard;
+use crate::units::Unit;
+use crate::validate::{validate_gauge, Field};
+use crate::web_share::ShareButtons;
+use gloo_events::EventListener;
+use gloo_timers::callback::Timeout;
+use log::warn;
+use rand::Rng;
+use std::cell::RefCell;
+use std::mem::{discriminant, Discriminant};
+use web_sys::wasm_bindgen::JsCast;
+use web_sys::{HtmlElement, HtmlInputElement, HtmlSelectElement, KeyboardEvent};
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+pub enum GeneratorMessage {
+    RouteChanged,
+    SetUnits(String),
+    SetLocale(Locale),
+    SetStitchesPerUnit(String),
+    SetRowsPerUnit(String),
+    SetEstimateRows(bool),
+    UseHeldDouble(String, String),
+    SetFabric(Fabric),
+    Scale(Box<Params>),
+    SetYarnPer100g(String),
+    SetFiber(Fiber),
+    SetMetadata(Metadata),
+    SetTally(Tally),
+    SetPalette(Palette),
+    SetAbbreviations(Abbreviations),
+    SetPhrasing(Phrasing),
+    SetSkeinLength(String),
+    SetSkeinPrice(String),
+    SetColors(String),
+    SetRowsPerStripe(String),
+    AddStripe,
+    RemoveStripe(usize),
+    SetStripeColor(usize, usize),
+    SetStripeRows(usize, String),
+    SetGradientColors(String),
+    SetBlendRows(String),
+    SetSelfStripingLength(String),
+    SetSelfStripingColors(String),
+    SetSelfStripingAlign(bool),
+    SetSphere(SphereParams),
+    SetDome(DomeParams),
+    SetCylinder(CylinderParams),
+    SetHat(HatParams),
+    SetPanel(PanelParams),
+    SetSwatch(SwatchParams),
+    SetRib(RibParams),
+    SetCable(Cable),
+    SetEyeletRows(String),
+    SetBobbleDensity(String),
+    SetRepeat(String),
+    SetDistribution(Distribution),
+    SetIncreaseCap(String),
+    SetRounding(Rounding),
+    SetRoundingMultiple(String),
+    SetSmoothCounts(bool),
+    SetMaxStitches(String),
+    SetMinCastOn(String),
+    SetNumbering(Numbering),
+    SetTwoAtATime(bool),
+    SetNeedles(NeedleStyle),
+    SetOwnedNeedles(Vec<OwnedNeedle>),
+    UseSize(String),
+    Reshuffle,
+    SetSizes(String),
+    SetKnitters(String),
+    UsePresetSizes,
+    LoadProject(Box<Project>),
+    ApplyPreset(Box<Preset>),
+    Undo,
+    Redo,
+    Pin(Box<Pattern>),
+    Unpin,
+    RestoreSession,
+    DismissSession,
+    Autosave(Box<Session>),
+    LoadRecent(Box<Session>),
+    StartDesigning(Vec<i32>),
+    StopDesigning,
+    SetCount(usize, String),
+    AddRow,
+    RemoveRow,
+    AdjustTension(Gauge, Vec<Row>),
+    ForgetKnitted,
+    ResumeFrom(Vec<Row>),
+}
+
+// How long the form has to be left alone before it is saved.
+const AUTOSAVE_DELAY_MS: u32 = 1000;
+
+// The form and pattern for whichever shape the route selects. Gauge, yarn and stripe settings are
+// kept here so they carry over when switching between shapes.
+pub struct Generator {
+    params: Params,
+    history: History<Params>,
+    // The kind of the last edit, so a run of keystrokes in one field is undone in one step.
+    last_edit: Option<Discriminant<GeneratorMessage>>,
+    // A pattern kept aside to compare the current one against.
+    pinned: Option<Pattern>,
+    // Stitch counts as typed in designer mode, replacing the generated ones.
+    designer: Option<Vec<String>>,
+    // The shape last shown, to notice when the route moves to another.
+    shown: Shape,
+    // Rows already knitted when the gauge was measured partway, kept as they were while the rest
+    // of the pattern is worked out for the measured gauge.
+    knit: Option<Vec<Row>>,
+    // The form from the last visit, until it is restored or dismissed; not saved over until then.
+    restorable: Option<Session>,
+    // What was last saved, and the pending save.
+    saved: Option<Session>,
+    // The id, rows and widest row of the pattern last shown, for the autosave to remember it by
+    // without designing it again; None when the form doesn't make one.
+    shown_pattern: RefCell<Option<(String, usize, i32)>>,
+    _autosave: Option<Timeout>,
+    recent: Vec<Recent>,
+    owned_needles: Vec<OwnedNeedle>,
+    // The pattern's heading, which takes the focus when a whole pattern is loaded at once.
+    pattern_heading: NodeRef,
+    focus_pattern: bool,
+    _location_listener: Option<LocationHandle>,
+    _keyboard_listener: Option<EventListener>,
+}
+
+impl Generator {
+    // Pins the current pattern, or shows how it differs from the pinned one.
+    fn comparison(&self, ctx: &yew::Context<Self>, current: &Pattern) -> Html {
+        let on_pin = {
+            let current = current.clone();
+            ctx.link()
+                .callback(move |_| GeneratorMessage::Pin(Box::new(current.clone())))
+        };
+        match &self.pinned {
+            None => html! {
+                <p><button type="button" onclick={on_pin}>{"Pin to compare"}</button></p>
+            },
+            Some(pinned) => html! {
+                <details class="comparison" open=true>
+                    <summary>{"Compared with the pinned pattern"}</summary>
+                    <p>
+                        <button type="button" onclick={on_pin}>{"Pin this one instead"}</button>
+                        <button type="button" onclick={ctx.link().callback(|_| GeneratorMessage::Unpin)}>{"Unpin"}</button>
+                    </p>
+                    { pattern_diff(pinned, current) }
+                </details>
+            },
+        }
+    }
+
+    fn shape(&self, ctx: &yew::Context<Self>) -> Shape {
+        ctx.link()
+            .route::<Route>()
+            .map_or(Shape::Sphere, |route| Shape::from_route(&route))
+    }
+
+    // Keeps the URL describing the current pattern so it can be shared.
+    fn sync_url(&self, ctx: &yew::Context<Self>) {
+        let shape = self.shape(ctx);
+        if let Some(navigator) = ctx.link().navigator() {
+            let query = ShareQuery::new(shape, &self.params);
+            if let Err(err) = navigator.replace_with_query(&shape.route(), &query) {
+                warn!("Couldn't update the URL: {}", err);
+            }
+        }
+    }
+
+    // Goes to `shape` with the current parameters. The route may change too, so the URL is set here
+    // rather than by `sync_url`.
+    fn show(&self, ctx: &yew::Context<Self>, shape: Shape) {
+        if let Some(navigator) = ctx.link().navigator() {
+            let query = ShareQuery::new(shape, &self.params);
+            if let Err(err) = navigator.push_with_query(&shape.route(), &query) {
+                warn!("Couldn't update the URL: {}", err);
+            }
+        }
+    }
+
+    // Takes the parameters from the URL when it was shared or edited, returning whether they changed.
+    fn read_url(&mut self, ctx: &yew::Context<Self>) -> bool {
+        let shape = self.shape(ctx);
+        let Some(location) = ctx.link().location() else {
+            return false;
+        };
+        if location.query_str().trim_start_matches('?').is_empty() {
+            // Switching tabs drops the query, so describe the new shape instead:
+            self.sync_url(ctx);
+            return false;
+        }
+        match location.query::<ShareQuery>() {
+            Ok(query) if query != ShareQuery::new(shape, &self.params) => {
+                self.params = query.apply(shape, &self.params);
+                true
+            }
+            Ok(_) => false,
+            Err(err) => {
+                warn!("Couldn't read parameters from the URL: {}", err);
+                false
+            }
+        }
+    }
+}
+
+impl Component for Generator {
+    type Message = GeneratorMessage;
+    type Properties = ();
+
+    fn create(ctx: &yew::Context<Self>) -> Self {
+        let location_listener = ctx
+            .link()
+            .add_location_listener(ctx.link().callback(|_| GeneratorMessage::RouteChanged));
+        let mut generator = Self {
+            params: Params::default(),
+            history: History::new(),
+            last_edit: None,
+            pinned: None,
+            designer: None,
+            shown: Shape::Sphere,
+            knit: None,
+            restorable: None,
+            saved: None,
+            shown_pattern: RefCell::new(None),
+            _autosave: None,
+            recent: storage::load_recent(),
+            owned_needles: storage::load_needles(),
+            pattern_heading: NodeRef::default(),
+            focus_pattern: false,
+            _location_listener: location_listener,
+            _keyboard_listener: undo_shortcuts(ctx),
+        };
+        // A shared link says what to show, so only offer the last session without one:
+        let had_query = ctx
+            .link()
+            .location()
+            .is_some_and(|l| !l.query_str().trim_start_matches('?').is_empty());
+        generator.shown = generator.shape(ctx);
+        generator.read_url(ctx);
+        let saved = storage::load_session();
+        generator.restorable = saved
+            .clone()
+            .filter(|session| !had_query && session.params != generator.params);
+        generator.saved = saved;
+        generator
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> Html {
+        let params = &self.params;
+        let shape = self.shape(ctx);
+
+        let on_input = ctx.link().callback(move |e: InputEvent| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            let units = input_el.value();
+            GeneratorMessage::SetUnits(units)
+        });
+        let on_sizes_input = ctx.link().callback(move |e: InputEvent| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            GeneratorMessage::SetSizes(input_el.value())
+        });
+        let on_knitters_input = ctx.link().callback(move |e: InputEvent| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            GeneratorMessage::SetKnitters(input_el.value())
+        });
+        let on_eyelet_input = ctx.link().callback(move |e: InputEvent| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            GeneratorMessage::SetEyeletRows(input_el.value())
+        });
+        let on_locale_change = ctx.link().batch_callback(|e: Event| {
+            let select_el: HtmlSelectElement = e.target_unchecked_into();
+            Locale::from_key(&select_el.value()).map(GeneratorMessage::SetLocale)
+        });
+        let on_row_word_change = {
+            let numbering = params.numbering;
+            ctx.link().batch_callback(move |e: Event| {
+                let select_el: HtmlSelectElement = e.target_unchecked_into();
+                RowWord::from_key(&select_el.value())
+                    .map(|word| GeneratorMessage::SetNumbering(Numbering { word, ..numbering }))
+            })
+        };
+        let on_skip_cast_on_toggle = {
+            let numbering = params.numbering;
+            ctx.link().callback(move |e: Event| {
+                let input_el: HtmlInputElement = e.target_unchecked_into();
+                GeneratorMessage::SetNumbering(Numbering {
+                    skip_cast_on: input_el.checked(),
+                    ..numbering
+                })
+            })
+        };
+        let on_charts_right_to_left_toggle = {
+            let numbering = params.numbering;
+            ctx.link().callback(move |e: Event| {
+                let input_el: HtmlInputElement = e.target_unchecked_into();
+                GeneratorMessage::SetNumbering(Numbering {
+                    charts_right_to_left: input_el.checked(),
+                    ..numbering
+                })
+            })
+        };
+        let on_two_at_a_time_toggle = ctx.link().callback(move |e: Event| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            GeneratorMessage::SetTwoAtATime(input_el.checked())
+        });
+        let on_needles_change = ctx.link().batch_callback(move |e: Event| {
+            let select_el: HtmlSelectElement = e.target_unchecked_into();
+            NeedleStyle::from_key(&select_el.value()).map(GeneratorMessage::SetNeedles)
+        });
+        let on_distribution_change = ctx.link().batch_callback(move |e: Event| {
+            let select_el: HtmlSelectElement = e.target_unchecked_into();
+            Distribution::from_key(&select_el.value()).map(GeneratorMessage::SetDistribution)
+        });
+        let on_rounding_change = ctx.link().batch_callback(move |e: Event| {
+            let select_el: HtmlSelectElement = e.target_unchecked_into();
+            Rounding::from_key(&select_el.value()).map(GeneratorMessage::SetRounding)
+        });
+        let on_rounding_multiple_input = ctx.link().callback(move |e: InputEvent| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            GeneratorMessage::SetRoundingMultiple(input_el.value())
+        });
+        let on_smooth_toggle = ctx.link().callback(move |e: Event| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            GeneratorMessage::SetSmoothCounts(input_el.checked())
+        });
+        let on_max_stitches_input = ctx.link().callback(move |e: InputEvent| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            GeneratorMessage::SetMaxStitches(input_el.value())
+        });
+        let on_min_cast_on_input = ctx.link().callback(move |e: InputEvent| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            GeneratorMessage::SetMinCastOn(input_el.value())
+        });
+        let on_cap_input = ctx.link().callback(move |e: InputEvent| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            GeneratorMessage::SetIncreaseCap(input_el.value())
+        });
+        let on_repeat_input = ctx.link().callback(move |e: InputEvent| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            GeneratorMessage::SetRepeat(input_el.value())
+        });
+        let on_bobble_input = ctx.link().callback(move |e: InputEvent| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            GeneratorMessage::SetBobbleDensity(input_el.value())
+        });
+        let on_message = ctx.link().callback(|msg| msg);
+        let gauge = params.gauge();
+        let mut validation = validate_gauge(
+            params.stitches_per_unit().ok(),
+            params.rows_per_unit().ok(),
+            &params.units,
+            params.locale,
+        );
+        validation.merge(shape.validate(params, gauge));
+
+        let shape_form = match shape {
+            Shape::Sphere => sphere::form(
+                params,
+                &validation,
+                ctx.link().callback(GeneratorMessage::SetSphere),
+            ),
+            Shape::Dome => dome::form(
+                params,
+                &validation,
+                ctx.link().callback(GeneratorMessage::SetDome),
+            ),
+            Shape::Cylinder => cylinder::form(
+                params,
+                &validation,
+                ctx.link().callback(GeneratorMessage::SetCylinder),
+            ),
+            Shape::Hat => hat::form(
+                params,
+                &validation,
+                ctx.link().callback(GeneratorMessage::SetHat),
+            ),
+            Shape::Panel => panel::form(
+                params,
+                &validation,
+                ctx.link().callback(GeneratorMessage::SetPanel),
+            ),
+            Shape::Swatch => swatch::form(
+                params,
+                &validation,
+                ctx.link().callback(GeneratorMessage::SetSwatch),
+            ),
+        };
+        // Tubes can have a ribbed brim, and open tubes a ribbed cuff at the other end too, with
+        // a cable up the body between them:
+        let tube_form = match shape {
+            Shape::Cylinder | Shape::Hat => html! {
+                <>
+                    { ribbing::form(params, shape == Shape::Hat, ctx.link().callback(GeneratorMessage::SetRib)) }
+                    { cables::form(params, ctx.link().callback(GeneratorMessage::SetCable)) }
+                </>
+            },
+            Shape::Sphere | Shape::Dome | Shape::Panel | Shape::Swatch => html! {},
+        };
+        let mut design = gauge
+            .filter(|_| !validation.has_errors())
+            .and_then(|gauge| Some((shape.design(params, gauge)?, gauge)));
+        // Typed counts are named as the generated pattern names its rows:
+        let flat = design.as_ref().map_or(
+            matches!(shape, Shape::Panel | Shape::Swatch),
+            |(design, _)| design.pattern.is_flat(),
+        );
+        if let Some(texts) = &self.designer {
+            let (counts, count_validation) = parse_counts(texts, params.numbering, flat);
+            if count_validation.has_errors() {
+                design = None;
+            } else if let Some((design, gauge)) = &mut design {
+                let counts = match params.smooth_counts {
+                    true => counts::smooth(&counts),
+                    false => counts,
+                };
+                design.pattern = Pattern::from_counts(&counts, params.seed, params.distribution);
+                shape.finish(design, params, *gauge);
+            }
+            validation.merge(count_validation);
+        }
+        if let (Some(knit), Some((design, _))) = (&self.knit, &mut design) {
+            design.pattern = design
+                .pattern
+                .resume(knit, params.seed, params.distribution);
+        }
+        if let Some((design, _)) = &design {
+            validation.check_shaping(&design.pattern, &params.units);
+        }
+        *self.shown_pattern.borrow_mut() = design.as_ref().map(|(design, _)| {
+            let rows = &design.pattern.rows;
+            (
+                design.pattern.id(),
+                rows.len(),
+                rows.iter().map(|r| r.stitches).max().unwrap_or(0),
+            )
+        });
+        let designer = match (&self.designer, &design) {
+            (Some(texts), _) => designer_table(texts, params.numbering, flat, &on_message),
+            (None, Some((design, _))) => {
+                let counts: Vec<i32> = design.pattern.rows.iter().map(|r| r.stitches).collect();
+                html! {
+                    <p><button type="button" onclick={ctx.link().callback(move |_| GeneratorMessage::StartDesigning(counts.clone()))}>{"Edit stitch counts"}</button></p>
+                }
+            }
+            (None, None) => html! {},
+        };
+        let pattern = html! {
+            <div>
+                if design.is_some() || self.designer.is_some() {
+                    <h1 ref={self.pattern_heading.clone()} tabindex="-1">{"Pattern"}</h1>
+                }
+                <p class="visually-hidden" role="status">{
+                    match &design {
+                        Some((design, _)) => format!("Pattern ready: {} {}s", design.pattern.rows.len(), design.pattern.word().to_lowercase()),
+                        None => String::new(),
+                    }
+                }</p>
+                if let Some((design, gauge)) = &design {
+                    <p class="share-link">
+                        <Link<Route, ShareQuery> to={Route::View} query={Some(ShareQuery::view(shape, params))}>
+                            {"Read-only view to share"}
+                        </Link<Route, ShareQuery>>
+                        <ShareButtons {shape} design={design.clone()} params={params.clone()} gauge={*gauge} />
+                    </p>
+                }
+                if design.is_some() && shape.uses_seed(params) {
+                    <p class="seed">
+                        {format!("Placement variant {} ", params.seed)}
+                        <button type="button" onclick={ctx.link().callback(|_| GeneratorMessage::Reshuffle)}>{"Reshuffle"}</button>
+                    </p>
+                }
+                { designer }
+                { for validation.for_field(Field::Pattern).map(issue_message) }
+                { for design.as_ref().map(|(design, _)| self.comparison(ctx, &design.pattern)) }
+                if let (Some(knit), Some((design, _))) = (&self.knit, &design) {
+                    <p class="app-notice">
+                        {format!("Up to {}, the pattern is kept as you knitted it; the rest is worked out again from there. ", design.pattern.label(knit.len() - 1))}
+                        <button type="button" onclick={ctx.link().callback(|_| GeneratorMessage::ForgetKnitted)}>{"Start again from the top"}</button>
+                    </p>
+                }
+                if let Some((design, gauge)) = &design {
+                    <details>
+                        <summary>{"Check my gauge partway"}</summary>
+                        <TensionWizard
+                            pattern={design.pattern.clone()}
+                            gauge={*gauge}
+                            unit_label={params.units.clone()}
+                            locale={params.locale}
+                            on_adjust={ctx.link().callback(|(gauge, knit)| GeneratorMessage::AdjustTension(gauge, knit))}
+                        />
+                    </details>
+                    <details>
+                        <summary>{"Pick up from a row"}</summary>
+                        <ResumeForm
+                            pattern={design.pattern.clone()}
+                            on_resume={ctx.link().callback(GeneratorMessage::ResumeFrom)}
+                        />
+                    </details>
+                }
+                if let Some((design, gauge)) = &design {
+                    { pattern_view(shape, design, params, *gauge) }
+                    if !params.sizes.trim().is_empty() {
+                        { graded_view(shape, params, *gauge) }
+                    }
+                }
+            </div>
+        };
+
+        html! {
+        <div>
+            if self.restorable.is_some() {
+                <p class="app-notice">
+                    {"Restore the settings you were using last time? "}
+                    <button type="button" onclick={ctx.link().callback(|_| GeneratorMessage::RestoreSession)}>{"Restore"}</button>
+                    <button type="button" onclick={ctx.link().callback(|_| GeneratorMessage::DismissSession)}>{"Start fresh"}</button>
+                </p>
+            }
+            <nav class="shape-tabs">
+                { for Shape::ALL.iter().map(|s| {
+                    let class = if *s == shape { "active" } else { "" };
+                    html! {
+                        <span class={class}><Link<Route> to={s.route()}>{s.name()}</Link<Route>></span>
+                    }
+                }) }
+            </nav>
+            <div class="history-buttons">
+                <button type="button" onclick={ctx.link().callback(|_| GeneratorMessage::Undo)} disabled={!self.history.can_undo()}>{"Undo"}</button>
+                <button type="button" onclick={ctx.link().callback(|_| GeneratorMessage::Redo)} disabled={!self.history.can_redo()}>{"Redo"}</button>
+            </div>
+            <div class="controls">
+                <ProjectsPanel
+                    shape={shape}
+                    params={params.clone()}
+                    on_load={ctx.link().callback(|project| GeneratorMessage::LoadProject(Box::new(project)))}
+                />
+                <RecentPanel
+                    recent={self.recent.clone()}
+                    on_load={ctx.link().callback(|session| GeneratorMessage::LoadRecent(Box::new(session)))}
+                />
+                <StatsPanel />
+                <PresetsPanel
+                    shape={shape}
+                    params={params.clone()}
+                    on_apply={ctx.link().callback(|preset| GeneratorMessage::ApplyPreset(Box::new(preset)))}
+                />
+                <details class="panel" open=true>
+                    <summary><h3>{format!("{} Size", shape.name())}</h3></summary>
+                    <span>
+                        <label>{"Units: "}</label>
+                        <input aria-label="Units" type="text" placeholder="Units (in, cm)" oninput={on_input} value={params.units.clone()}/>
+                    </span>
+                    <span>
+                        <label>{"Language: "}</label>
+                        <select aria-label="Language" onchange={on_locale_change}>
+                            { for Locale::ALL.iter().map(|l| html! {
+                                <option value={l.key()} selected={*l == params.locale}>{l.name()}</option>
+                            }) }
+                        </select>
+                        <span class="field-note">{"Sets the decimal separator and which of cm or in comes first; the pattern is written in English."}</span>
+                    </span>
+                    { shape_form }
+                    <ScaleControl
+                        {shape}
+                        params={params.clone()}
+                        on_scale={ctx.link().callback(|scaled| GeneratorMessage::Scale(Box::new(scaled)))}
+                    />
+                    { tube_form }
+                    <span>
+                        <label>{"Eyelet rows: "}</label>
+                        <input aria-label="Eyelet rows" type="text" placeholder="Row numbers, e.g. 8, 9" value={params.eyelet_rows.clone()} oninput={on_eyelet_input}/>
+                        <span class="field-note">{"Plain rows only; a fold along an eyelet row makes a picot edge."}</span>
+                    </span>
+                    <span>
+                        <label>{"Number the pattern in: "}</label>
+                        <select aria-label="Number the pattern in" onchange={on_row_word_change}>
+                            { for RowWord::ALL.iter().map(|w| html! {
+                                <option value={w.key()} selected={*w == params.numbering.word}>{w.name()}</option>
+                            }) }
+                        </select>
+                        <label>
+                            <input type="checkbox" checked={params.numbering.skip_cast_on} onchange={on_skip_cast_on_toggle}/>
+                            {" Don't count the cast on as a row"}
+                        </label>
+                        if matches!(shape, Shape::Panel | Shape::Swatch) {
+                            <label>
+                                <input type="checkbox" checked={params.numbering.charts_right_to_left} onchange={on_charts_right_to_left_toggle}/>
+                                {" Read every chart row from the right"}
+                            </label>
+                        }
+                    </span>
+                    if shape.knitted_in_the_round(params) {
+                        <span>
+                            <label>
+                                <input type="checkbox" checked={params.two_at_a_time} onchange={on_two_at_a_time_toggle}/>
+                                {" Make two at once on one long circular needle"}
+                            </label>
+                        </span>
+                        if !params.two_at_a_time {
+                            <span>
+                                <label>{"Needle notes for: "}</label>
+                                <select aria-label="Needle notes for" onchange={on_needles_change}>
+                                    { for NeedleStyle::ALL.iter().map(|s| html! {
+                                        <option value={s.key()} selected={*s == params.needles}>{s.name()}</option>
+                                    }) }
+                                </select>
+                            </span>
+                        }
+                    }
+                    if matches!(shape, Shape::Sphere | Shape::Dome) || self.designer.is_some() {
+                        <span>
+                            <label>{"Increase spacing: "}</label>
+                            <select aria-label="Increase spacing" onchange={on_distribution_change}>
+                                { for Distribution::ALL.iter().filter(|d| **d != Distribution::Amigurumi || params.fabric.crochet().is_some()).map(|d| html! {
+                                    <option value={d.key()} selected={*d == params.distribution}>{d.name()}</option>
+                                }) }
+                            </select>
+                        </span>
+                    }
+                    if shape == Shape::Sphere || self.designer.is_some() {
+                        <span>
+                            <label>
+                                <input type="checkbox" checked={params.smooth_counts} onchange={on_smooth_toggle}/>
+                                {" Smooth out one-stitch wobbles in the counts"}
+                            </label>
+                        </span>
+                    }
+                    if shape == Shape::Sphere {
+                        <span>
+                            <label>{"Round rows to stitches: "}</label>
+                            <select aria-label="Round rows to stitches" onchange={on_rounding_change}>
+                                { for Rounding::ALL.iter().map(|r| html! {
+                                    <option value={r.key()} selected={*r == params.rounding}>{r.name()}</option>
+                                }) }
+                            </select>
+                            if params.rounding == Rounding::Multiple {
+                                <input aria-label="Multiple of stitches" type="number" min="1" size="3" placeholder="Stitches" value={params.rounding_multiple.clone()} oninput={on_rounding_multiple_input}/>
+                            }
+                        </span>
+                        <span>
+                            <label>{"At most one increase per: "}</label>
+                            <input aria-label="At most one increase per" type="number" min="1" placeholder="Stitches" value={params.increase_cap.clone()} oninput={on_cap_input}/>
+                            <span class="field-note">{"Increases a row can't take move to the rows after it."}</span>
+                        </span>
+                        <span>
+                            <label>{"Start with at least: "}</label>
+                            <input aria-label="Start with at least" type="number" min="1" placeholder="Stitches" value={params.min_cast_on.clone()} oninput={on_min_cast_on_input}/>
+                        </span>
+                    }
+                    if !matches!(shape, Shape::Sphere | Shape::Dome) {
+                        <span>
+                            <label>{"Stitch pattern repeat: "}</label>
+                            <input aria-label="Stitch pattern repeat" type="number" min="1" placeholder="Stitches" value={params.repeat.clone()} oninput={on_repeat_input}/>
+                            { for validation.for_field(Field::Repeat).map(issue_message) }
+                            { for gauge.and_then(|gauge| fit::report(shape, params, gauge)).map(|report| html! {
+                                <span class="field-note">{report}</span>
+                            }) }
+                        </span>
+                    }
+                    <span>
+                        <label>{"Most stitches the needles hold: "}</label>
+                        <input aria-label="Most stitches the needles hold" type="number" min="1" placeholder="No limit" value={params.max_stitches.clone()} oninput={on_max_stitches_input}/>
+                        { for design.as_ref().map(|(design, gauge)| capacity::advice(shape, params, &design.pattern, *gauge, &on_message)) }
+                    </span>
+                    <span>
+                        <label>{"Bobbles per 100 stitches: "}</label>
+                        <input aria-label="Bobbles per 100 stitches" type="text" size="4" placeholder="None" value={params.bobble_density.clone()} oninput={on_bobble_input}/>
+                        { for validation.for_field(Field::BobbleDensity).map(issue_message) }
+                    </span>
+                    <span>
+                        <label>{"Also grade for sizes: "}</label>
+                        <input aria-label="Also grade for sizes" type="text" placeholder={format!("More {}s, e.g. 5; 6", shape.size_name().to_lowercase())} value={params.sizes.clone()} oninput={on_sizes_input}/>
+                        <button type="button" onclick={ctx.link().callback(|_| GeneratorMessage::UsePresetSizes)}>{"S/M/L"}</button>
+                    </span>
+                    <span>
+                        <label>{"Knitters sharing the work: "}</label>
+                        <input aria-label="Knitters sharing the work" type="number" min="1" placeholder="1" value={params.knitters.clone()} oninput={on_knitters_input}/>
+                    </span>
+                </details>
+                <GaugePanel params={params.clone()} validation={validation.clone()} on_message={on_message.clone()} />
+                <YarnPanel params={params.clone()} on_message={on_message.clone()} />
+                <NeedleInventoryPanel
+                    needles={self.owned_needles.clone()}
+                    locale={params.locale}
+                    on_change={ctx.link().callback(GeneratorMessage::SetOwnedNeedles)}
+                />
+                <StripesPanel params={params.clone()} on_message={on_message.clone()} />
+                <ChartPalettePanel params={params.clone()} on_message={on_message.clone()} />
+                <AbbreviationsPanel params={params.clone()} on_message={on_message.clone()} />
+                <PhrasingPanel params={params.clone()} on_message={on_message.clone()} />
+                <PublishingPanel params={params.clone()} on_message={on_message} />
+            </div>
+
+            { pattern }
+        </div>
+        }
+    }
+
+    // Saves the form shortly after it stops changing, so closing the tab doesn't lose it.
+    fn rendered(&mut self, ctx: &yew::Context<Self>, _first_render: bool) {
+        if self.focus_pattern {
+            self.focus_pattern = false;
+            if let Some(heading) = self.pattern_heading.cast::<HtmlElement>() {
+                let _ = heading.focus();
+            }
+        }
+        if self.restorable.is_some() {
+            return;
+        }
+        let session = Session {
+            shape: self.shape(ctx),
+            params: self.params.clone(),
+        };
+        if self.saved.as_ref() != Some(&session) {
+            self.saved = Some(session.clone());
+            let link = ctx.link().clone();
+            self._autosave = Some(Timeout::new(AUTOSAVE_DELAY_MS, move || {
+                link.send_message(GeneratorMessage::Autosave(Box::new(session)))
+            }));
+        }
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            GeneratorMessage::RouteChanged => {
+                // Counts typed in or rows knitted for one shape don't describe another:
+                let shape = self.shape(ctx);
+                if shape != self.shown {
+                    self.shown = shape;
+                    self.designer = None;
+                    self.knit = None;
+                }
+                self.read_url(ctx);
+                return true;
+            }
+            GeneratorMessage::Undo | GeneratorMessage::Redo => {
+                let restored = match msg {
+                    GeneratorMessage::Undo => self.history.undo(self.params.clone()),
+                    _ => self.history.redo(self.params.clone()),
+                };
+                let Some(restored) = restored else {
+                    return false;
+                };
+                self.params = restored;
+                self.last_edit = None;
+                self.sync_url(ctx);
+                return true;
+            }
+            GeneratorMessage::RestoreSession => {
+                if let Some(session) = self.restorable.take() {
+                    self.knit = None;
+                    self.history.record(self.params.clone());
+                    self.last_edit = None;
+                    self.params = session.params;
+                    self.focus_pattern = true;
+                    self.show(ctx, session.shape);
+                }
+                return true;
+            }
+            GeneratorMessage::DismissSession => {
+                self.restorable = None;
+                return true;
+            }
+            GeneratorMessage::SetOwnedNeedles(needles) => {
+                storage::save_needles(&needles);
+                self.owned_needles = needles;
+                return true;
+            }
+            GeneratorMessage::Autosave(session) => {
+                storage::save_session(&session);
+                // Settled parameters that make a pattern are also remembered as recent. The session
+                // is the one last shown, since any change since would have put off this save:
+                let shown = self.shown_pattern.borrow().clone();
+                return match shown {
+                    Some((id, rows, widest)) => {
+                        storage::record_generated(session.shape, &id);
+                        self.recent = storage::add_recent(Recent {
+                            rows,
+                            widest,
+                            session: *session,
+                        });
+                        true
+                    }
+                    None => false,
+                };
+            }
+            GeneratorMessage::LoadRecent(session) => {
+                self.knit = None;
+                self.history.record(self.params.clone());
+                self.last_edit = None;
+                self.params = session.params;
+                self.focus_pattern = true;
+                self.show(ctx, session.shape);
+                return true;
+            }
+            GeneratorMessage::StartDesigning(counts) => {
+                self.designer = Some(counts.iter().map(|c| c.to_string()).collect());
+                return true;
+            }
+            GeneratorMessage::StopDesigning => {
+                self.designer = None;
+                return true;
+            }
+            GeneratorMessage::ForgetKnitted => {
+                self.knit = None;
+                return true;
+            }
+            GeneratorMessage::ResumeFrom(knit) => {
+                self.knit = Some(knit);
+                return true;
+            }
+            GeneratorMessage::SetCount(index, text) => {
+                if let Some(count) = self.designer.as_mut().and_then(|t| t.get_mut(index)) {
+                    *count = text;
+                }
+                return true;
+            }
+            GeneratorMessage::AddRow => {
+                if let Some(texts) = &mut self.designer {
+                    texts.push(texts.last().cloned().unwrap_or_else(|| String::from("6")));
+                }
+                return true;
+            }
+            GeneratorMessage::RemoveRow => {
+                if let Some(texts) = &mut self.designer {
+                    texts.pop();
+                }
+                return true;
+            }
+            GeneratorMessage::Pin(pattern) => {
+                self.pinned = Some(*pattern);
+                return true;
+            }
+            GeneratorMessage::Unpin => {
+                self.pinned = None;
+                return true;
+            }
+            _ => {}
+        }
+        self.restorable = None;
+        let edit = discriminant(&msg);
+        if self.last_edit != Some(edit) {
+            self.history.record(self.params.clone());
+            self.last_edit = Some(edit);
+        }
+        let shape = self.shape(ctx);
+        let params = &mut self.params;
+        match msg {
+            GeneratorMessage::RouteChanged
+            | GeneratorMessage::Undo
+            | GeneratorMessage::Redo
+            | GeneratorMessage::Pin(_)
+            | GeneratorMessage::Unpin
+            | GeneratorMessage::RestoreSession
+            | GeneratorMessage::DismissSession
+            | GeneratorMessage::SetOwnedNeedles(_)
+            | GeneratorMessage::Autosave(_)
+            | GeneratorMessage::LoadRecent(_)
+            | GeneratorMessage::StartDesigning(_)
+            | GeneratorMessage::StopDesigning
+            | GeneratorMessage::ForgetKnitted
+            | GeneratorMessage::ResumeFrom(_)
+            | GeneratorMessage::SetCount(..)
+            | GeneratorMessage::AddRow
+            | GeneratorMessage::RemoveRow => {}
+            GeneratorMessage::SetUnits(val) => params.units = val,
+            GeneratorMessage::SetLocale(val) => params.locale = val,
+            GeneratorMessage::SetStitchesPerUnit(val) => {
+                params.stitches_per_unit = val;
+                params.held_double = None;
+            }
+            GeneratorMessage::SetRowsPerUnit(val) => params.rows_per_unit = val,
+            GeneratorMessage::SetEstimateRows(val) => params.estimate_rows = val,
+            GeneratorMessage::UseHeldDouble(first, second) => {
+                if let Some(gauge) = held_double::parse_strands(&first, &second, params.locale) {
+                    params.stitches_per_unit = params.locale.decimal(gauge, 1);
+                    params.estimate_rows = true;
+                    params.held_double = Some((first, second));
+                }
+            }
+            GeneratorMessage::AdjustTension(gauge, knit) => {
+                let locale = params.locale;
+                let text = |value: f64| locale.number((value * 100.0).round() / 100.0);
+                params.stitches_per_unit = text(gauge.stitches_per_unit);
+                params.rows_per_unit = text(gauge.rows_per_unit);
+                params.estimate_rows = false;
+                self.knit = Some(knit);
+            }
+            GeneratorMessage::SetFabric(val) => {
+                // Switching between knitting and crochet keeps the yarn, so its gauge is carried
+                // over as an estimate for the other craft:
+                let crafts_differ = val.crochet().is_some() != params.fabric.crochet().is_some();
+                if let Some(gauge) = params.gauge().filter(|_| crafts_differ) {
+                    let gauge = gauge.convert(params.fabric, val);
+                    params.stitches_per_unit = params.locale.decimal(gauge.stitches_per_unit, 1);
+                    params.rows_per_unit = params.locale.decimal(gauge.rows_per_unit, 1);
+                    params.held_double = None;
+                }
+                params.fabric = val;
+            }
+            GeneratorMessage::Scale(val) => *params = *val,
+            GeneratorMessage::SetYarnPer100g(val) => params.yarn_per_100g = val,
+            GeneratorMessage::SetFiber(val) => params.fiber = val,
+            GeneratorMessage::SetMetadata(val) => params.metadata = val,
+            GeneratorMessage::SetTally(val) => params.tally = val,
+            GeneratorMessage::SetPalette(val) => params.palette = val,
+            GeneratorMessage::SetAbbreviations(val) => params.abbreviations = val,
+            GeneratorMessage::SetPhrasing(val) => params.phrasing = val,
+            GeneratorMessage::SetSkeinLength(val) => params.skein_length = val,
+            GeneratorMessage::SetSkeinPrice(val) => params.skein_price = val,
+            GeneratorMessage::SetColors(val) => params.colors = val,
+            GeneratorMessage::SetRowsPerStripe(val) => params.rows_per_stripe = val,
+            GeneratorMessage::AddStripe => {
+                // Each new band follows on in the next color:
+                let color = params
+                    .stripe_sequence
+                    .last()
+                    .map_or(0, |stripe| (stripe.color + 1) % MAX_COLORS);
+                params.stripe_sequence.push(Stripe {
+                    color,
+                    rows: String::from("2"),
+                });
+            }
+            GeneratorMessage::RemoveStripe(i) => {
+                params.stripe_sequence.remove(i);
+            }
+            GeneratorMessage::SetStripeColor(i, color) => params.stripe_sequence[i].color = color,
+            GeneratorMessage::SetStripeRows(i, val) => params.stripe_sequence[i].rows = val,
+            GeneratorMessage::SetGradientColors(val) => params.gradient_colors = val,
+            GeneratorMessage::SetBlendRows(val) => params.blend_rows = val,
+            GeneratorMessage::SetSelfStripingLength(val) => params.self_striping_length = val,
+            GeneratorMessage::SetSelfStripingColors(val) => params.self_striping_colors = val,
+            GeneratorMessage::SetSelfStripingAlign(val) => params.self_striping_align = val,
+            GeneratorMessage::SetSphere(val) => params.sphere = val,
+            GeneratorMessage::SetDome(val) => params.dome = val,
+            GeneratorMessage::SetCylinder(val) => params.cylinder = val,
+            GeneratorMessage::SetHat(val) => params.hat = val,
+            GeneratorMessage::SetPanel(val) => params.panel = val,
+            GeneratorMessage::SetSwatch(val) => params.swatch = val,
+            GeneratorMessage::SetRib(val) => params.rib = val,
+            GeneratorMessage::SetCable(val) => params.cable = val,
+            GeneratorMessage::SetEyeletRows(val) => params.eyelet_rows = val,
+            GeneratorMessage::SetBobbleDensity(val) => params.bobble_density = val,
+            GeneratorMessage::SetRepeat(val) => params.repeat = val,
+            GeneratorMessage::SetDistribution(val) => params.distribution = val,
+            GeneratorMessage::SetIncreaseCap(val) => params.increase_cap = val,
+            GeneratorMessage::SetRounding(val) => params.rounding = val,
+            GeneratorMessage::SetRoundingMultiple(val) => params.rounding_multiple = val,
+            GeneratorMessage::SetSmoothCounts(val) => params.smooth_counts = val,
+            GeneratorMessage::SetMaxStitches(val) => params.max_stitches = val,
+            GeneratorMessage::SetMinCastOn(val) => params.min_cast_on = val,
+            GeneratorMessage::SetNumbering(val) => params.numbering = val,
+            GeneratorMessage::SetTwoAtATime(val) => params.two_at_a_time = val,
+            GeneratorMessage::SetNeedles(val) => params.needles = val,
+            GeneratorMessage::UseSize(val) => *params = shape.with_size(params, &val),
+            GeneratorMessage::SetSizes(val) => params.sizes = val,
+            GeneratorMessage::SetKnitters(val) => params.knitters = val,
+            GeneratorMessage::UsePresetSizes => {
+                let units = params.units().unwrap_or(Unit::Inch);
+                let [small, rest @ ..] = shape
+                    .preset_sizes()
+                    .map(|inches| format!("{}", Unit::convert(inches, Unit::Inch, units).round()));
+                *params = shape.with_size(params, &small);
+                params.sizes = rest.join("; ");
+            }
+            // Seeds are kept short so they are easy to read out and type back in.
+            GeneratorMessage::Reshuffle => params.seed = rand::thread_rng().gen_range(1..100_000),
+            GeneratorMessage::ApplyPreset(preset) => {
+                *params = preset.apply(params);
+                self.focus_pattern = true;
+            }
+            GeneratorMessage::LoadProject(project) => {
+                *params = project.params;
+                self.knit = None;
+                self.focus_pattern = true;
+                self.show(ctx, project.shape);
+                return true;
+            }
+        }
+        self.sync_url(ctx);
+        true
+    }
+}
+
+// Ctrl+Z undoes and Ctrl+Shift+Z or Ctrl+Y redoes, except in text fields which have their own undo.
+fn undo_shortcuts(ctx: &yew::Context<Generator>) -> Option<EventListener> {
+    let window = web_sys::window()?;
+    let link = ctx.link().clone();
+    Some(EventListener::new(&window, "keydown", move |e| {
+        let Some(e) = e.dyn_ref::<KeyboardEvent>() else {
+            return;
+        };
+        let in_text_field = e
+            .target()
+            .and_then(|t| t.dyn_into::<HtmlInputElement>().ok())
+            .is_some_and(|input| input.type_() == "text");
+        if !(e.ctrl_key() || e.meta_key()) || in_text_field {
+            return;
+        }
+        let msg = match (e.key().to_lowercase().as_str(), e.shift_key()) {
+            ("z", false) => GeneratorMessage::Undo,
+            ("z", true) | ("y", false) => GeneratorMessage::Redo,
+            _ => return,
+        };
+        e.prevent_default();
+        link.send_message(msg);
+    }))
+}
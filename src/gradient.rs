@@ -0,0 +1,24 @@
+// Colors for a gradient: the rows are split into equal bands, one per color, and each change of
+// color is softened by alternating the two colors a row at a time for `blend` rows around it.
+pub fn gradient_colors(rows: usize, colors: usize, blend: usize) -> Vec<usize> {
+    if rows == 0 || colors < 2 {
+        return vec![0; rows];
+    }
+    let mut plan: Vec<usize> = (0..rows).map(|row| row * colors / rows).collect();
+    // A blend can't reach further than the bands either side of it.
+    let blend = blend.min(rows / colors);
+    for color in 1..colors {
+        let Some(boundary) = plan.iter().position(|c| *c == color) else {
+            continue;
+        };
+        let start = boundary.saturating_sub(blend / 2);
+        for (i, row) in plan.iter_mut().enumerate().skip(start).take(blend) {
+            *row = if (i - start) % 2 == 0 {
+                color
+            } else {
+                color - 1
+            };
+        }
+    }
+    plan
+}
@@ -0,0 +1,205 @@
+use crate::gauge::Gauge;
+use crate::params::Params;
+use crate::parse::split_list;
+use crate::pattern::Pattern;
+use crate::shapes::Shape;
+use crate::validate::Severity;
+use yew::prelude::*;
+
+// Each size the pattern is graded for, as entered, and its pattern or why it couldn't be made.
+pub fn graded_patterns(
+    shape: Shape,
+    params: &Params,
+    gauge: Gauge,
+) -> Vec<(String, Result<Pattern, String>)> {
+    let extra = split_list(&params.sizes, params.locale);
+    std::iter::once(shape.size(params).trim())
+        .chain(extra)
+        .map(|size| (size.to_string(), sized_pattern(shape, params, gauge, size)))
+        .collect()
+}
+
+// The pattern for the shape at another size, as entered, or why it couldn't be made.
+pub fn sized_pattern(
+    shape: Shape,
+    params: &Params,
+    gauge: Gauge,
+    size: &str,
+) -> Result<Pattern, String> {
+    let sized = shape.with_size(params, size);
+    let validation = shape.validate(&sized, Some(gauge));
+    match validation
+        .issues
+        .into_iter()
+        .find(|i| i.severity == Severity::Error)
+    {
+        Some(issue) => Err(issue.message),
+        None => shape
+            .design(&sized, gauge)
+            .map(|design| design.pattern)
+            .ok_or_else(|| String::from("no pattern for this size")),
+    }
+}
+
+// Splits text into runs of digits and runs of everything else.
+fn tokens(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    for (i, c) in text.char_indices().skip(1) {
+        let previous = text[..i].chars().next_back();
+        if previous.is_some_and(|p| p.is_ascii_digit() != c.is_ascii_digit()) {
+            tokens.push(&text[start..i]);
+            start = i;
+        }
+    }
+    if start < text.len() {
+        tokens.push(&text[start..]);
+    }
+    tokens
+}
+
+// Combines one instruction per size into the usual "k12 (14, 16)" form: numbers that differ are
+// given for the first size with the others in brackets. Instructions that differ in wording are
+// written out for each size.
+pub fn merge_instructions(instructions: &[(&str, String)]) -> String {
+    let Some((_, first)) = instructions.first() else {
+        return String::new();
+    };
+    let split: Vec<Vec<&str>> = instructions.iter().map(|(_, text)| tokens(text)).collect();
+    let same_wording = split.iter().all(|t| {
+        t.len() == split[0].len()
+            && t.iter()
+                .zip(&split[0])
+                .all(|(a, b)| a == b || is_number(a) && is_number(b))
+    });
+    if instructions.len() == 1 || split.iter().all(|t| *t == split[0]) {
+        return first.clone();
+    }
+    if !same_wording {
+        return instructions
+            .iter()
+            .map(|(size, text)| format!("[{}] {}", size, text))
+            .collect::<Vec<_>>()
+            .join(" ");
+    }
+    (0..split[0].len())
+        .map(|i| {
+            let values: Vec<&str> = split.iter().map(|t| t[i]).collect();
+            if values.iter().all(|v| *v == values[0]) {
+                values[0].to_string()
+            } else {
+                format!("{} ({})", values[0], values[1..].join(", "))
+            }
+        })
+        .collect()
+}
+
+fn is_number(token: &str) -> bool {
+    token.chars().all(|c| c.is_ascii_digit())
+}
+
+// The instructions for every size at once, row by row.
+pub fn graded_view(shape: Shape, params: &Params, gauge: Gauge) -> Html {
+    let sizes = graded_patterns(shape, params, gauge);
+    let labels: Vec<&str> = sizes.iter().map(|(size, _)| size.as_str()).collect();
+    let problems = sizes.iter().filter_map(|(size, pattern)| {
+        pattern.as_ref().err().map(|message| {
+            html! {<p class="field-warning">{format!("Size {} left out: {}", size, message)}</p>}
+        })
+    });
+    let patterns: Vec<(&str, &Pattern)> = sizes
+        .iter()
+        .filter_map(|(size, pattern)| Some((size.as_str(), pattern.as_ref().ok()?)))
+        .collect();
+    let length = patterns
+        .iter()
+        .map(|(_, p)| p.rows.len())
+        .max()
+        .unwrap_or(0);
+    let rows = (0..length).map(|i| {
+        let present: Vec<(&str, String)> = patterns
+            .iter()
+            .filter(|(_, p)| i < p.rows.len())
+            .map(|(size, p)| (*size, p.instruction(i)))
+            .collect();
+        let only = if present.len() < patterns.len() {
+            let names: Vec<&str> = present.iter().map(|(size, _)| *size).collect();
+            format!("Size {} only: ", names.join(", "))
+        } else {
+            String::new()
+        };
+        html! {<div>{format!("{}{}", only, merge_instructions(&present))}</div>}
+    });
+    let heading = match labels.split_first() {
+        Some((first, [])) => first.to_string(),
+        Some((first, rest)) => format!("{} ({})", first, rest.join(", ")),
+        None => String::new(),
+    };
+
+    html! {
+        <div class="graded">
+            <h3>{"All sizes"}</h3>
+            <p>{format!("{}: {} {}", shape.size_name(), heading, &params.units)}</p>
+            { for problems }
+            <ul>{ for rows }</ul>
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn merge(instructions: &[(&str, &str)]) -> String {
+        let owned: Vec<(&str, String)> = instructions
+            .iter()
+            .map(|(size, text)| (*size, text.to_string()))
+            .collect();
+        merge_instructions(&owned)
+    }
+
+    #[test]
+    fn one_size_or_the_same_instruction_is_kept() {
+        assert_eq!(merge(&[]), "");
+        assert_eq!(merge(&[("S", "Round 2: k12")]), "Round 2: k12");
+        assert_eq!(
+            merge(&[("S", "Round 2: k12"), ("M", "Round 2: k12")]),
+            "Round 2: k12"
+        );
+    }
+
+    #[test]
+    fn numbers_that_differ_are_bracketed() {
+        assert_eq!(
+            merge(&[
+                ("S", "Round 4: k12"),
+                ("M", "Round 4: k14"),
+                ("L", "Round 4: k16")
+            ]),
+            "Round 4: k12 (14, 16)"
+        );
+        assert_eq!(
+            merge(&[
+                ("S", "Round 5: k3, inc (total of 6 inc, 30 st total)"),
+                ("M", "Round 5: k4, inc (total of 6 inc, 36 st total)")
+            ]),
+            "Round 5: k3 (4), inc (total of 6 inc, 30 (36) st total)"
+        );
+    }
+
+    #[test]
+    fn longer_numbers_in_one_size_still_line_up() {
+        assert_eq!(
+            merge(&[("S", "Round 9: k8"), ("M", "Round 9: k112")]),
+            "Round 9: k8 (112)"
+        );
+    }
+
+    #[test]
+    fn different_wording_is_written_out_for_each_size() {
+        assert_eq!(
+            merge(&[("S", "Round 3: k12"), ("M", "Round 3: p12")]),
+            "[S] Round 3: k12 [M] Round 3: p12"
+        );
+    }
+}
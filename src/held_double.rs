@@ -0,0 +1,33 @@
+use crate::locale::Locale;
+use crate::parse::parse_number;
+
+// The gauge of two strands held together as one, from the stitch gauges of each yarn alone. A
+// yarn's stitch gauge goes as one over its thickness, and two strands together are about as thick
+// as a yarn with the area of both, so two of the same yarn knit about 0.7 times as many stitches.
+pub fn combined_gauge(first: f64, second: f64) -> f64 {
+    1.0 / (first.powi(-2) + second.powi(-2)).sqrt()
+}
+
+// The combined stitch gauge of two yarns entered as text, when both are numbers above 0.
+pub fn parse_strands(first: &str, second: &str, locale: Locale) -> Option<f64> {
+    match (parse_number(first, locale), parse_number(second, locale)) {
+        (Ok(first), Ok(second)) if first > 0.0 && second > 0.0 => {
+            Some(combined_gauge(first, second))
+        }
+        _ => None,
+    }
+}
+
+// The assumption behind a gauge worked out for yarns held together, for the materials.
+pub fn note(strands: &(String, String), unit_label: &str, locale: Locale) -> String {
+    // Gauges are shown the reader's way, as entered when they can't be read:
+    let gauge = |text: &str| {
+        parse_number(text, locale).map_or(text.trim().to_string(), |value| locale.number(value))
+    };
+    format!(
+        "Held double: one strand each of a yarn knitting {} st/{u} and one knitting {} st/{u}, worked together as one. The gauge was estimated from the two; knit a swatch with both strands to confirm it.",
+        gauge(&strands.0),
+        gauge(&strands.1),
+        u = unit_label
+    )
+}
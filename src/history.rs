@@ -0,0 +1,46 @@
+// Undo/redo stacks of earlier and undone states.
+pub struct History<T> {
+    past: Vec<T>,
+    future: Vec<T>,
+}
+
+// Beyond this, the oldest states are forgotten.
+const LIMIT: usize = 100;
+
+impl<T> History<T> {
+    pub fn new() -> History<T> {
+        History {
+            past: Vec::new(),
+            future: Vec::new(),
+        }
+    }
+
+    // Remembers the state from before a change. A new change makes undone states unreachable.
+    pub fn record(&mut self, previous: T) {
+        self.past.push(previous);
+        if self.past.len() > LIMIT {
+            self.past.remove(0);
+        }
+        self.future.clear();
+    }
+
+    pub fn undo(&mut self, current: T) -> Option<T> {
+        let previous = self.past.pop()?;
+        self.future.push(current);
+        Some(previous)
+    }
+
+    pub fn redo(&mut self, current: T) -> Option<T> {
+        let next = self.future.pop()?;
+        self.past.push(current);
+        Some(next)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.past.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.future.is_empty()
+    }
+}
@@ -0,0 +1,152 @@
+use gloo_events::EventListener;
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::js_sys::Reflect;
+use web_sys::wasm_bindgen::JsCast;
+use web_sys::{KeyboardEvent, Navigator, WakeLockSentinel, WakeLockType};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct KnittingModeProps {
+    // The instruction for the row being worked, if any are left.
+    pub instruction: Option<String>,
+    pub note: Option<String>,
+    pub done: usize,
+    pub total: usize,
+    pub on_next: Callback<()>,
+    pub on_previous: Callback<()>,
+    pub on_exit: Callback<()>,
+}
+
+// Whether the wake lock is still wanted, and the lock once the browser grants it.
+#[derive(Default)]
+struct WakeLockState {
+    wanted: bool,
+    sentinel: Option<WakeLockSentinel>,
+}
+
+// Just the current row in large type, filling the screen and keeping it on while it is shown.
+#[function_component(KnittingMode)]
+pub fn knitting_mode(props: &KnittingModeProps) -> Html {
+    use_effect_with_deps(
+        |_| {
+            let state = Rc::new(RefCell::new(WakeLockState {
+                wanted: true,
+                sentinel: None,
+            }));
+            keep_screen_on(state.clone());
+            // The browser lets go of the lock whenever the page is hidden, so ask again on return:
+            let document = web_sys::window().and_then(|w| w.document());
+            let on_visible = document.map(|document| {
+                let state = state.clone();
+                EventListener::new(&document.clone(), "visibilitychange", move |_| {
+                    if !document.hidden() && state.borrow().wanted {
+                        keep_screen_on(state.clone());
+                    }
+                })
+            });
+            let root = web_sys::window()
+                .and_then(|w| w.document())
+                .and_then(|d| d.document_element());
+            if let Some(root) = root {
+                let _ = root.request_fullscreen();
+            }
+            move || {
+                drop(on_visible);
+                let mut state = state.borrow_mut();
+                state.wanted = false;
+                if let Some(sentinel) = state.sentinel.take() {
+                    let _ = sentinel.release();
+                }
+                if let Some(document) = web_sys::window().and_then(|w| w.document()) {
+                    if document.fullscreen_element().is_some() {
+                        document.exit_fullscreen();
+                    }
+                }
+            }
+        },
+        (),
+    );
+    // The arrow keys, space and enter step through the rows and escape leaves, so a knitter can
+    // keep their hands near the keyboard or use a page-turner pedal.
+    use_effect_with_deps(
+        |(on_next, on_previous, on_exit)| {
+            let (on_next, on_previous, on_exit) =
+                (on_next.clone(), on_previous.clone(), on_exit.clone());
+            let listener = web_sys::window().map(|window| {
+                EventListener::new(&window, "keydown", move |e| {
+                    let Some(e) = e.dyn_ref::<KeyboardEvent>() else {
+                        return;
+                    };
+                    let on = match e.key().as_str() {
+                        "ArrowRight" | "ArrowDown" | " " | "Enter" | "PageDown" => &on_next,
+                        "ArrowLeft" | "ArrowUp" | "PageUp" => &on_previous,
+                        "Escape" => &on_exit,
+                        _ => return,
+                    };
+                    e.prevent_default();
+                    on.emit(());
+                })
+            });
+            move || drop(listener)
+        },
+        (
+            props.on_next.clone(),
+            props.on_previous.clone(),
+            props.on_exit.clone(),
+        ),
+    );
+    let on_previous = props.on_previous.reform(|_: MouseEvent| ());
+    let on_next = props.on_next.reform(|_: MouseEvent| ());
+    let on_exit = props.on_exit.reform(|_: MouseEvent| ());
+
+    html! {
+        <div class="knitting-mode" role="dialog" aria-label="Knitting mode">
+            <p class="knitting-mode-progress">{format!("{} of {} rows done", props.done, props.total)}</p>
+            <p class="knitting-mode-row" aria-live="polite">{
+                props.instruction.clone().unwrap_or_else(|| String::from("All rows done!"))
+            }</p>
+            if let Some(note) = &props.note {
+                <p><em>{note}</em></p>
+            }
+            <div class="knitting-mode-buttons">
+                <button type="button" onclick={on_previous} disabled={props.done == 0}>{"Previous"}</button>
+                <button type="button" onclick={on_next} disabled={props.instruction.is_none()}>{"Next"}</button>
+            </div>
+            <button type="button" onclick={on_exit}>{"Exit knitting mode"}</button>
+            <p class="knitting-mode-keys">{"Keys: → or space for the next row, ← for the previous, Esc to exit"}</p>
+        </div>
+    }
+}
+
+// Asks the browser to keep the screen on. Browsers without the Wake Lock API just let it sleep.
+fn keep_screen_on(state: Rc<RefCell<WakeLockState>>) {
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+    let navigator = window.navigator();
+    if !has_wake_lock(&navigator) {
+        return;
+    }
+    let request = navigator.wake_lock().request(WakeLockType::Screen);
+    spawn_local(async move {
+        let Ok(sentinel) = JsFuture::from(request).await else {
+            return;
+        };
+        let Ok(sentinel) = sentinel.dyn_into::<WakeLockSentinel>() else {
+            return;
+        };
+        let mut state = state.borrow_mut();
+        // Knitting mode may have been closed while waiting:
+        if state.wanted {
+            state.sentinel = Some(sentinel);
+        } else {
+            let _ = sentinel.release();
+        }
+    });
+}
+
+fn has_wake_lock(navigator: &Navigator) -> bool {
+    Reflect::has(navigator, &"wakeLock".into()).unwrap_or(false)
+}
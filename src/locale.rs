@@ -0,0 +1,112 @@
+use crate::units::Unit;
+use serde::{Deserialize, Serialize};
+
+// The language the knitter reads in, which sets how numbers and measurements are written: the
+// decimal separator, and which system a length is given in first. The text itself is in English.
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum Locale {
+    #[default]
+    EnglishUs,
+    EnglishUk,
+    German,
+    French,
+    Spanish,
+    Dutch,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 6] = [
+        Locale::EnglishUs,
+        Locale::EnglishUk,
+        Locale::German,
+        Locale::French,
+        Locale::Spanish,
+        Locale::Dutch,
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Locale::EnglishUs => "en-US",
+            Locale::EnglishUk => "en-GB",
+            Locale::German => "de",
+            Locale::French => "fr",
+            Locale::Spanish => "es",
+            Locale::Dutch => "nl",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Locale::EnglishUs => "English (US)",
+            Locale::EnglishUk => "English (UK)",
+            Locale::German => "Deutsch",
+            Locale::French => "Français",
+            Locale::Spanish => "Español",
+            Locale::Dutch => "Nederlands",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Locale> {
+        Locale::ALL.into_iter().find(|locale| locale.key() == key)
+    }
+
+    // Whether decimals are written with a comma, as "4,5".
+    pub fn decimal_comma(&self) -> bool {
+        !matches!(self, Locale::EnglishUs | Locale::EnglishUk)
+    }
+
+    // Whether a length entered in `unit` is given in centimeters first. Everywhere but the US
+    // measures in them; there, lengths lead with whatever they were entered in.
+    fn metric_first(&self, unit: Unit) -> bool {
+        *self != Locale::EnglishUs || unit != Unit::Inch
+    }
+
+    // `value` with as many decimals as it has, such as "1.25" or "1,25".
+    pub fn number(&self, value: f64) -> String {
+        let text = value.to_string();
+        match self.decimal_comma() {
+            true => text.replace('.', ","),
+            false => text,
+        }
+    }
+
+    // `value` with `places` decimals, such as "4.5" or "4,5".
+    pub fn decimal(&self, value: f64, places: usize) -> String {
+        let text = format!("{:.*}", places, value);
+        match self.decimal_comma() {
+            true => text.replace('.', ","),
+            false => text,
+        }
+    }
+
+    // `value` to one decimal, leaving off a decimal of 0, as "10" but "3.9".
+    pub fn short(&self, value: f64) -> String {
+        let text = self.decimal(value, 1);
+        let zero = match self.decimal_comma() {
+            true => ",0",
+            false => ".0",
+        };
+        text.strip_suffix(zero).map_or(text.clone(), str::to_string)
+    }
+
+    // A length in both systems, such as "10 cm / 3.9 in", so a shared pattern reads anywhere; the
+    // reader's own system comes first. Lengths in units that aren't known are shown as entered.
+    pub fn dual(&self, value: f64, units: Option<Unit>, label: &str) -> String {
+        let Some(unit) = units else {
+            return format!("{} {}", self.short(value), label);
+        };
+        let other = unit.other();
+        let both = [(value, unit), (Unit::convert(value, unit, other), other)];
+        let [first, second] = match (unit == Unit::Inch) == self.metric_first(unit) {
+            true => [both[1], both[0]],
+            false => both,
+        };
+        format!(
+            "{} {} / {} {}",
+            self.short(first.0),
+            first.1.abbreviation(),
+            self.short(second.0),
+            second.1.abbreviation()
+        )
+    }
+}
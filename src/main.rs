@@ -1,229 +1,171 @@
-use log::info;
-use rand::prelude::*;
-use std::iter::zip;
-use web_sys::HtmlInputElement;
+mod abbreviations;
+mod bobbles;
+#[cfg(all(test, target_arch = "wasm32"))]
+mod browser_tests;
+mod cables;
+mod capacity;
+mod colorwork;
+mod cost;
+mod counts;
+mod designer;
+mod diagnostics;
+mod diff;
+mod dual_craft;
+mod embed;
+mod explain;
+mod export;
+mod fair_isle;
+mod finishing;
+mod fit;
+mod font;
+mod form;
+mod gauge;
+mod gauge_compare;
+mod generator;
+mod gradient;
+mod grading;
+mod held_double;
+mod history;
+mod knitting_mode;
+mod locale;
+mod metadata;
+mod motifs;
+mod needle_inventory;
+mod needles;
+mod number_input;
+mod numbering;
+mod offline;
+mod pairs;
+mod palette;
+mod panels;
+mod params;
+mod parse;
+mod pattern;
+mod pattern_view;
+mod phrasing;
+mod png;
+mod presets;
+mod preview;
+mod projects;
+mod ravelry;
+mod recent;
+mod resume;
+mod ribbing;
+mod row_tracker;
+mod scale;
+mod self_striping;
+mod shapes;
+mod share;
+mod shared_view;
+mod sizing;
+mod skill;
+mod split;
+mod stats;
+mod stitch_table;
+mod stitchlib;
+mod storage;
+mod stripes;
+mod stuffing;
+mod tally;
+mod tension;
+mod theme;
+mod timing;
+mod units;
+mod validate;
+mod web_share;
+mod yarn;
+
+use embed::HeightReporter;
+use generator::Generator;
+use offline::OfflineSupport;
+use shapes::Route;
+use shared_view::SharedView;
+use theme::{ThemeContext, ThemePicker};
 use yew::prelude::*;
+use yew_router::prelude::*;
 
-pub enum SphereMessage {
-    SetUnits(String),
-    SetDiameter(Option<f64>),
-    SetStitchesPerUnit(Option<f64>),
-    SetRowsPerUnit(Option<f64>),
-}
-
-pub struct SphereComponent {
-    units: String,
-    diameter: Option<f64>,
-    stitches_per_unit: Option<f64>,
-    rows_per_unit: Option<f64>,
-}
-
-impl Component for SphereComponent {
-    type Message = SphereMessage;
-    type Properties = ();
-
-    fn create(_ctx: &yew::Context<Self>) -> Self {
-        Self {
-            units: String::from("in"),
-            diameter: None,
-            stitches_per_unit: None,
-            rows_per_unit: None,
-        }
-    }
-    fn view(&self, ctx: &yew::Context<Self>) -> Html {
-        let on_input = ctx.link().callback(move |e: InputEvent| {
-            let input_el: HtmlInputElement = e.target_unchecked_into();
-            let units = input_el.value();
-            SphereMessage::SetUnits(units)
-        });
-
-        let on_diam_input = ctx.link().callback(move |e: InputEvent| {
-            let input_el: HtmlInputElement = e.target_unchecked_into();
-            let diameter = input_el.value().parse().ok();
-            SphereMessage::SetDiameter(diameter)
-        });
-        let on_st_per_u_input = ctx.link().callback(move |e: InputEvent| {
-            let input_el: HtmlInputElement = e.target_unchecked_into();
-            let st_per_u = input_el.value().parse().ok();
-            SphereMessage::SetStitchesPerUnit(st_per_u)
-        });
-        let on_row_per_u_input = ctx.link().callback(move |e: InputEvent| {
-            let input_el: HtmlInputElement = e.target_unchecked_into();
-            let row_per_u = input_el.value().parse().ok();
-            SphereMessage::SetRowsPerUnit(row_per_u)
-        });
-
-        let mut instructions = Vec::<Html>::new();
-        let pattern = if let (Some(diameter), Some(stitches_per_unit), Some(rows_per_unit)) =
-            (&self.diameter, &self.stitches_per_unit, &self.rows_per_unit)
-        {
-            generate_instructions_for_sphere(
-                diameter,
-                rows_per_unit,
-                stitches_per_unit,
-                &mut instructions,
-            );
-            html! {
-                <div>
-                    <h1>{"Pattern"}</h1>
-                    <ul>{instructions}</ul>
-                </div>
-            }
-        } else {
-            html!{<div/>}
-        };
-
-        html! {
-        <div>
-            <div>
-                <span>
-                    <h3>{"Sphere Size"}</h3>
-                    <span>
-                        <label>{"Units: "}</label>
-                        <input type="text" placeholder="Units (in, cm)" oninput={on_input} value={self.units.clone()}/>
-                    </span>
-                    <span>
-                        <label>{"Diameter: "}</label>
-                        <input type="number" placeholder="Diameter of sphere" oninput={on_diam_input}/>
-                    </span>
-                </span>
-                <span>
-                    <h3>{"Gauge"}</h3>
-                    <span>
-                        <label>{format!("Stitches/{}: ", &self.units)}</label>
-                        <input type="number" placeholder="Stitch count" oninput={on_st_per_u_input}/>
-                    </span>
-                    <span>
-                        <label>{format!("Rows/{}: ", &self.units)}</label>
-                        <input type="number" placeholder="Row count" oninput={on_row_per_u_input}/>
-                    </span>
-                </span>
-            </div>
-
-            { pattern }
-        </div>
-        }
-    }
-
-    fn update(&mut self, _ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
-        match msg {
-            SphereMessage::SetUnits(val) => {
-                self.units = val;
-                true
-            }
-            SphereMessage::SetDiameter(val) => {
-                self.diameter = val;
-                true
-            }
-            SphereMessage::SetStitchesPerUnit(val) => {
-                self.stitches_per_unit = val;
-                true
-            }
-            SphereMessage::SetRowsPerUnit(val) => {
-                self.rows_per_unit = val;
-                true
-            }
-        }
-    }
-}
-
-fn generate_instructions_for_sphere(
-    diameter: &f64,
-    rows_per_unit: &f64,
-    stitches_per_unit: &f64,
-    instructions: &mut Vec<Html>,
-) {
-    let r = diameter / 2.0;
-    let pi = std::f64::consts::PI;
-    let mut rng = StdRng::seed_from_u64(123);
-
-    let circle_dist = 2.0 * pi * r / 4.0;
-    let rough_rows_in_hemisphere = circle_dist * rows_per_unit;
-    let row_pairs_in_hemisphere = (rough_rows_in_hemisphere / 2.0).ceil() as i32;
-
-    let rows = 1..=row_pairs_in_hemisphere;
-    let per_row_pair_angle = (pi / 2.0) / f64::from(row_pairs_in_hemisphere);
-
-    let angles: Vec<f64> = rows.map(|x| f64::from(x) * per_row_pair_angle).collect();
-    let radius_of_row: Vec<f64> = angles.iter().map(|a| r * f64::sin(*a)).collect();
-    let row_length: Vec<f64> = radius_of_row.iter().map(|r| 2.0 * pi * r).collect();
-    let stitch_count: Vec<f64> = row_length.iter().map(|rl| stitches_per_unit * rl).collect();
-    let stitch_count_int: Vec<i32> = stitch_count.iter().map(|c| c.round() as i32).collect();
-
-    // Copy the sequence and delete one element to shift:
-    let d1 = stitch_count_int.clone();
-    let mut d2 = stitch_count_int.clone();
-    d2.remove(0);
-    // diff will be x_i - x_{i-1}. Start it with None since first element has no diff:
-    let mut diff: Vec<Option<i32>> = zip(d1, d2).map(|(x, y)| Some(y - x)).collect();
-    diff.insert(0, None);
-
-    for (i, (count, inc_by)) in zip(stitch_count_int, diff).enumerate() {
-        match inc_by {
-            None => {
-                instructions
-                    .push(html! {<div>{format!("Row 1: Cast on {} stitches", count)}</div>});
-                instructions.push(html! {<div>{format!("Row 2: k{}", count)}</div>});
+#[function_component(App)]
+pub fn app() -> Html {
+    let theme = use_state(storage::load_theme);
+    let embedded = *use_state(embed::requested);
+    let context = ThemeContext {
+        theme: *theme,
+        set_theme: {
+            let theme = theme.clone();
+            Callback::from(move |new_theme| {
+                storage::save_theme(new_theme);
+                theme.set(new_theme);
+            })
+        },
+    };
+    // The stylesheet takes its colors from the theme set on the root element, so the page
+    // background, previews and printouts all follow it.
+    use_effect_with_deps(
+        |theme| {
+            let root = web_sys::window()
+                .and_then(|w| w.document())
+                .and_then(|d| d.document_element());
+            if let Some(root) = root {
+                let _ = root.set_attribute("data-theme", theme.key());
             }
-            Some(inc) => {
-                instructions.push(generate_row_instruction(inc, count, &mut rng, i));
-                instructions.push(html! {<div>{format!("Row {}: k{}", 2*i+2, count)}</div>});
+        },
+        *theme,
+    );
+    // Embedded in another site, the stylesheet drops the margins and fits the frame.
+    use_effect_with_deps(
+        |embedded| {
+            let root = web_sys::window()
+                .and_then(|w| w.document())
+                .and_then(|d| d.document_element());
+            if let (Some(root), true) = (root, *embedded) {
+                let _ = root.set_attribute("data-layout", "embed");
             }
-        }
+        },
+        embedded,
+    );
+
+    // Only the form and the pattern, for yarn shops to put in an iframe on their own sites:
+    if embedded {
+        return html! {
+            <ContextProvider<ThemeContext> {context}>
+                <main>
+                    <HeightReporter />
+                    <HashRouter>
+                        <Switch<Route> render={switch} />
+                    </HashRouter>
+                    if let Some(url) = embed::full_app_url() {
+                        <footer>
+                            <a href={url} target="_blank" rel="noopener">{ "Open in the Knitting Pattern Generator" }</a>
+                        </footer>
+                    }
+                </main>
+            </ContextProvider<ThemeContext>>
+        };
     }
-}
 
-fn generate_row_instruction(inc: i32, count: i32, rng: &mut StdRng, i: usize) -> Html {
-    if inc + inc == count {
-        return html! {<div>{format!("Row {}: *k1,inc rep from * to end (total of {} inc, {} st total)", 2*i+1, inc, count)}</div>};
-    } else if inc > 1 {
-        // Row with increases
-        // Divide in to roughly even blocks of knitting which will have increases between them:
-        let blocks = inc + 1;
-        // Figure out how many stitches in each block *before* the increases happen:
-        let block_sizes = f64::floor((f64::from(count - inc)) / f64::from(blocks)) as i32;
-        // Since we use floor, we rounded down so we may have a few stitches left after the blocks:
-        let rem = count - (blocks * block_sizes + inc);
-        // We don't want to start everything inc at the same place or we end up with too much of a pattern
-        // so pick a random amount to put at the beginning:
-        let before_st = rng.gen_range(0..(rem + block_sizes));
-        // Figure out how many stitches that leaves at the end:
-        let after_st = rem + 2*block_sizes - before_st;
-        let instruction = format!("Row {}: k{} st, inc, * k{}, inc, rep from * {} times, k{} (total of {} inc, {} st total)",
-                                              2*i+1,before_st,     block_sizes,        blocks-2,  after_st,    inc,    count);
-        info!("before_st: {}, inc: {}, count: {}", count-inc, inc, count);
-        info!("total st: {}, total incs: {}", before_st+block_sizes*(blocks-2)+after_st, 1+(blocks-2));
-        info!(
-            "{} --- block_sizes={}, rem={}, before_st={}, blocks={}, count={}, sum={}",
-            instruction,
-            block_sizes,
-            rem,
-            before_st,
-            blocks,
-            count,
-            before_st + 1 + (block_sizes + 1) * (blocks - 1) + after_st
-        );
-        info!("End");
-        return html! {<div>{instruction}</div>};
-    } else if inc == 1 {
-        // Row without significant increases
-        return html! {<div>{format!("Row {}: Knit, inc. by total of {} st for total of {} st in row", 2*i+1, inc, count)}</div>};
-    } else {
-        return html! {<div>{format!("Row {}: k{}", 2*i+1, count)}</div>};
+    html! {
+        <ContextProvider<ThemeContext> {context}>
+            <main>
+                <h1>{ "Knitting Pattern Generator" }</h1>
+                <OfflineSupport />
+                <ThemePicker />
+                <HashRouter>
+                    <Switch<Route> render={switch} />
+                </HashRouter>
+                <footer>
+                    <br/><br/>
+                    <a href={"https://github.com/dmcallas/knitting-pattern-generator/"}>{ "View the code on Github" }</a>
+                </footer>
+            </main>
+        </ContextProvider<ThemeContext>>
     }
 }
 
-#[function_component(App)]
-pub fn app() -> Html {
-    html! {
-        <main>
-            <h1>{ "Sphere Pattern Generator" }</h1>
-            <p><SphereComponent /></p>
-            <footer>
-                <br/><br/>
-                <a href={"https://github.com/dmcallas/knitting-pattern-generator/"}>{ "View the code on Github" }</a>
-            </footer>
-        </main>
+// A shared link opens on its own without the form; every other route is a tab of the generator.
+fn switch(route: Route) -> Html {
+    match route {
+        Route::View => html! { <SharedView /> },
+        _ => html! { <Generator /> },
     }
 }
 
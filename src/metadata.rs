@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+
+// The terms a designer publishes a pattern under.
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum License {
+    #[default]
+    AllRightsReserved,
+    PersonalUse,
+    CcBy,
+    CcBySa,
+    CcByNc,
+    CcByNcSa,
+}
+
+impl License {
+    pub const ALL: [License; 6] = [
+        License::AllRightsReserved,
+        License::PersonalUse,
+        License::CcBy,
+        License::CcBySa,
+        License::CcByNc,
+        License::CcByNcSa,
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            License::AllRightsReserved => "all-rights-reserved",
+            License::PersonalUse => "personal-use",
+            License::CcBy => "cc-by",
+            License::CcBySa => "cc-by-sa",
+            License::CcByNc => "cc-by-nc",
+            License::CcByNcSa => "cc-by-nc-sa",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            License::AllRightsReserved => "All rights reserved",
+            License::PersonalUse => "Personal use only",
+            License::CcBy => "CC BY 4.0",
+            License::CcBySa => "CC BY-SA 4.0",
+            License::CcByNc => "CC BY-NC 4.0",
+            License::CcByNcSa => "CC BY-NC-SA 4.0",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<License> {
+        License::ALL.into_iter().find(|l| l.key() == key)
+    }
+
+    // What the license allows, in a sentence for the top of the pattern.
+    pub fn terms(&self, author: &str) -> String {
+        let author = match author.trim() {
+            "" => "the designer",
+            author => author,
+        };
+        let creative_commons = |name: &str, allows: &str, path: &str| {
+            format!(
+                "Licensed under Creative Commons {} 4.0: {}, crediting {}. https://creativecommons.org/licenses/{}/4.0/",
+                name, allows, author, path
+            )
+        };
+        match self {
+            License::AllRightsReserved => format!("Copyright {}. All rights reserved.", author),
+            License::PersonalUse => format!(
+                "Copyright {}. For personal use only: knit from it for yourself and as gifts, but don't share or sell the pattern or sell what is made from it.",
+                author
+            ),
+            License::CcBy => creative_commons(
+                "Attribution",
+                "share and adapt it for any purpose, even commercially",
+                "by",
+            ),
+            License::CcBySa => creative_commons(
+                "Attribution-ShareAlike",
+                "share and adapt it for any purpose, under these same terms",
+                "by-sa",
+            ),
+            License::CcByNc => creative_commons(
+                "Attribution-NonCommercial",
+                "share and adapt it, but not commercially",
+                "by-nc",
+            ),
+            License::CcByNcSa => creative_commons(
+                "Attribution-NonCommercial-ShareAlike",
+                "share and adapt it, not commercially and under these same terms",
+                "by-nc-sa",
+            ),
+        }
+    }
+}
+
+// Who made a pattern and on what terms, written at the top of the pattern and every export.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Metadata {
+    pub title: String,
+    pub author: String,
+    pub license: License,
+}
+
+impl Metadata {
+    // The lines that head the pattern: title, designer and license. None until a title or designer
+    // is given, since a pattern for the knitter's own use needs no notice.
+    pub fn heading(&self) -> Option<Vec<String>> {
+        let (title, author) = (self.title.trim(), self.author.trim());
+        if title.is_empty() && author.is_empty() {
+            return None;
+        }
+        let mut lines = Vec::new();
+        if !title.is_empty() {
+            lines.push(title.to_string());
+        }
+        if !author.is_empty() {
+            lines.push(format!("by {}", author));
+        }
+        lines.push(self.license.terms(author));
+        Some(lines)
+    }
+}
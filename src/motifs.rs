@@ -0,0 +1,250 @@
+use crate::colorwork::{
+    chart_view, colorwork_instructions, duplicate_stitch_instructions, Chart, YARN_COLORS,
+};
+use crate::font;
+use crate::gauge::Gauge;
+use crate::locale::Locale;
+use crate::palette::Palette;
+use crate::parse::{parse_length, parse_number};
+use crate::pattern::{color_name, Pattern};
+use crate::units::Unit;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Motif {
+    Heart,
+    Star,
+    Diamond,
+    Tree,
+    Kiss,
+    Hug,
+    // Letters typed in, set in the stitch alphabet.
+    Text,
+}
+
+impl Motif {
+    pub const ALL: [Motif; 7] = [
+        Motif::Heart,
+        Motif::Star,
+        Motif::Diamond,
+        Motif::Tree,
+        Motif::Kiss,
+        Motif::Hug,
+        Motif::Text,
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Motif::Heart => "heart",
+            Motif::Star => "star",
+            Motif::Diamond => "diamond",
+            Motif::Tree => "tree",
+            Motif::Kiss => "x",
+            Motif::Hug => "o",
+            Motif::Text => "text",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Motif::Heart => "Heart",
+            Motif::Star => "Star",
+            Motif::Diamond => "Diamond",
+            Motif::Tree => "Tree",
+            Motif::Kiss => "Letter X",
+            Motif::Hug => "Letter O",
+            Motif::Text => "Text",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Motif> {
+        Motif::ALL.into_iter().find(|m| m.key() == key)
+    }
+
+    // The chart, from the top, one character per stitch; `text` is only used by `Motif::Text`.
+    pub fn picture(&self, text: &str) -> Vec<String> {
+        let picture: &[&str] = match self {
+            Motif::Text => return font::render(text),
+            Motif::Heart => &[
+                ".##.##.", "#######", "#######", ".#####.", "..###..", "...#...",
+            ],
+            Motif::Star => &[
+                "...#...", "...#...", "#######", ".#####.", "..###..", ".##.##.", "#.....#",
+            ],
+            Motif::Diamond => &[
+                "...#...", "..###..", ".#####.", "#######", ".#####.", "..###..", "...#...",
+            ],
+            Motif::Tree => &[
+                "...#...", "..###..", ".#####.", "..###..", ".#####.", "#######", "...#...",
+                "...#...",
+            ],
+            Motif::Kiss => &[
+                "#.....#", ".#...#.", "..#.#..", "...#...", "..#.#..", ".#...#.", "#.....#",
+            ],
+            Motif::Hug => &[
+                ".#####.", "#.....#", "#.....#", "#.....#", "#.....#", "#.....#", ".#####.",
+            ],
+        };
+        picture.iter().map(|line| line.to_string()).collect()
+    }
+}
+
+// Where a motif goes: its bottom edge this far up the piece, centered this many degrees around
+// from the start of the round.
+#[derive(Clone, PartialEq)]
+struct Placement {
+    motif: Motif,
+    // For `Motif::Text`.
+    text: String,
+    height: String,
+    around: String,
+    color: usize,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct MotifsProps {
+    pub pattern: Pattern,
+    pub gauge: Gauge,
+    pub unit_label: String,
+    pub locale: Locale,
+    pub palette: Palette,
+}
+
+// Places motifs from the library on the piece, to be knitted in as colorwork or embroidered on
+// afterwards in duplicate stitch.
+#[function_component(Motifs)]
+pub fn motifs(props: &MotifsProps) -> Html {
+    let placements = use_state(Vec::<Placement>::new);
+    let duplicate_stitch = use_state(|| false);
+
+    let units = Unit::from_label(&props.unit_label);
+    let base = Chart::from_pattern(&props.pattern);
+    let mut chart = base.clone();
+    for placement in placements.iter() {
+        let height = parse_length(&placement.height, units, props.locale)
+            .unwrap_or(0.0)
+            .max(0.0);
+        let around = parse_number(&placement.around, props.locale).unwrap_or(0.0) / 360.0;
+        let bottom = (height * props.gauge.rows_per_unit).round() as usize;
+        let picture = placement.motif.picture(&placement.text);
+        chart.stamp(&picture, bottom, around, placement.color);
+    }
+
+    let update = |i: usize, change: fn(&mut Placement, String)| {
+        let placements = placements.clone();
+        move |value: String| {
+            let mut list = (*placements).clone();
+            change(&mut list[i], value);
+            placements.set(list);
+        }
+    };
+    let rows = placements.iter().enumerate().map(|(i, placement)| {
+        let on_motif = update(i, |p, key| {
+            if let Some(motif) = Motif::from_key(&key) {
+                p.motif = motif;
+            }
+        });
+        let on_motif = Callback::from(move |e: Event| {
+            let select_el: HtmlSelectElement = e.target_unchecked_into();
+            on_motif(select_el.value());
+        });
+        let on_color = update(i, |p, color| p.color = color.parse().unwrap_or(p.color));
+        let on_color = Callback::from(move |e: Event| {
+            let select_el: HtmlSelectElement = e.target_unchecked_into();
+            on_color(select_el.value());
+        });
+        let on_text = update(i, |p, text| p.text = text);
+        let on_text = Callback::from(move |e: InputEvent| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            on_text(input_el.value());
+        });
+        let on_height = update(i, |p, height| p.height = height);
+        let on_height = Callback::from(move |e: InputEvent| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            on_height(input_el.value());
+        });
+        let on_around = update(i, |p, around| p.around = around);
+        let on_around = Callback::from(move |e: InputEvent| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            on_around(input_el.value());
+        });
+        let on_remove = {
+            let placements = placements.clone();
+            Callback::from(move |_: MouseEvent| {
+                let mut list = (*placements).clone();
+                list.remove(i);
+                placements.set(list);
+            })
+        };
+        let motifs = Motif::ALL.iter().map(|motif| {
+            html! {
+                <option value={motif.key()} selected={*motif == placement.motif}>{motif.name()}</option>
+            }
+        });
+        let colors = (1..YARN_COLORS.len()).map(|color| {
+            html! {
+                <option value={color.to_string()} selected={color == placement.color}>
+                    {format!("Color {}", color_name(color))}
+                </option>
+            }
+        });
+        html! {
+            <li>
+                <select aria-label="Motif" onchange={on_motif}>{ for motifs }</select>
+                if placement.motif == Motif::Text {
+                    <input aria-label="Motif text" type="text" size="8" placeholder="Initials" value={placement.text.clone()} oninput={on_text}/>
+                }
+                <select aria-label="Motif color" onchange={on_color}>{ for colors }</select>
+                <label>{format!(" {} up ", props.unit_label)}</label>
+                <input aria-label={format!("Motif height, {} up", props.unit_label)} type="text" size="4" value={placement.height.clone()} oninput={on_height}/>
+                <label>{" degrees around "}</label>
+                <input aria-label="Motif position, degrees around" type="text" size="4" value={placement.around.clone()} oninput={on_around}/>
+                {" "}
+                <button type="button" onclick={on_remove}>{"Remove"}</button>
+            </li>
+        }
+    });
+    let on_add = {
+        let placements = placements.clone();
+        Callback::from(move |_: MouseEvent| {
+            let mut list = (*placements).clone();
+            list.push(Placement {
+                motif: Motif::Heart,
+                text: String::new(),
+                height: String::from("1"),
+                around: String::from("0"),
+                color: 1,
+            });
+            placements.set(list);
+        })
+    };
+    let on_method = {
+        let duplicate_stitch = duplicate_stitch.clone();
+        Callback::from(move |e: Event| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            duplicate_stitch.set(input_el.checked());
+        })
+    };
+
+    html! {
+        <div class="motifs">
+            <ol>{ for rows }</ol>
+            <p>
+                <button type="button" onclick={on_add}>{"Add motif"}</button>
+                <label>
+                    <input type="checkbox" checked={*duplicate_stitch} onchange={on_method}/>
+                    {" Embroider in duplicate stitch instead of knitting it in"}
+                </label>
+            </p>
+            if !placements.is_empty() {
+                { chart_view(&chart, &props.pattern, &props.palette) }
+                if *duplicate_stitch {
+                    { duplicate_stitch_instructions(&props.pattern, &base, &chart) }
+                } else {
+                    { colorwork_instructions(&props.pattern, &chart) }
+                }
+            }
+        </div>
+    }
+}
@@ -0,0 +1,300 @@
+use crate::gauge::Gauge;
+use crate::locale::Locale;
+use crate::params::Params;
+use crate::parse::{parse_length, parse_number};
+use crate::pattern::Pattern;
+use crate::units::Unit;
+use serde::{Deserialize, Serialize};
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+// The shortest circular needle whose cable can be pulled out into a loop for small circumferences.
+const MAGIC_LOOP_CM: f64 = 80.0;
+
+#[derive(Clone, Copy, Default, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub enum NeedleKind {
+    #[default]
+    Circular,
+    Dpn,
+}
+
+impl NeedleKind {
+    pub const ALL: [NeedleKind; 2] = [NeedleKind::Circular, NeedleKind::Dpn];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            NeedleKind::Circular => "circular",
+            NeedleKind::Dpn => "dpn",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            NeedleKind::Circular => "Circular",
+            NeedleKind::Dpn => "Double-pointed",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<NeedleKind> {
+        NeedleKind::ALL.into_iter().find(|k| k.key() == key)
+    }
+}
+
+// A needle the knitter owns. Circulars are measured tip to tip, double-pointed needles end to end.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct OwnedNeedle {
+    pub kind: NeedleKind,
+    pub size_mm: f64,
+    pub length_cm: f64,
+}
+
+impl OwnedNeedle {
+    pub fn describe(&self, locale: Locale) -> String {
+        let kind = match self.kind {
+            NeedleKind::Circular => "circular",
+            NeedleKind::Dpn => "double-pointed needles",
+        };
+        format!(
+            "{} mm {} cm {}",
+            locale.short(self.size_mm),
+            locale.short(self.length_cm),
+            kind
+        )
+    }
+
+    // How the needle works a round of `circumference` cm, or None if it can't. A circular needle
+    // needs the stitches to reach around it, unless it is long enough for a magic loop; double-
+    // pointed needles hold about their own length of fabric on each of three.
+    fn in_the_round(&self, circumference: f64) -> Option<&'static str> {
+        match self.kind {
+            NeedleKind::Circular if circumference >= self.length_cm => Some("straight around"),
+            NeedleKind::Circular if self.length_cm >= MAGIC_LOOP_CM => Some("by magic loop"),
+            NeedleKind::Circular => None,
+            NeedleKind::Dpn => (circumference <= 3.0 * self.length_cm).then_some(""),
+        }
+    }
+
+    // Whether the needle holds a flat row `width` cm wide, bunched up on the cable of a circular.
+    fn holds_flat(&self, width: f64) -> bool {
+        match self.kind {
+            NeedleKind::Circular => width <= 2.0 * self.length_cm,
+            NeedleKind::Dpn => width <= self.length_cm,
+        }
+    }
+}
+
+// The needle sizes in mm usually used for a stockinette gauge of `per_10cm` stitches, from the
+// Craft Yarn Council's ranges for each yarn weight.
+fn usual_sizes(per_10cm: f64) -> (f64, f64) {
+    match per_10cm {
+        g if g >= 33.0 => (1.5, 2.25),
+        g if g >= 27.0 => (2.25, 3.25),
+        g if g >= 23.0 => (3.25, 3.75),
+        g if g >= 21.0 => (3.75, 4.5),
+        g if g >= 16.0 => (4.5, 5.5),
+        g if g >= 12.0 => (5.5, 8.0),
+        g if g >= 7.0 => (8.0, 12.75),
+        _ => (12.75, 25.0),
+    }
+}
+
+// Runs of consecutive rows, by index, for which `suits` holds, each with the way it is worked.
+fn runs(
+    pattern: &Pattern,
+    suits: impl Fn(i32) -> Option<&'static str>,
+) -> Vec<(usize, usize, &'static str)> {
+    let mut runs: Vec<(usize, usize, &'static str)> = Vec::new();
+    for (i, row) in pattern.rows.iter().enumerate() {
+        match (suits(row.stitches), runs.last_mut()) {
+            (Some(how), Some(last)) if last.1 + 1 == i && last.2 == how => last.1 = i,
+            (Some(how), _) => runs.push((i, i, how)),
+            (None, _) => {}
+        }
+    }
+    runs
+}
+
+// Rows `first` to `last` by index, as "rounds 1 to 12".
+fn row_range(pattern: &Pattern, first: usize, last: usize) -> String {
+    let label = |index: usize| match pattern.label(index) {
+        label if label.is_empty() => String::from("the cast on"),
+        label => label.to_lowercase(),
+    };
+    let shown = |index: usize| pattern.numbering.shown(pattern.rows[index].number);
+    match (shown(first), shown(last)) {
+        _ if first == last => label(first),
+        (Some(first), Some(last)) => {
+            format!("{}s {} to {}", pattern.word().to_lowercase(), first, last)
+        }
+        _ => format!("{} to {}", label(first), label(last)),
+    }
+}
+
+// Which of the knitter's needles suit the pattern, for the materials: those of about the usual size
+// for the gauge, with the rows each can work given the circumference or width of the fabric on it.
+// Nothing for crochet, or when the units aren't known.
+pub fn recommend(
+    needles: &[OwnedNeedle],
+    pattern: &Pattern,
+    params: &Params,
+    gauge: Gauge,
+) -> Vec<String> {
+    let Some(units) = params.units() else {
+        return Vec::new();
+    };
+    if needles.is_empty() || params.fabric.crochet().is_some() || pattern.rows.is_empty() {
+        return Vec::new();
+    }
+    let per_cm = gauge.stitches_per_unit * Unit::convert(1.0, Unit::Centimeter, units);
+    let (smallest, largest) = usual_sizes(per_cm * 10.0);
+    let sizes = format!(
+        "{} to {} mm",
+        params.locale.short(smallest),
+        params.locale.short(largest)
+    );
+    let sized = needles
+        .iter()
+        .filter(|needle| needle.size_mm >= smallest - 0.5 && needle.size_mm <= largest + 0.5);
+    let flat = pattern.is_flat();
+    let lines: Vec<String> = sized
+        .filter(|needle| !pattern.two_at_a_time || needle.length_cm >= MAGIC_LOOP_CM)
+        .filter_map(|needle| {
+            let runs = runs(pattern, |stitches| {
+                let fabric = f64::from(stitches) / per_cm;
+                match flat {
+                    true => needle.holds_flat(fabric).then_some(""),
+                    false if pattern.two_at_a_time => Some("by magic loop"),
+                    false => needle.in_the_round(fabric),
+                }
+            });
+            let whole = runs.len() == 1 && runs[0].0 == 0 && runs[0].1 == pattern.rows.len() - 1;
+            let rows: Vec<String> = runs
+                .iter()
+                .map(|(first, last, how)| {
+                    let rows = match whole {
+                        true => String::from("the whole piece"),
+                        false => row_range(pattern, *first, *last),
+                    };
+                    format!("{} {}", rows, how).trim_end().to_string()
+                })
+                .collect();
+            (!rows.is_empty()).then(|| {
+                format!(
+                    "Your {}: {}",
+                    needle.describe(params.locale),
+                    rows.join(", ")
+                )
+            })
+        })
+        .collect();
+    match lines.is_empty() {
+        true => vec![format!(
+            "None of your needles suit this piece; this gauge is usually worked on {}",
+            sizes
+        )],
+        false => lines,
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct NeedleInventoryProps {
+    pub needles: Vec<OwnedNeedle>,
+    pub locale: Locale,
+    pub on_change: Callback<Vec<OwnedNeedle>>,
+}
+
+// The circular and double-pointed needles the knitter owns, kept in this browser, so the materials
+// can say which of them to use.
+#[function_component(NeedleInventoryPanel)]
+pub fn needle_inventory_panel(props: &NeedleInventoryProps) -> Html {
+    let kind = use_state(NeedleKind::default);
+    let size = use_state(String::new);
+    let length = use_state(String::new);
+    let on_input = |state: &UseStateHandle<String>| {
+        let state = state.clone();
+        Callback::from(move |e: InputEvent| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            state.set(input_el.value());
+        })
+    };
+    let on_kind = {
+        let kind = kind.clone();
+        Callback::from(move |e: Event| {
+            let select_el: HtmlSelectElement = e.target_unchecked_into();
+            if let Some(chosen) = NeedleKind::from_key(&select_el.value()) {
+                kind.set(chosen);
+            }
+        })
+    };
+    // Lengths may be entered in inches, as needles are sold in the US:
+    let new_needle = match (
+        parse_number(&size, props.locale)
+            .ok()
+            .filter(|size| *size > 0.0),
+        parse_length(&length, Some(Unit::Centimeter), props.locale)
+            .ok()
+            .filter(|length| *length > 0.0),
+    ) {
+        (Some(size_mm), Some(length_cm)) => Some(OwnedNeedle {
+            kind: *kind,
+            size_mm,
+            length_cm: (length_cm * 10.0).round() / 10.0,
+        }),
+        _ => None,
+    };
+    let on_add = {
+        let (needles, on_change) = (props.needles.clone(), props.on_change.clone());
+        let (size, length) = (size.clone(), length.clone());
+        Callback::from(move |_: MouseEvent| {
+            let Some(needle) = new_needle else {
+                return;
+            };
+            let mut needles = needles.clone();
+            needles.push(needle);
+            needles.sort_by(|a, b| {
+                (a.kind, a.size_mm, a.length_cm)
+                    .partial_cmp(&(b.kind, b.size_mm, b.length_cm))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+            on_change.emit(needles);
+            size.set(String::new());
+            length.set(String::new());
+        })
+    };
+    let owned = props.needles.iter().enumerate().map(|(i, needle)| {
+        let (needles, on_change) = (props.needles.clone(), props.on_change.clone());
+        let on_delete = Callback::from(move |_: MouseEvent| {
+            let mut needles = needles.clone();
+            needles.remove(i);
+            on_change.emit(needles);
+        });
+        html! {
+            <li>
+                {format!("{} ", needle.describe(props.locale))}
+                <button type="button" onclick={on_delete}>{"Remove"}</button>
+            </li>
+        }
+    });
+
+    html! {
+        <details class="panel">
+            <summary><h3>{"Your needles"}</h3></summary>
+            if props.needles.is_empty() {
+                <p>{"Add the needles you own, and the materials will say which of them suit each pattern."}</p>
+            } else {
+                <ul>{ for owned }</ul>
+            }
+            <span>
+                <select aria-label="Needle kind" onchange={on_kind}>
+                    { for NeedleKind::ALL.iter().map(|k| html! {
+                        <option value={k.key()} selected={*k == *kind}>{k.name()}</option>
+                    }) }
+                </select>
+                <input aria-label="Needle size in mm" type="text" size="4" placeholder="Size (mm)" value={(*size).clone()} oninput={on_input(&size)}/>
+                <input aria-label="Needle length" type="text" size="6" placeholder="Length (cm)" value={(*length).clone()} oninput={on_input(&length)}/>
+                <button type="button" onclick={on_add} disabled={new_needle.is_none()}>{"Add"}</button>
+            </span>
+            <span class="field-note">{"Lengths are in cm unless given as \"16 in\". Kept in this browser only."}</span>
+        </details>
+    }
+}
@@ -0,0 +1,87 @@
+use crate::gauge::Gauge;
+use crate::pattern::Pattern;
+use crate::units::Unit;
+use serde::{Deserialize, Serialize};
+
+const DPNS: &str = "double-pointed needles, divided over 3 or 4 of them";
+
+// The needles the knitter likes to work small circumferences on, for pieces worked in the round.
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum NeedleStyle {
+    #[default]
+    None,
+    Dpn,
+    MagicLoop,
+    ShortCircular,
+}
+
+impl NeedleStyle {
+    pub const ALL: [NeedleStyle; 4] = [
+        NeedleStyle::None,
+        NeedleStyle::Dpn,
+        NeedleStyle::MagicLoop,
+        NeedleStyle::ShortCircular,
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            NeedleStyle::None => "none",
+            NeedleStyle::Dpn => "dpn",
+            NeedleStyle::MagicLoop => "magic-loop",
+            NeedleStyle::ShortCircular => "circular",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            NeedleStyle::None => "No needle notes",
+            NeedleStyle::Dpn => "Double-pointed needles",
+            NeedleStyle::MagicLoop => "Magic loop",
+            NeedleStyle::ShortCircular => "40 cm (16 in) circular",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<NeedleStyle> {
+        NeedleStyle::ALL.into_iter().find(|s| s.key() == key)
+    }
+
+    // The circumference in cm at which to change needles, with the needles for fewer stitches and
+    // for more. A circular needle needs the stitches to reach around it, and double-pointed
+    // needles get crowded past about 15 cm of fabric on each of four.
+    fn plan(&self) -> Option<(f64, &'static str, &'static str)> {
+        match self {
+            NeedleStyle::None => None,
+            NeedleStyle::Dpn => Some((60.0, DPNS, "a 40 cm (16 in) circular needle")),
+            NeedleStyle::MagicLoop => Some((
+                80.0,
+                "an 80 cm (32 in) circular needle by the magic loop method, divided in half with a loop of cable pulled out between them",
+                "an 80 cm (32 in) circular needle, knitting straight around without a loop",
+            )),
+            NeedleStyle::ShortCircular => Some((40.0, DPNS, "a 40 cm (16 in) circular needle")),
+        }
+    }
+}
+
+// Notes on which needles to work `pattern` on: what to cast on with, then a change of needles
+// before each row that starts with too few or too many stitches for the needles in use. Only for
+// pieces worked in the round, whose units are known.
+pub fn apply(pattern: &mut Pattern, style: NeedleStyle, gauge: Gauge, units: Option<Unit>) {
+    let (Some((cm, small, large)), Some(units)) = (style.plan(), units) else {
+        return;
+    };
+    if pattern.is_flat() || pattern.rows.is_empty() {
+        return;
+    }
+    let threshold = Unit::convert(cm, Unit::Centimeter, units) * gauge.stitches_per_unit;
+    let fits_large = |stitches: i32| stitches as f64 >= threshold;
+    let mut large_now = fits_large(pattern.rows[0].stitches);
+    pattern.rows[0].needles = Some(format!("on {}", if large_now { large } else { small }));
+    for i in 1..pattern.rows.len() {
+        let below = pattern.rows[i - 1].stitches;
+        if fits_large(below) != large_now {
+            large_now = !large_now;
+            let tool = if large_now { large } else { small };
+            pattern.rows[i].needles = Some(format!("first move the stitches to {}", tool));
+        }
+    }
+}
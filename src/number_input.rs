@@ -0,0 +1,121 @@
+use crate::locale::Locale;
+use crate::units::Unit;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+// Range and step for the slider and stepper buttons of a `NumberInput`.
+#[derive(Clone, Copy, PartialEq)]
+pub struct Scale {
+    pub min: f64,
+    pub max: f64,
+    pub step: f64,
+}
+
+impl Scale {
+    // Lengths from about an inch up to `max_inches`, converted to the entered units.
+    pub fn for_length(units: Option<Unit>, max_inches: f64) -> Scale {
+        let max = Unit::convert(max_inches, Unit::Inch, units.unwrap_or(Unit::Inch)).round();
+        match units {
+            Some(Unit::Centimeter) => Scale {
+                min: 2.0,
+                max,
+                step: 0.5,
+            },
+            Some(Unit::Millimeter) => Scale {
+                min: 20.0,
+                max,
+                step: 5.0,
+            },
+            _ => Scale {
+                min: 1.0,
+                max,
+                step: 0.25,
+            },
+        }
+    }
+
+    // Gauges within a per-inch range, converted to the entered units.
+    pub fn for_gauge(units: Option<Unit>, per_inch: (f64, f64)) -> Scale {
+        let units_per_inch = Unit::convert(1.0, Unit::Inch, units.unwrap_or(Unit::Inch));
+        let step = match units {
+            Some(Unit::Centimeter) => 0.1,
+            Some(Unit::Millimeter) => 0.01,
+            _ => 0.25,
+        };
+        Scale {
+            min: per_inch.0 / units_per_inch,
+            max: per_inch.1 / units_per_inch,
+            step,
+        }
+    }
+
+    // Formats a value with just enough decimals for this scale's step, as the knitter writes them.
+    fn format(&self, value: f64, locale: Locale) -> String {
+        let stepped = (value / self.step).round() * self.step;
+        locale.number((stepped * 100.0).round() / 100.0)
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct NumberInputProps {
+    pub label: String,
+    pub placeholder: String,
+    // The text as typed, and the value it was read as (if it could be).
+    pub value: String,
+    pub current: Option<f64>,
+    pub scale: Scale,
+    pub locale: Locale,
+    pub oninput: Callback<String>,
+    #[prop_or_default]
+    pub children: Children,
+}
+
+// A text field that accepts anything the parser does, plus a slider and -/+ buttons for nudging it.
+#[function_component(NumberInput)]
+pub fn number_input(props: &NumberInputProps) -> Html {
+    let (scale, locale) = (props.scale, props.locale);
+    let on_text = props.oninput.reform(|e: InputEvent| {
+        let input_el: HtmlInputElement = e.target_unchecked_into();
+        input_el.value()
+    });
+    let on_slide = props.oninput.reform(move |e: InputEvent| {
+        let input_el: HtmlInputElement = e.target_unchecked_into();
+        let value = input_el.value();
+        value
+            .parse()
+            .map_or(value, |slid| scale.format(slid, locale))
+    });
+    let nudge = |direction: f64| {
+        let current = props.current;
+        props.oninput.reform(move |_: MouseEvent| {
+            let start = current.unwrap_or(scale.min - direction * scale.step);
+            scale.format((start + direction * scale.step).max(0.0), locale)
+        })
+    };
+    let slider_value = props
+        .current
+        .unwrap_or(scale.min)
+        .clamp(scale.min, scale.max);
+
+    // The label without its colon, to name the field and its buttons for screen readers.
+    let name = props.label.trim_end_matches([':', ' ']);
+
+    html! {
+        <span class="number-input">
+            <label>{&props.label}</label>
+            <input aria-label={name.to_string()} type="text" placeholder={props.placeholder.clone()} value={props.value.clone()} oninput={on_text}/>
+            <button type="button" aria-label={format!("Less {}", name.to_lowercase())} onclick={nudge(-1.0)}>{"−"}</button>
+            <input
+                aria-label={format!("{} slider", name)}
+                type="range"
+                min={scale.min.to_string()}
+                max={scale.max.to_string()}
+                step={scale.step.to_string()}
+                value={slider_value.to_string()}
+                oninput={on_slide}
+            />
+            <button type="button" aria-label={format!("More {}", name.to_lowercase())} onclick={nudge(1.0)}>{"+"}</button>
+            { for props.children.iter() }
+        </span>
+    }
+}
@@ -0,0 +1,87 @@
+use serde::{Deserialize, Serialize};
+
+// What the pattern calls each row.
+#[derive(Clone, Copy, Default, PartialEq, Hash, Serialize, Deserialize)]
+pub enum RowWord {
+    // Rows for pieces worked flat, rounds for pieces worked in the round.
+    #[default]
+    Auto,
+    Row,
+    Round,
+}
+
+impl RowWord {
+    pub const ALL: [RowWord; 3] = [RowWord::Auto, RowWord::Row, RowWord::Round];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            RowWord::Auto => "auto",
+            RowWord::Row => "row",
+            RowWord::Round => "round",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RowWord::Auto => "Rows flat, rounds in the round",
+            RowWord::Row => "Always rows",
+            RowWord::Round => "Always rounds",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<RowWord> {
+        RowWord::ALL.into_iter().find(|w| w.key() == key)
+    }
+}
+
+// How the rows of a pattern are named and numbered wherever they are written out: instructions,
+// the row tracker, tables, charts and exports. Patterns number their rows from 1 at the cast on
+// internally; this only changes what the knitter sees.
+#[derive(Clone, Copy, Default, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Numbering {
+    pub word: RowWord,
+    // Leave the cast on unnumbered, so the first row worked is row 1.
+    pub skip_cast_on: bool,
+    // Read every chart row from the right, as in the round, even on flat pieces.
+    pub charts_right_to_left: bool,
+}
+
+impl Numbering {
+    // "Row" or "Round", for a piece worked flat or not.
+    pub fn word(&self, flat: bool) -> &'static str {
+        match (self.word, flat) {
+            (RowWord::Row, _) | (RowWord::Auto, true) => "Row",
+            (RowWord::Round, _) | (RowWord::Auto, false) => "Round",
+        }
+    }
+
+    // The number shown for the pattern's row `number`, or None for an unnumbered cast on.
+    pub fn shown(&self, number: usize) -> Option<usize> {
+        match self.skip_cast_on {
+            true => number.checked_sub(1).filter(|shown| *shown > 0),
+            false => Some(number),
+        }
+    }
+
+    // The pattern's row number for a number the knitter entered.
+    pub fn row(&self, shown: usize) -> usize {
+        match self.skip_cast_on {
+            true => shown + 1,
+            false => shown,
+        }
+    }
+
+    // Whether a chart row is read from the right, and so numbered at the right edge. Flat pieces
+    // are read back and forth, right side rows from the right and wrong side rows from the left.
+    pub fn right_to_left(&self, wrong_side: bool) -> bool {
+        self.charts_right_to_left || !wrong_side
+    }
+
+    // Such as "Round 3", or empty for an unnumbered cast on.
+    pub fn label(&self, number: usize, flat: bool) -> String {
+        self.shown(number).map_or(String::new(), |shown| {
+            format!("{} {}", self.word(flat), shown)
+        })
+    }
+}
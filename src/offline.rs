@@ -0,0 +1,113 @@
+use gloo_events::EventListener;
+use log::warn;
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::js_sys::{Function, Reflect};
+use web_sys::wasm_bindgen::{JsCast, JsValue};
+use web_sys::{ServiceWorkerRegistration, ServiceWorkerState};
+use yew::prelude::*;
+
+// Registers the service worker that lets the app work offline, and offers to install the app or
+// reload when a new version has been downloaded.
+#[function_component(OfflineSupport)]
+pub fn offline_support() -> Html {
+    let update_ready = use_state(|| false);
+    // The browser's deferred install prompt, once it says the app can be installed.
+    let install_prompt = use_state(|| None::<JsValue>);
+
+    {
+        let update_ready = update_ready.clone();
+        let install_prompt = install_prompt.clone();
+        use_effect_with_deps(
+            move |_| {
+                register(Callback::from(move |_| update_ready.set(true)));
+                let listener = web_sys::window().map(|window| {
+                    EventListener::new(&window, "beforeinstallprompt", move |e| {
+                        e.prevent_default();
+                        install_prompt.set(Some(e.clone().into()));
+                    })
+                });
+                move || drop(listener)
+            },
+            (),
+        );
+    }
+
+    let on_install = {
+        let install_prompt = install_prompt.clone();
+        Callback::from(move |_: MouseEvent| {
+            if let Some(prompt) = &*install_prompt {
+                let show = Reflect::get(prompt, &"prompt".into())
+                    .ok()
+                    .and_then(|f| f.dyn_into::<Function>().ok());
+                if let Some(show) = show {
+                    let _ = show.call0(prompt);
+                }
+            }
+            // A prompt can only be shown once:
+            install_prompt.set(None);
+        })
+    };
+    let on_reload = Callback::from(|_: MouseEvent| {
+        if let Some(window) = web_sys::window() {
+            let _ = window.location().reload();
+        }
+    });
+
+    html! {
+        <>
+            if *update_ready {
+                <p class="app-notice">
+                    {"A new version of the generator is ready. "}
+                    <button type="button" onclick={on_reload}>{"Reload"}</button>
+                </p>
+            }
+            if install_prompt.is_some() {
+                <p class="app-notice">
+                    {"Install the generator to use it offline. "}
+                    <button type="button" onclick={on_install}>{"Install"}</button>
+                </p>
+            }
+        </>
+    }
+}
+
+// Registers sw.js, calling `on_update` when a newer version has been installed over a running one.
+fn register(on_update: Callback<()>) {
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+    let navigator = window.navigator();
+    if !Reflect::has(&navigator, &"serviceWorker".into()).unwrap_or(false) {
+        return;
+    }
+    let container = navigator.service_worker();
+    let registering = container.register("sw.js");
+    spawn_local(async move {
+        let registration = match JsFuture::from(registering).await {
+            Ok(registration) => registration.unchecked_into::<ServiceWorkerRegistration>(),
+            Err(err) => {
+                warn!("Couldn't register the service worker: {:?}", err);
+                return;
+            }
+        };
+        let watched = registration.clone();
+        EventListener::new(&registration, "updatefound", move |_| {
+            let Some(worker) = watched.installing() else {
+                return;
+            };
+            let installed = worker.clone();
+            let on_update = on_update.clone();
+            EventListener::new(&worker, "statechange", move |_| {
+                // With no controller this is the first install rather than an update:
+                let running = web_sys::window()
+                    .and_then(|w| w.navigator().service_worker().controller())
+                    .is_some();
+                if installed.state() == ServiceWorkerState::Installed && running {
+                    on_update.emit(());
+                }
+            })
+            .forget();
+        })
+        .forget();
+    });
+}
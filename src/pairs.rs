@@ -0,0 +1,35 @@
+use crate::pattern::Pattern;
+
+// A round written for one piece, reworded for working two pieces at once: the cast on is made for
+// each piece from its own ball, and every round is worked on each piece in turn.
+pub fn each_piece(instruction: &str, cast_on: bool) -> String {
+    if cast_on {
+        return format!("{} for each piece, each from its own ball", instruction);
+    }
+    match instruction.split_once(": ") {
+        Some((label, steps)) => format!("{}: on each piece in turn, {}", label, steps),
+        None => format!("On each piece in turn: {}", instruction),
+    }
+}
+
+// How to set up two pieces on one long circular needle by the magic loop method, and work a round
+// across both, before the instructions start.
+pub fn setup(pattern: &Pattern) -> Vec<String> {
+    let word = pattern.word().to_lowercase();
+    vec![
+        String::from(
+            "Both pieces are worked at once on one long circular needle, 80 cm (32 in) or longer, by the magic loop method. Each piece has its own ball of yarn; the yarn amounts are for one piece, so you need the same again for the second.",
+        ),
+        String::from(
+            "Set up: cast on the first piece and divide its stitches in half, one half on each needle tip. Slide it down the cable, then cast on the second piece with the second ball and divide it the same way, so both hang side by side with the working yarns at the same end.",
+        ),
+        format!(
+            "Every {}: work across the front needle of the first piece, then the second, with each piece's own yarn. Turn, pull the cable through, and work the back needle of the second piece, then the first. Keep the balls apart so the yarns don't twist.",
+            word
+        ),
+        format!(
+            "Each {} below is written for one piece; work it on both before moving on to the next.",
+            word
+        ),
+    ]
+}
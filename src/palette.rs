@@ -0,0 +1,117 @@
+use crate::colorwork::YARN_COLORS;
+use crate::pattern::color_name;
+use serde::{Deserialize, Serialize};
+
+// Symbols for yarns A, B, C... in charts, the main color left blank as printed charts do.
+pub const SYMBOLS: [&str; 6] = ["", "×", "●", "▲", "◆", "■"];
+
+// Sets of chart colors for yarns A, B, C...
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum PalettePreset {
+    // The yarn colors, or a picture's colors for Fair Isle.
+    #[default]
+    AsKnitted,
+    // The Okabe-Ito colors, told apart with any common color vision deficiency.
+    ColorblindSafe,
+    HighContrast,
+    // Shades of gray, for printing in black and white.
+    Grayscale,
+}
+
+impl PalettePreset {
+    pub const ALL: [PalettePreset; 4] = [
+        PalettePreset::AsKnitted,
+        PalettePreset::ColorblindSafe,
+        PalettePreset::HighContrast,
+        PalettePreset::Grayscale,
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            PalettePreset::AsKnitted => "yarn",
+            PalettePreset::ColorblindSafe => "colorblind",
+            PalettePreset::HighContrast => "contrast",
+            PalettePreset::Grayscale => "gray",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PalettePreset::AsKnitted => "Yarn colors",
+            PalettePreset::ColorblindSafe => "Colorblind-safe",
+            PalettePreset::HighContrast => "High contrast",
+            PalettePreset::Grayscale => "Grayscale for printing",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<PalettePreset> {
+        PalettePreset::ALL.into_iter().find(|p| p.key() == key)
+    }
+
+    fn colors(&self) -> Option<[&'static str; 6]> {
+        match self {
+            PalettePreset::AsKnitted => None,
+            PalettePreset::ColorblindSafe => Some([
+                "#ffffff", "#0072b2", "#e69f00", "#009e73", "#cc79a7", "#f0e442",
+            ]),
+            PalettePreset::HighContrast => Some([
+                "#ffffff", "#000000", "#d55e00", "#0072b2", "#f0e442", "#7f7f7f",
+            ]),
+            PalettePreset::Grayscale => Some([
+                "#ffffff", "#000000", "#7f7f7f", "#c8c8c8", "#404040", "#a0a0a0",
+            ]),
+        }
+    }
+}
+
+// How yarns are drawn in charts: a preset, any yarn's color or symbol set by hand, and whether
+// the symbols are drawn in the stitches.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Palette {
+    pub preset: PalettePreset,
+    // CSS colors for yarns A, B, C..., empty to use the preset's.
+    pub colors: Vec<String>,
+    // Symbols for yarns A, B, C..., empty to use the usual ones.
+    pub symbols: Vec<String>,
+    pub show_symbols: bool,
+}
+
+impl Palette {
+    // The color to draw `yarn` in, for a chart that would otherwise use `knitted`.
+    pub fn color(&self, yarn: usize, knitted: &[String]) -> String {
+        let custom = self.colors.get(yarn).filter(|c| !c.is_empty());
+        let preset = self.preset.colors().and_then(|c| c.get(yarn).copied());
+        match (custom, preset) {
+            (Some(custom), _) => custom.clone(),
+            (None, Some(preset)) => preset.to_string(),
+            (None, None) => knitted
+                .get(yarn)
+                .cloned()
+                .unwrap_or_else(|| YARN_COLORS[yarn % YARN_COLORS.len()].to_string()),
+        }
+    }
+
+    pub fn symbol(&self, yarn: usize) -> String {
+        match self.symbols.get(yarn).filter(|s| !s.is_empty()) {
+            Some(symbol) => symbol.clone(),
+            None => SYMBOLS
+                .get(yarn)
+                .map_or_else(|| color_name(yarn), |s| s.to_string()),
+        }
+    }
+
+    // Yarn `yarn`'s color or symbol changed to `value`, padding the lists out to reach it.
+    pub fn with(&self, yarn: usize, value: String, symbol: bool) -> Palette {
+        let mut palette = self.clone();
+        let list = match symbol {
+            true => &mut palette.symbols,
+            false => &mut palette.colors,
+        };
+        if list.len() <= yarn {
+            list.resize(yarn + 1, String::new());
+        }
+        list[yarn] = value;
+        palette
+    }
+}
@@ -0,0 +1,570 @@
+use crate::abbreviations::GLOSSARY;
+use crate::colorwork::YARN_COLORS;
+use crate::finishing::Fiber;
+use crate::form::{field_message, issue_message};
+use crate::gauge::Fabric;
+use crate::generator::GeneratorMessage;
+use crate::held_double;
+use crate::metadata::{License, Metadata};
+use crate::number_input::{NumberInput, Scale};
+use crate::palette::{Palette, PalettePreset};
+use crate::params::Params;
+use crate::pattern::color_name;
+use crate::phrasing::{self, Phrase};
+use crate::stripes::{self, MAX_COLORS};
+use crate::tally::Tally;
+use crate::validate::{Field, Validation, ROWS_PER_INCH, STITCHES_PER_INCH};
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+// The sections of the generator's form below the size. Each reports changes as generator messages
+// and can be collapsed to save space on a small screen.
+
+#[derive(Properties, PartialEq)]
+pub struct GaugePanelProps {
+    pub params: Params,
+    pub validation: Validation,
+    pub on_message: Callback<GeneratorMessage>,
+}
+
+#[function_component(GaugePanel)]
+pub fn gauge_panel(props: &GaugePanelProps) -> Html {
+    let (params, validation) = (&props.params, &props.validation);
+    let on_st_per_u_input = props
+        .on_message
+        .reform(GeneratorMessage::SetStitchesPerUnit);
+    let on_row_per_u_input = props.on_message.reform(GeneratorMessage::SetRowsPerUnit);
+    let on_estimate_toggle = props.on_message.reform(move |e: Event| {
+        let input_el: HtmlInputElement = e.target_unchecked_into();
+        GeneratorMessage::SetEstimateRows(input_el.checked())
+    });
+    let on_fabric_change = {
+        let on_message = props.on_message.clone();
+        Callback::from(move |e: Event| {
+            let select_el: HtmlSelectElement = e.target_unchecked_into();
+            if let Some(fabric) = Fabric::from_key(&select_el.value()) {
+                on_message.emit(GeneratorMessage::SetFabric(fabric));
+            }
+        })
+    };
+    // Each yarn's stitch gauge alone, for working out the gauge of the two held together:
+    let (first, second) = params.held_double.clone().unwrap_or_default();
+    let first = use_state(|| first);
+    let second = use_state(|| second);
+    let on_strand_input = |state: &UseStateHandle<String>| {
+        let state = state.clone();
+        Callback::from(move |e: InputEvent| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            state.set(input_el.value());
+        })
+    };
+    let held = held_double::parse_strands(&first, &second, params.locale);
+    let on_use_held = {
+        let (first, second) = ((*first).clone(), (*second).clone());
+        props.on_message.reform(move |_: MouseEvent| {
+            GeneratorMessage::UseHeldDouble(first.clone(), second.clone())
+        })
+    };
+    let units = params.units();
+    let stitches_per_unit = params.stitches_per_unit();
+    let rows_per_unit = params.rows_per_unit();
+
+    html! {
+        <details class="panel" open=true>
+            <summary><h3>{"Gauge"}</h3></summary>
+            <NumberInput
+                label={format!("Stitches/{}: ", &params.units)}
+                placeholder={"Stitch count"}
+                value={params.stitches_per_unit.clone()}
+                current={stitches_per_unit.as_ref().ok().copied()}
+                scale={Scale::for_gauge(units, STITCHES_PER_INCH)}
+                locale={params.locale}
+                oninput={on_st_per_u_input}
+            >
+                { field_message(&params.stitches_per_unit, &stitches_per_unit, "st", params.locale, validation, Field::StitchesPerUnit) }
+            </NumberInput>
+            if params.estimate_rows {
+                <span>
+                    <label>{format!("Rows/{}: ", &params.units)}</label>
+                    <span>{
+                        match rows_per_unit {
+                            Ok(rows) => format!("{} (estimated)", params.locale.decimal(rows, 1)),
+                            Err(_) => String::from("(estimated)"),
+                        }
+                    }</span>
+                    { for validation.for_field(Field::RowsPerUnit).map(issue_message) }
+                </span>
+            } else {
+                <NumberInput
+                    label={format!("Rows/{}: ", &params.units)}
+                    placeholder={"Row count"}
+                    value={params.rows_per_unit.clone()}
+                    current={rows_per_unit.as_ref().ok().copied()}
+                    scale={Scale::for_gauge(units, ROWS_PER_INCH)}
+                    locale={params.locale}
+                    oninput={on_row_per_u_input}
+                >
+                    { field_message(&params.rows_per_unit, &rows_per_unit, "rows", params.locale, validation, Field::RowsPerUnit) }
+                </NumberInput>
+            }
+            <span>
+                <label>
+                    <input type="checkbox" checked={params.estimate_rows} onchange={on_estimate_toggle}/>
+                    {" Estimate rows from stitches"}
+                </label>
+            </span>
+            <span>
+                <label>{"Fabric: "}</label>
+                <select aria-label="Fabric" onchange={on_fabric_change}>
+                    { for Fabric::ALL.iter().map(|f| html! {
+                        <option value={f.key()} selected={*f == params.fabric}>{f.name()}</option>
+                    }) }
+                </select>
+                if params.fabric == Fabric::Brioche {
+                    <span class="field-note">
+                        {"Brioche is worked on flat panels, each row in two passes of colors A and B; count its rows by the Vs up one column. Other shapes stay in stockinette."}
+                    </span>
+                }
+                if !params.estimate_rows && params.fabric != Fabric::Stockinette {
+                    <span class="field-note">
+                        {format!("Measure the row gauge on a swatch in {}; it changes how tall the shaping comes out.", params.fabric.name().to_lowercase())}
+                    </span>
+                }
+            </span>
+            <details>
+                <summary>{"Holding two yarns together"}</summary>
+                <span>
+                    <label>{format!("First yarn alone, stitches/{}: ", &params.units)}</label>
+                    <input aria-label={format!("First yarn alone, stitches/{}", &params.units)} type="text" size="4" value={(*first).clone()} oninput={on_strand_input(&first)}/>
+                </span>
+                <span>
+                    <label>{format!("Second yarn alone, stitches/{}: ", &params.units)}</label>
+                    <input aria-label={format!("Second yarn alone, stitches/{}", &params.units)} type="text" size="4" value={(*second).clone()} oninput={on_strand_input(&second)}/>
+                </span>
+                if let Some(gauge) = held {
+                    <span>
+                        {format!("Together: about {} st/{} ", params.locale.decimal(gauge, 1), &params.units)}
+                        <button type="button" onclick={on_use_held}>{"Use this gauge"}</button>
+                    </span>
+                }
+                <span class="field-note">
+                    {"Two strands knit up about as thick as one yarn with the bulk of both, so two of the same yarn give about 0.7 times the stitches. The rows are estimated from the stitches."}
+                </span>
+            </details>
+        </details>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct PanelProps {
+    pub params: Params,
+    pub on_message: Callback<GeneratorMessage>,
+}
+
+#[function_component(YarnPanel)]
+pub fn yarn_panel(props: &PanelProps) -> Html {
+    let params = &props.params;
+    let on_fiber_change = {
+        let on_message = props.on_message.clone();
+        Callback::from(move |e: Event| {
+            let select_el: HtmlSelectElement = e.target_unchecked_into();
+            if let Some(fiber) = Fiber::from_key(&select_el.value()) {
+                on_message.emit(GeneratorMessage::SetFiber(fiber));
+            }
+        })
+    };
+    let on_yarn_per_100g_input = props.on_message.reform(move |e: InputEvent| {
+        let input_el: HtmlInputElement = e.target_unchecked_into();
+        GeneratorMessage::SetYarnPer100g(input_el.value())
+    });
+    let on_skein_length_input = props.on_message.reform(move |e: InputEvent| {
+        let input_el: HtmlInputElement = e.target_unchecked_into();
+        GeneratorMessage::SetSkeinLength(input_el.value())
+    });
+    let on_skein_price_input = props.on_message.reform(move |e: InputEvent| {
+        let input_el: HtmlInputElement = e.target_unchecked_into();
+        GeneratorMessage::SetSkeinPrice(input_el.value())
+    });
+    let yarn_unit = params.units().map_or("yd", |u| u.yarn_unit().0);
+
+    html! {
+        <details class="panel" open=true>
+            <summary><h3>{"Yarn"}</h3></summary>
+            <span>
+                <label>{"Fiber: "}</label>
+                <select aria-label="Fiber" onchange={on_fiber_change}>
+                    { for Fiber::ALL.iter().map(|f| html! {
+                        <option value={f.key()} selected={*f == params.fiber}>{f.name()}</option>
+                    }) }
+                </select>
+            </span>
+            <span>
+                <label>{format!("{}/100 g: ", yarn_unit)}</label>
+                <input aria-label={format!("{}/100 g", yarn_unit)} type="text" placeholder="Optional, from the label" value={params.yarn_per_100g.clone()} oninput={on_yarn_per_100g_input}/>
+            </span>
+            <span>
+                <label>{format!("{} per skein: ", yarn_unit)}</label>
+                <input aria-label={format!("{} per skein", yarn_unit)} type="text" placeholder="Optional" value={params.skein_length.clone()} oninput={on_skein_length_input}/>
+            </span>
+            <span>
+                <label>{"Price per skein: "}</label>
+                <input aria-label="Price per skein" type="text" placeholder="Optional" value={params.skein_price.clone()} oninput={on_skein_price_input}/>
+            </span>
+        </details>
+    }
+}
+
+#[function_component(StripesPanel)]
+pub fn stripes_panel(props: &PanelProps) -> Html {
+    let params = &props.params;
+    let on_colors_input = props.on_message.reform(move |e: InputEvent| {
+        let input_el: HtmlInputElement = e.target_unchecked_into();
+        GeneratorMessage::SetColors(input_el.value())
+    });
+    let on_rows_per_stripe_input = props.on_message.reform(move |e: InputEvent| {
+        let input_el: HtmlInputElement = e.target_unchecked_into();
+        GeneratorMessage::SetRowsPerStripe(input_el.value())
+    });
+    let bands = params.stripe_sequence.iter().enumerate().map(|(i, stripe)| {
+        let on_color_change = {
+            let on_message = props.on_message.clone();
+            Callback::from(move |e: Event| {
+                let select_el: HtmlSelectElement = e.target_unchecked_into();
+                if let Ok(color) = select_el.value().parse() {
+                    on_message.emit(GeneratorMessage::SetStripeColor(i, color));
+                }
+            })
+        };
+        let on_rows_input = props.on_message.reform(move |e: InputEvent| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            GeneratorMessage::SetStripeRows(i, input_el.value())
+        });
+        let on_remove = props
+            .on_message
+            .reform(move |_: MouseEvent| GeneratorMessage::RemoveStripe(i));
+        let colors = (0..MAX_COLORS).map(|color| {
+            html! {
+                <option value={color.to_string()} selected={color == stripe.color}>
+                    {format!("Color {}", color_name(color))}
+                </option>
+            }
+        });
+        html! {
+            <li>
+                <select aria-label="Stripe color" onchange={on_color_change}>{ for colors }</select>
+                {" × "}
+                <input aria-label="Rows in the stripe" type="number" min="1" size="4" placeholder="Rows" value={stripe.rows.clone()} oninput={on_rows_input}/>
+                {" rows "}
+                <button type="button" onclick={on_remove}>{"Remove"}</button>
+            </li>
+        }
+    });
+    let on_add = props
+        .on_message
+        .reform(|_: MouseEvent| GeneratorMessage::AddStripe);
+    let on_gradient_input = props.on_message.reform(move |e: InputEvent| {
+        let input_el: HtmlInputElement = e.target_unchecked_into();
+        GeneratorMessage::SetGradientColors(input_el.value())
+    });
+    let on_blend_input = props.on_message.reform(move |e: InputEvent| {
+        let input_el: HtmlInputElement = e.target_unchecked_into();
+        GeneratorMessage::SetBlendRows(input_el.value())
+    });
+    let on_self_striping_input = props.on_message.reform(move |e: InputEvent| {
+        let input_el: HtmlInputElement = e.target_unchecked_into();
+        GeneratorMessage::SetSelfStripingLength(input_el.value())
+    });
+    let on_self_striping_colors_input = props.on_message.reform(move |e: InputEvent| {
+        let input_el: HtmlInputElement = e.target_unchecked_into();
+        GeneratorMessage::SetSelfStripingColors(input_el.value())
+    });
+    let on_align_toggle = props.on_message.reform(move |e: Event| {
+        let input_el: HtmlInputElement = e.target_unchecked_into();
+        GeneratorMessage::SetSelfStripingAlign(input_el.checked())
+    });
+    let yarn_unit = params
+        .units()
+        .map_or(params.units.as_str(), |units| units.yarn_unit().0);
+    let sequence_rows: usize = stripes::bands(&params.stripe_sequence)
+        .iter()
+        .map(|(_, rows)| rows)
+        .sum();
+
+    html! {
+        <details class="panel" open=true>
+            <summary><h3>{"Stripes"}</h3></summary>
+            <span>
+                <label>{"Colors: "}</label>
+                <input aria-label="Colors" type="number" min="1" max="6" value={params.colors.clone()} oninput={on_colors_input}/>
+            </span>
+            <span>
+                <label>{"Rows per stripe: "}</label>
+                <input aria-label="Rows per stripe" type="number" min="1" placeholder="Rows" value={params.rows_per_stripe.clone()} oninput={on_rows_per_stripe_input}/>
+            </span>
+            <h4>{"Stripe sequence"}</h4>
+            <ol class="stripe-sequence">{ for bands }</ol>
+            <button type="button" onclick={on_add}>{"Add band"}</button>
+            if sequence_rows > 0 {
+                <span class="field-note">
+                    {format!("Repeats every {} rows, in place of the even stripes above.", sequence_rows)}
+                </span>
+            }
+            <h4>{"Gradient"}</h4>
+            <span>
+                <label>{"Colors: "}</label>
+                <input aria-label="Colors" type="number" min="2" max={MAX_COLORS.to_string()} placeholder="Off" value={params.gradient_colors.clone()} oninput={on_gradient_input}/>
+            </span>
+            <span>
+                <label>{"Blend rows: "}</label>
+                <input aria-label="Blend rows" type="number" min="0" placeholder="0" value={params.blend_rows.clone()} oninput={on_blend_input}/>
+            </span>
+            <span class="field-note">
+                {"Fades from color A at the start to the last color at the end, alternating rows for a softer change; replaces stripes."}
+            </span>
+            <h4>{"Self-striping yarn"}</h4>
+            <span>
+                <label>{format!("Each color lasts ({}): ", yarn_unit)}</label>
+                <input aria-label={format!("Each color lasts ({})", yarn_unit)} type="text" size="5" placeholder="Off" value={params.self_striping_length.clone()} oninput={on_self_striping_input}/>
+            </span>
+            <span>
+                <label>{"Colors in the repeat: "}</label>
+                <input aria-label="Colors in the repeat" type="number" min="2" placeholder="2" value={params.self_striping_colors.clone()} oninput={on_self_striping_colors_input}/>
+            </span>
+            <span>
+                <label>
+                    <input type="checkbox" checked={params.self_striping_align} onchange={on_align_toggle}/>
+                    {" Adjust the start for clean stripes"}
+                </label>
+            </span>
+        </details>
+    }
+}
+
+#[function_component(PublishingPanel)]
+pub fn publishing_panel(props: &PanelProps) -> Html {
+    let metadata = &props.params.metadata;
+    let on_title_input = {
+        let metadata = metadata.clone();
+        props.on_message.reform(move |e: InputEvent| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            GeneratorMessage::SetMetadata(Metadata {
+                title: input_el.value(),
+                ..metadata.clone()
+            })
+        })
+    };
+    let on_author_input = {
+        let metadata = metadata.clone();
+        props.on_message.reform(move |e: InputEvent| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            GeneratorMessage::SetMetadata(Metadata {
+                author: input_el.value(),
+                ..metadata.clone()
+            })
+        })
+    };
+    let on_tally_change = {
+        let on_message = props.on_message.clone();
+        Callback::from(move |e: Event| {
+            let select_el: HtmlSelectElement = e.target_unchecked_into();
+            if let Some(tally) = Tally::from_key(&select_el.value()) {
+                on_message.emit(GeneratorMessage::SetTally(tally));
+            }
+        })
+    };
+    let on_license_change = {
+        let (metadata, on_message) = (metadata.clone(), props.on_message.clone());
+        Callback::from(move |e: Event| {
+            let select_el: HtmlSelectElement = e.target_unchecked_into();
+            if let Some(license) = License::from_key(&select_el.value()) {
+                on_message.emit(GeneratorMessage::SetMetadata(Metadata {
+                    license,
+                    ..metadata.clone()
+                }));
+            }
+        })
+    };
+
+    html! {
+        <details class="panel">
+            <summary><h3>{"Publishing"}</h3></summary>
+            <span>
+                <label>{"Title: "}</label>
+                <input aria-label="Title" type="text" placeholder="Pattern name" value={metadata.title.clone()} oninput={on_title_input}/>
+            </span>
+            <span>
+                <label>{"Designer: "}</label>
+                <input aria-label="Designer" type="text" placeholder="Your name" value={metadata.author.clone()} oninput={on_author_input}/>
+            </span>
+            <span>
+                <label>{"License: "}</label>
+                <select aria-label="License" onchange={on_license_change}>
+                    { for License::ALL.iter().map(|l| html! {
+                        <option value={l.key()} selected={*l == metadata.license}>{l.name()}</option>
+                    }) }
+                </select>
+            </span>
+            <span class="field-note">
+                {"Once there is a title or designer, both head the pattern and its downloads with the license's terms."}
+            </span>
+            <span>
+                <label>{"Tally boxes: "}</label>
+                <select aria-label="Tally boxes" onchange={on_tally_change}>
+                    { for Tally::ALL.iter().map(|t| html! {
+                        <option value={t.key()} selected={*t == props.params.tally}>{t.name()}</option>
+                    }) }
+                </select>
+            </span>
+            <span class="field-note">
+                {"Printed beside the rows of a shared pattern and its text download, to tick off on paper."}
+            </span>
+        </details>
+    }
+}
+
+#[function_component(ChartPalettePanel)]
+pub fn chart_palette_panel(props: &PanelProps) -> Html {
+    let palette = &props.params.palette;
+    let knitted: Vec<String> = YARN_COLORS.iter().map(|c| c.to_string()).collect();
+    let on_preset_change = {
+        let (palette, on_message) = (palette.clone(), props.on_message.clone());
+        Callback::from(move |e: Event| {
+            let select_el: HtmlSelectElement = e.target_unchecked_into();
+            if let Some(preset) = PalettePreset::from_key(&select_el.value()) {
+                // A preset replaces any colors picked by hand.
+                on_message.emit(GeneratorMessage::SetPalette(Palette {
+                    preset,
+                    colors: Vec::new(),
+                    ..palette.clone()
+                }));
+            }
+        })
+    };
+    let on_yarn_input = |yarn: usize, symbol: bool| {
+        let palette = palette.clone();
+        props.on_message.reform(move |e: InputEvent| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            GeneratorMessage::SetPalette(palette.with(yarn, input_el.value(), symbol))
+        })
+    };
+    let on_symbols_toggle = {
+        let palette = palette.clone();
+        props.on_message.reform(move |e: Event| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            GeneratorMessage::SetPalette(Palette {
+                show_symbols: input_el.checked(),
+                ..palette.clone()
+            })
+        })
+    };
+    let yarns = (0..YARN_COLORS.len()).map(|yarn| {
+        html! {
+            <span>
+                <label>{format!("{}: ", color_name(yarn))}</label>
+                <input aria-label={format!("Color of yarn {}", color_name(yarn))} type="color" value={palette.color(yarn, &knitted)} oninput={on_yarn_input(yarn, false)}/>
+                <input aria-label={format!("Symbol for yarn {}", color_name(yarn))} type="text" size="2" maxlength="2" placeholder={palette.symbol(yarn)} value={palette.symbols.get(yarn).cloned().unwrap_or_default()} oninput={on_yarn_input(yarn, true)}/>
+            </span>
+        }
+    });
+
+    html! {
+        <details class="panel">
+            <summary><h3>{"Chart colors"}</h3></summary>
+            <span>
+                <label>{"Palette: "}</label>
+                <select aria-label="Palette" onchange={on_preset_change}>
+                    { for PalettePreset::ALL.iter().map(|p| html! {
+                        <option value={p.key()} selected={*p == palette.preset}>{p.name()}</option>
+                    }) }
+                </select>
+            </span>
+            <span>
+                <label>
+                    <input type="checkbox" checked={palette.show_symbols} onchange={on_symbols_toggle}/>
+                    {" Mark each yarn with a symbol"}
+                </label>
+            </span>
+            { for yarns }
+            <span class="field-note">
+                {"Symbols tell the yarns apart without relying on color, and survive printing in black and white. Charts downloaded as SVG keep these colors and symbols."}
+            </span>
+        </details>
+    }
+}
+
+#[function_component(AbbreviationsPanel)]
+pub fn abbreviations_panel(props: &PanelProps) -> Html {
+    let abbreviations = &props.params.abbreviations;
+    let entries = GLOSSARY.iter().map(|(abbreviation, meaning)| {
+        let on_input = {
+            let abbreviations = abbreviations.clone();
+            props.on_message.reform(move |e: InputEvent| {
+                let input_el: HtmlInputElement = e.target_unchecked_into();
+                GeneratorMessage::SetAbbreviations(abbreviations.with(abbreviation, input_el.value()))
+            })
+        };
+        let value = match abbreviations.get(abbreviation) {
+            written if written == *abbreviation => String::new(),
+            written => written.to_string(),
+        };
+        html! {
+            <span>
+                <label title={*meaning}>{format!("{}: ", abbreviation)}</label>
+                <input aria-label={format!("Write {} as", abbreviation)} type="text" size="6" placeholder={*abbreviation} {value} oninput={on_input}/>
+            </span>
+        }
+    });
+
+    html! {
+        <details class="panel">
+            <summary><h3>{"Abbreviations"}</h3></summary>
+            { for entries }
+            <span class="field-note">
+                {"Write any abbreviation the way you are used to reading it, such as M1 for inc. The instructions and their list of abbreviations both use yours; leave one empty to keep the usual."}
+            </span>
+        </details>
+    }
+}
+
+#[function_component(PhrasingPanel)]
+pub fn phrasing_panel(props: &PanelProps) -> Html {
+    let phrasing = &props.params.phrasing;
+    let entries = Phrase::ALL.into_iter().map(|phrase| {
+        let on_input = {
+            let phrasing = phrasing.clone();
+            props.on_message.reform(move |e: InputEvent| {
+                let input_el: HtmlInputElement = e.target_unchecked_into();
+                GeneratorMessage::SetPhrasing(phrasing.with(phrase, input_el.value()))
+            })
+        };
+        let value = match phrasing.get(phrase) {
+            template if template == phrase.usual() => String::new(),
+            template => template.to_string(),
+        };
+        let placeholders: Vec<String> = phrase
+            .placeholders()
+            .iter()
+            .map(|(name, meaning)| format!("{{{}}} {}", name, meaning))
+            .collect();
+        html! {
+            <div>
+                <label>{format!("{}: ", phrase.name())}</label>
+                <input aria-label={format!("Template for {}", phrase.name().to_lowercase())} type="text" size="50" placeholder={phrase.usual()} value={value.clone()} oninput={on_input}/>
+                if let Err(message) = phrasing::check(phrase, &value) {
+                    <span class="field-error">{format!("{}; the usual wording is used until it's fixed.", message)}</span>
+                }
+                <span class="field-note">{placeholders.join(", ")}</span>
+            </div>
+        }
+    });
+
+    html! {
+        <details class="panel">
+            <summary><h3>{"Instruction phrasing"}</h3></summary>
+            { for entries }
+            <span class="field-note">
+                {"Write these kinds of row in your own house style, with each value in braces where it goes, such as \"{n}: *k{b}, M1; rep from * {t} times\". Rows not spaced exactly evenly, and crochet, keep the usual wording; leave one empty to keep the usual."}
+            </span>
+        </details>
+    }
+}
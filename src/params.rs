@@ -0,0 +1,198 @@
+use crate::abbreviations::Abbreviations;
+use crate::cables::Cable;
+use crate::counts::Rounding;
+use crate::finishing::Fiber;
+use crate::form::parse_count;
+use crate::gauge::{Fabric, Gauge};
+use crate::locale::Locale;
+use crate::metadata::Metadata;
+use crate::needles::NeedleStyle;
+use crate::numbering::Numbering;
+use crate::palette::Palette;
+use crate::parse::{parse_number, ParseError};
+use crate::pattern::Distribution;
+use crate::phrasing::Phrasing;
+use crate::ribbing::RibParams;
+use crate::shapes::cylinder::CylinderParams;
+use crate::shapes::dome::DomeParams;
+use crate::shapes::hat::HatParams;
+use crate::shapes::panel::PanelParams;
+use crate::shapes::sphere::SphereParams;
+use crate::shapes::swatch::SwatchParams;
+use crate::stripes::Stripe;
+use crate::tally::Tally;
+use crate::units::Unit;
+use serde::{Deserialize, Serialize};
+
+// Everything entered in the form, as typed. The gauge, yarn and stripe settings are shared by
+// all shapes; each shape keeps its own dimensions.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Params {
+    pub units: String,
+    pub stitches_per_unit: String,
+    pub rows_per_unit: String,
+    pub estimate_rows: bool,
+    // The stitch gauges of two yarns held together, when the gauge was estimated from them.
+    pub held_double: Option<(String, String)>,
+    pub fabric: Fabric,
+    pub fiber: Fiber,
+    pub yarn_per_100g: String,
+    pub skein_length: String,
+    pub skein_price: String,
+    pub colors: String,
+    pub rows_per_stripe: String,
+    // Bands of color worked in turn and repeated to the end; when there are any they replace the
+    // even stripes above.
+    pub stripe_sequence: Vec<Stripe>,
+    // Colors to fade through from the first row to the last, and how many rows of alternating
+    // colors blend each one into the next. A gradient replaces any stripes.
+    pub gradient_colors: String,
+    pub blend_rows: String,
+    // A self-striping yarn: how much yarn each color lasts, in yards or meters, and how many colors
+    // it repeats through. Whether to start part way into a color so the stripes come out clean.
+    pub self_striping_length: String,
+    pub self_striping_colors: String,
+    pub self_striping_align: bool,
+    // Seeds the random placement of increases, so the same parameters always give the same pattern.
+    pub seed: u64,
+    pub distribution: Distribution,
+    // At most one increase for every this many stitches of the row below, on shapes that increase.
+    pub increase_cap: String,
+    // How rows are rounded to whole stitches, and the multiple for rounding to a multiple.
+    pub rounding: Rounding,
+    pub rounding_multiple: String,
+    // Whether to flatten one-stitch wobbles in the stitch counts.
+    pub smooth_counts: bool,
+    // The most stitches the needles hold, so the widest row can be kept within it.
+    pub max_stitches: String,
+    // The fewest stitches to start a shape on, since a first row of two or three is hard to work.
+    pub min_cast_on: String,
+    // More sizes to grade the pattern for, separated by commas, in addition to the shape's own size.
+    pub sizes: String,
+    // How many knitters share the work, each knitting a run of rows.
+    pub knitters: String,
+    pub sphere: SphereParams,
+    pub dome: DomeParams,
+    pub cylinder: CylinderParams,
+    pub hat: HatParams,
+    // Brims and cuffs for the shapes worked as tubes.
+    pub rib: RibParams,
+    pub cable: Cable,
+    // Numbers of the rows to work as eyelet rows, separated by commas, as `numbering` numbers them.
+    pub eyelet_rows: String,
+    // What the written pattern calls its rows and whether it counts the cast on.
+    pub numbering: Numbering,
+    // Write pieces worked in the round for making two at once, as for a pair of socks.
+    pub two_at_a_time: bool,
+    // The needles to write notes for on pieces worked in the round.
+    pub needles: NeedleStyle,
+    // The width of a stitch pattern's repeat, which the cast on is fitted to.
+    pub repeat: String,
+    // Bobbles per hundred stitches, scattered using the seed.
+    pub bobble_density: String,
+    pub panel: PanelParams,
+    pub swatch: SwatchParams,
+    // The colors and symbols charts are drawn in.
+    pub palette: Palette,
+    // The knitter's own abbreviations for the instructions.
+    pub abbreviations: Abbreviations,
+    // The studio's own wording for some kinds of row.
+    pub phrasing: Phrasing,
+    // How numbers and measurements are written.
+    pub locale: Locale,
+    // The title, designer and license the pattern is published with.
+    pub metadata: Metadata,
+    // Boxes to tick off beside the rows of a printed or downloaded pattern.
+    pub tally: Tally,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Self {
+            units: String::from("in"),
+            stitches_per_unit: String::new(),
+            rows_per_unit: String::new(),
+            estimate_rows: false,
+            held_double: None,
+            fabric: Fabric::Stockinette,
+            fiber: Fiber::Wool,
+            yarn_per_100g: String::new(),
+            skein_length: String::new(),
+            skein_price: String::new(),
+            colors: String::from("1"),
+            rows_per_stripe: String::new(),
+            stripe_sequence: Vec::new(),
+            gradient_colors: String::new(),
+            blend_rows: String::new(),
+            self_striping_length: String::new(),
+            self_striping_colors: String::new(),
+            self_striping_align: false,
+            seed: 123,
+            distribution: Distribution::Offset,
+            increase_cap: String::new(),
+            rounding: Rounding::Nearest,
+            rounding_multiple: String::new(),
+            smooth_counts: false,
+            max_stitches: String::new(),
+            min_cast_on: String::new(),
+            sizes: String::new(),
+            knitters: String::new(),
+            sphere: SphereParams::default(),
+            dome: DomeParams::default(),
+            cylinder: CylinderParams::default(),
+            hat: HatParams::default(),
+            rib: RibParams::default(),
+            cable: Cable::None,
+            eyelet_rows: String::new(),
+            numbering: Numbering::default(),
+            two_at_a_time: false,
+            needles: NeedleStyle::None,
+            repeat: String::new(),
+            bobble_density: String::new(),
+            panel: PanelParams::default(),
+            swatch: SwatchParams::default(),
+            palette: Palette::default(),
+            abbreviations: Abbreviations::default(),
+            phrasing: Phrasing::default(),
+            locale: Locale::default(),
+            metadata: Metadata::default(),
+            tally: Tally::None,
+        }
+    }
+}
+
+impl Params {
+    pub fn units(&self) -> Option<Unit> {
+        Unit::from_label(&self.units)
+    }
+
+    pub fn stitches_per_unit(&self) -> Result<f64, ParseError> {
+        parse_number(&self.stitches_per_unit, self.locale)
+    }
+
+    // Row gauge to generate with: either what was entered or an estimate from the stitch gauge.
+    pub fn rows_per_unit(&self) -> Result<f64, ParseError> {
+        if self.estimate_rows {
+            self.stitches_per_unit()
+                .map(|st| self.fabric.estimate_rows_per_unit(st))
+        } else {
+            parse_number(&self.rows_per_unit, self.locale)
+        }
+    }
+
+    // How many knitters the work is split between, at least one.
+    pub fn knitters(&self) -> usize {
+        parse_count(&self.knitters).unwrap_or(1).max(1)
+    }
+
+    pub fn gauge(&self) -> Option<Gauge> {
+        match (self.stitches_per_unit(), self.rows_per_unit()) {
+            (Ok(stitches_per_unit), Ok(rows_per_unit)) => Some(Gauge {
+                stitches_per_unit,
+                rows_per_unit,
+            }),
+            _ => None,
+        }
+    }
+}
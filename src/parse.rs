@@ -0,0 +1,198 @@
+use crate::locale::Locale;
+use crate::units::Unit;
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+pub enum ParseError {
+    Empty,
+    NotANumber(String),
+    // A comma that could part thousands or be a decimal point, as in "1,200".
+    AmbiguousComma(String),
+    ZeroDenominator,
+    UnknownUnit(String),
+    UnconvertibleUnit(Unit),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "Enter a value"),
+            ParseError::NotANumber(text) => {
+                write!(
+                    f,
+                    "\"{}\" isn't a number; try something like 3.5 or 3 1/2",
+                    text
+                )
+            }
+            ParseError::AmbiguousComma(text) => write!(
+                f,
+                "\"{}\" could be read two ways; leave out the thousands separator, as 1200",
+                text
+            ),
+            ParseError::ZeroDenominator => write!(f, "A fraction can't have 0 on the bottom"),
+            ParseError::UnknownUnit(unit) => {
+                write!(f, "Unknown unit \"{}\"; use in, cm or mm", unit)
+            }
+            ParseError::UnconvertibleUnit(unit) => write!(
+                f,
+                "Can't convert {} to the selected units; set Units to in, cm or mm",
+                unit.abbreviation()
+            ),
+        }
+    }
+}
+
+// Parses decimals, fractions and mixed numbers such as "3.5", "1/2", "3 1/2" or "3-1/2", and
+// "3,5" where `locale` writes decimals with a comma.
+pub fn parse_number(input: &str, locale: Locale) -> Result<f64, ParseError> {
+    let text = input.trim();
+    if text.is_empty() {
+        return Err(ParseError::Empty);
+    }
+    // A whole number followed by a fraction, separated by a space or dash:
+    let (whole, fraction) = match text.split_once([' ', '-']) {
+        Some((whole, fraction)) if !whole.trim().is_empty() && fraction.contains('/') => {
+            (Some(whole), fraction)
+        }
+        _ => (None, text),
+    };
+    let mut value = match fraction.split_once('/') {
+        Some((numerator, denominator)) => {
+            let denominator = parse_plain(denominator, locale)?;
+            if denominator == 0.0 {
+                return Err(ParseError::ZeroDenominator);
+            }
+            parse_plain(numerator, locale)? / denominator
+        }
+        None => parse_plain(fraction, locale)?,
+    };
+    if let Some(whole) = whole {
+        value += parse_plain(whole, locale)?;
+    }
+    Ok(value)
+}
+
+// Parses a length with an optional unit suffix ("9cm", "3 1/2 in") and converts it to `units`.
+// Lengths without a suffix are taken to already be in `units`.
+pub fn parse_length(input: &str, units: Option<Unit>, locale: Locale) -> Result<f64, ParseError> {
+    let text = input.trim();
+    let number_end = text
+        .rfind(|c: char| c.is_ascii_digit())
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    let (number, suffix) = text.split_at(number_end);
+    if number.is_empty() && !suffix.is_empty() {
+        return Err(ParseError::NotANumber(text.to_string()));
+    }
+    let value = parse_number(number, locale)?;
+    if suffix.trim().is_empty() {
+        return Ok(value);
+    }
+    let from = Unit::from_label(suffix)
+        .ok_or_else(|| ParseError::UnknownUnit(suffix.trim().to_string()))?;
+    match units {
+        Some(to) => Ok(Unit::convert(value, from, to)),
+        None => Err(ParseError::UnconvertibleUnit(from)),
+    }
+}
+
+fn parse_plain(text: &str, locale: Locale) -> Result<f64, ParseError> {
+    let text = text.trim();
+    let number = match (text.contains(','), locale.decimal_comma()) {
+        (false, _) => text.to_string(),
+        // A decimal comma, as in "4,5":
+        (true, true) if !text.contains('.') => text.replacen(',', ".", 1),
+        _ => return Err(ParseError::AmbiguousComma(text.to_string())),
+    };
+    number
+        .parse::<f64>()
+        .ok()
+        .filter(|value| value.is_finite())
+        .ok_or_else(|| ParseError::NotANumber(text.to_string()))
+}
+
+// Splits a list of numbers as the knitter typed it. Items are parted by semicolons, or in lists
+// without any by commas; where a comma is also the decimal point, only a comma and a space part
+// two items, so "4,5, 6" is two sizes.
+pub fn split_list(text: &str, locale: Locale) -> Vec<&str> {
+    let items: Vec<&str> = match (text.contains(';'), locale.decimal_comma()) {
+        (true, _) => text.split(';').collect(),
+        (false, true) => text.split(", ").collect(),
+        (false, false) => text.split(',').collect(),
+    };
+    items
+        .into_iter()
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const US: Locale = Locale::EnglishUs;
+
+    #[test]
+    fn reads_decimals_fractions_and_mixed_numbers() {
+        assert_eq!(parse_number("3.5", US), Ok(3.5));
+        assert_eq!(parse_number(" 1/2 ", US), Ok(0.5));
+        assert_eq!(parse_number("3 1/2", US), Ok(3.5));
+        assert_eq!(parse_number("3-1/4", US), Ok(3.25));
+    }
+
+    #[test]
+    fn reads_a_decimal_comma_only_where_the_locale_writes_one() {
+        assert_eq!(parse_number("4,5", Locale::German), Ok(4.5));
+        assert_eq!(
+            parse_number("1,200", US),
+            Err(ParseError::AmbiguousComma(String::from("1,200")))
+        );
+        assert_eq!(
+            parse_number("1.200,5", Locale::German),
+            Err(ParseError::AmbiguousComma(String::from("1.200,5")))
+        );
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        assert_eq!(parse_number("  ", US), Err(ParseError::Empty));
+        assert_eq!(parse_number("1/0", US), Err(ParseError::ZeroDenominator));
+        assert_eq!(
+            parse_number("abc", US),
+            Err(ParseError::NotANumber(String::from("abc")))
+        );
+        assert!(parse_number("inf", US).is_err());
+    }
+
+    #[test]
+    fn converts_lengths_with_a_unit() {
+        assert_eq!(parse_length("20", Some(Unit::Centimeter), US), Ok(20.0));
+        assert_eq!(parse_length("25 mm", Some(Unit::Centimeter), US), Ok(2.5));
+        assert_eq!(parse_length("1 in", Some(Unit::Centimeter), US), Ok(2.54));
+        assert_eq!(parse_length("3 1/2in", Some(Unit::Inch), US), Ok(3.5));
+    }
+
+    #[test]
+    fn rejects_units_it_cant_convert() {
+        assert_eq!(
+            parse_length("4 ft", Some(Unit::Inch), US),
+            Err(ParseError::UnknownUnit(String::from("ft")))
+        );
+        assert_eq!(
+            parse_length("4 cm", None, US),
+            Err(ParseError::UnconvertibleUnit(Unit::Centimeter))
+        );
+        assert_eq!(
+            parse_length("cm", Some(Unit::Inch), US),
+            Err(ParseError::NotANumber(String::from("cm")))
+        );
+    }
+
+    #[test]
+    fn splits_lists_without_breaking_decimal_commas() {
+        assert_eq!(split_list("5, 6,7", US), ["5", "6", "7"]);
+        assert_eq!(split_list("4,5, 6", Locale::German), ["4,5", "6"]);
+        assert_eq!(split_list("4,5;6; ", Locale::German), ["4,5", "6"]);
+    }
+}
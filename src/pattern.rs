@@ -0,0 +1,1181 @@
+use crate::abbreviations::Abbreviations;
+use crate::cables::Cable;
+use crate::gauge::Fabric;
+use crate::numbering::Numbering;
+use crate::pairs;
+use crate::phrasing::Phrasing;
+use crate::stitchlib::StitchPattern;
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::hash::{Hash, Hasher};
+use std::iter::zip;
+
+// How the increases in a row are spread out.
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum Distribution {
+    // Even blocks between the increases, starting a random number of stitches into the row so
+    // the increases don't stack up from one increase row to the next.
+    #[default]
+    Offset,
+    // As evenly as the stitches allow, with blocks differing by at most one stitch, the same
+    // every time.
+    Even,
+    // The crocheter's ball: six increases every round, with every other round started half a
+    // block in so they don't line up.
+    Amigurumi,
+}
+
+impl Distribution {
+    pub const ALL: [Distribution; 3] = [
+        Distribution::Offset,
+        Distribution::Even,
+        Distribution::Amigurumi,
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Distribution::Offset => "offset",
+            Distribution::Even => "even",
+            Distribution::Amigurumi => "amigurumi",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Distribution::Offset => "Offset blocks",
+            Distribution::Even => "Evenly spaced",
+            Distribution::Amigurumi => "Amigurumi, 6 per round",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Distribution> {
+        Distribution::ALL.into_iter().find(|d| d.key() == key)
+    }
+}
+
+// The stitches to knit before each of `inc` increases, spreading `stitches` as evenly as possible
+// the way a line is drawn across pixels.
+pub fn even_blocks(stitches: i32, inc: i32) -> Vec<i32> {
+    (0..inc)
+        .map(|i| (i + 1) * stitches / inc - i * stitches / inc)
+        .collect()
+}
+
+// How a row changes the stitch count, with increase placement already decided.
+#[derive(Clone, PartialEq, Hash)]
+pub enum Shaping {
+    CastOn,
+    Plain,
+    // A purl row: the wrong side of stockinette worked flat, or garter stitch in the round.
+    Purl,
+    // A wrong side row of stockinette with `border` garter stitches at each edge.
+    Bordered {
+        border: i32,
+    },
+    // Row `row` of a stitch pattern from the library, repeated all the way along.
+    Texture {
+        stitch: &'static StitchPattern,
+        row: usize,
+    },
+    // A row of two-color brioche, in two passes from the same side: knits in one color, slide the
+    // work back, then purls in the other. The set-up row lays down the first slipped stitches.
+    Brioche {
+        setup: bool,
+    },
+    // Eyelets all the way along, keeping the stitch count: *yo, k2tog (p2tog from the wrong side).
+    Eyelet,
+    // A round with a cable panel at the start: p1, the cable's stitches for `round` of its repeat,
+    // p1, then knit to the end.
+    Cable {
+        cable: Cable,
+        round: usize,
+    },
+    // Increase after every stitch.
+    Alternate {
+        inc: i32,
+    },
+    // Increases spread around the row: `before` stitches, inc, then `repeats` of
+    // (`block` stitches, inc), then `after` stitches.
+    Spaced {
+        inc: i32,
+        before: i32,
+        block: i32,
+        repeats: i32,
+        after: i32,
+    },
+    // Increases spread as `even_blocks` places them over the stitches of the row below.
+    Even {
+        inc: i32,
+    },
+    // A crochet round of six increases, or for the closing half six decreases, each after `block`
+    // single crochet; an `offset` round starts half a block in.
+    Amigurumi {
+        block: i32,
+        offset: bool,
+        decrease: bool,
+    },
+    // The first round off a small cast on, worked stitch by stitch: `into[i]` stitches are worked
+    // into stitch `i` of the round below, so 1 is a plain stitch and 2 an increase.
+    Ring {
+        into: Vec<i32>,
+    },
+    // A single increase anywhere in the row.
+    Single,
+    // Decreases evenly spaced around the row: `dec` repeats of (`block` stitches, k2tog).
+    Decrease {
+        dec: i32,
+        block: i32,
+    },
+    // Decreases that don't divide the row evenly: `dec` repeats of (`block` stitches, k2tog), then
+    // `after` stitches.
+    DecreaseSpaced {
+        dec: i32,
+        block: i32,
+        after: i32,
+    },
+    // A k2tog after each of `knits` stitches in turn, then knit to the end: the decreases placed
+    // one by one, as when mirroring an increase row.
+    DecreaseAt {
+        knits: Vec<i32>,
+    },
+}
+
+impl Shaping {
+    // Stitches of the finished row of `count` stitches, counted from 0, made by the increases. A
+    // single increase is taken to be made at the end of the row.
+    pub fn increase_stitches(&self, count: i32) -> Vec<usize> {
+        match *self {
+            Shaping::Single => vec![count.max(1) as usize - 1],
+            Shaping::Spaced {
+                inc, before, block, ..
+            } => (0..inc)
+                .map(|i| (before + i * (block + 1)) as usize)
+                .collect(),
+            Shaping::Even { inc } => even_blocks(count - inc, inc)
+                .iter()
+                .scan(0, |stitch, block| {
+                    *stitch += *block as usize + 1;
+                    Some(*stitch - 1)
+                })
+                .collect(),
+            Shaping::Alternate { inc } => (0..inc as usize).map(|i| 2 * i + 1).collect(),
+            Shaping::Ring { ref into } => {
+                // The first stitch worked into each stitch below is the old one; the rest are made.
+                let mut stitch = 0;
+                let mut made = Vec::new();
+                for &worked in into {
+                    made.extend(stitch + 1..stitch + worked.max(1) as usize);
+                    stitch += worked.max(1) as usize;
+                }
+                made
+            }
+            Shaping::Amigurumi {
+                block,
+                offset,
+                decrease: false,
+            } => {
+                // Each increase is two stitches worked into one; the second is the one made.
+                let start = if offset && block >= 2 {
+                    block / 2
+                } else {
+                    block
+                };
+                (0..6)
+                    .map(|i| (start + 1 + i * (block + 2)) as usize)
+                    .collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    // Stitches of the finished row, counted from 0, that each k2tog makes.
+    pub fn decrease_stitches(&self) -> Vec<usize> {
+        let knits = match *self {
+            Shaping::Decrease { dec, block } | Shaping::DecreaseSpaced { dec, block, .. } => {
+                vec![block; dec.max(0) as usize]
+            }
+            Shaping::DecreaseAt { ref knits } => knits.clone(),
+            _ => return Vec::new(),
+        };
+        knits
+            .iter()
+            .scan(0, |stitch, knit| {
+                *stitch += (*knit).max(0) as usize + 1;
+                Some(*stitch - 1)
+            })
+            .collect()
+    }
+
+    // The row that takes back out the stitches made by this row's increases, from the `count`
+    // stitches this row ends with. Each increase is knit together with the stitch before it, or
+    // after it at the start of the row. Rows without increases mirror as themselves.
+    pub fn mirrored(&self, count: i32) -> Shaping {
+        if let Shaping::Amigurumi {
+            block,
+            offset,
+            decrease: false,
+        } = *self
+        {
+            return Shaping::Amigurumi {
+                block,
+                offset,
+                decrease: true,
+            };
+        }
+        let made = self.increase_stitches(count);
+        if made.is_empty() {
+            return self.clone();
+        }
+        let mut knits = Vec::new();
+        let mut next = 0;
+        for &stitch in &made {
+            let first = if stitch > next {
+                stitch - 1
+            } else if stitch + 1 < count as usize && !made.contains(&(stitch + 1)) {
+                stitch
+            } else {
+                // Increases too close together to take out one at a time, so spread the
+                // decreases evenly instead.
+                return decrease_shaping(made.len() as i32, count);
+            };
+            knits.push((first - next) as i32);
+            next = first + 2;
+        }
+        Shaping::DecreaseAt { knits }
+    }
+}
+
+// Writes out the first round off a ring stitch by stitch, grouping stitches worked alike, such as
+// "k2, inc in each of next 2 st", and saying so when every stitch is worked alike.
+fn ring_steps(into: &[i32]) -> String {
+    let mut runs: Vec<(i32, usize)> = Vec::new();
+    for &worked in into {
+        match runs.last_mut() {
+            Some((last, times)) if *last == worked => *times += 1,
+            _ => runs.push((worked, 1)),
+        }
+    }
+    if let [(worked, _)] = runs[..] {
+        return match worked {
+            2 => String::from("inc in each st around"),
+            worked => format!("work {} st into each st around", worked),
+        };
+    }
+    let steps: Vec<String> = runs
+        .iter()
+        .map(|&(worked, times)| match (worked, times) {
+            (1, times) => format!("k{}", times),
+            (2, 1) => String::from("inc"),
+            (2, times) => format!("inc in each of next {} st", times),
+            (worked, 1) => format!("work {} st into next st", worked),
+            (worked, times) => format!("work {} st into each of next {} st", worked, times),
+        })
+        .collect();
+    written_steps(&steps)
+}
+
+// Rewords a knitted row for crochet in `stitch`: knits and purls become that stitch, k2tog its
+// decrease, and the cast on a starting chain.
+fn crochet_wording(knit: &str, stitch: &str) -> String {
+    let knit = knit
+        .replace("Cast on", "Chain")
+        .replace("Knit", &format!("Work {}", stitch));
+    let reword = |word: &str| {
+        // Keep any repeat marks and punctuation around the stitch itself, as in "*k2tog".
+        let start = word.find(|c: char| c.is_ascii_alphanumeric()).unwrap_or(0);
+        let end = word
+            .rfind(|c: char| c.is_ascii_alphanumeric())
+            .map_or(start, |end| end + 1);
+        let (lead, body, trail) = (&word[..start], &word[start..end], &word[end..]);
+        let count = body.strip_prefix('k').or_else(|| body.strip_prefix('p'));
+        let body = match count {
+            Some("2tog") => format!("{}2tog", stitch),
+            Some(count) if !count.is_empty() && count.chars().all(|c| c.is_ascii_digit()) => {
+                format!("{} {}", stitch, count)
+            }
+            _ if body == "sc" => stitch.to_string(),
+            _ => body.to_string(),
+        };
+        format!("{}{}{}", lead, body, trail)
+    };
+    // Steps are parted by commas, with or without a space after, as in "*k1,inc":
+    knit.split(' ')
+        .map(|word| word.split(',').map(reword).collect::<Vec<_>>().join(","))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Writes out the steps across a row, as a repeat when they come round again.
+fn written_steps(steps: &[String]) -> String {
+    let len = steps.len();
+    // The steps come round again after the shortest stretch that evens out.
+    let period = (1..=len)
+        .find(|p| len.is_multiple_of(*p) && steps[..len - p] == steps[*p..])
+        .unwrap_or(len)
+        .max(1);
+    match len / period {
+        0 | 1 => steps.join(", "),
+        repeats => format!(
+            "*{} rep from * {} times",
+            steps[..period].join(", "),
+            repeats
+        ),
+    }
+}
+
+#[derive(Clone, PartialEq, Hash)]
+pub struct Row {
+    pub number: usize,
+    pub stitches: i32,
+    pub shaping: Shaping,
+    // Index of the yarn color the row is worked in, 0 being the main color.
+    pub color: usize,
+    // For intarsia, runs of (color, stitches) across the row in the order they are worked; empty
+    // when the whole row is worked in `color`.
+    pub segments: Vec<(usize, usize)>,
+    // Worked from the wrong side, on flat pieces.
+    pub wrong_side: bool,
+    // Stitches, counted from 0, to make a bobble in.
+    pub bobbles: Vec<usize>,
+    // The crochet stitch the row is worked in, for crochet fabrics; knitted when None.
+    pub crochet: Option<&'static str>,
+    // A change of needles to make before working the row, or the needles to cast on with.
+    pub needles: Option<String>,
+}
+
+// Colors are referred to as A, B, C... in the pattern.
+pub fn color_name(color: usize) -> String {
+    match u8::try_from(color).ok().filter(|c| *c < 26) {
+        Some(c) => char::from(b'A' + c).to_string(),
+        None => format!("#{}", color + 1),
+    }
+}
+
+impl Row {
+    // Runs of (color, stitches) across the row, whether or not it is intarsia.
+    pub fn runs(&self) -> Vec<(usize, usize)> {
+        if self.segments.is_empty() {
+            vec![(self.color, self.stitches.max(0) as usize)]
+        } else {
+            self.segments.clone()
+        }
+    }
+
+    // The (color, stitches) of each pass worked across the row: one, or two for brioche.
+    pub fn passes(&self) -> Vec<(usize, usize)> {
+        let count = self.stitches.max(0) as usize;
+        match self.shaping {
+            Shaping::Brioche { .. } => vec![(0, count), (1, count)],
+            _ => self.runs(),
+        }
+    }
+
+    // Stitches worked in the row.
+    pub fn worked(&self) -> i64 {
+        self.passes()
+            .iter()
+            .map(|(_, stitches)| *stitches as i64)
+            .sum()
+    }
+
+    pub fn new(number: usize, stitches: i32, shaping: Shaping) -> Row {
+        Row {
+            number,
+            stitches,
+            shaping,
+            color: 0,
+            segments: Vec::new(),
+            wrong_side: false,
+            bobbles: Vec::new(),
+            crochet: None,
+            needles: None,
+        }
+    }
+
+    // The row written out after `label`, such as "Round 3", in crochet terms for crochet rows. Use
+    // `Pattern::instruction` to label it the way the pattern numbers its rows.
+    pub fn instruction(&self, label: &str) -> String {
+        let knit = self.knit_instruction(label);
+        match self.crochet {
+            Some(stitch) => crochet_wording(&knit, stitch),
+            None => knit,
+        }
+    }
+
+    fn knit_instruction(&self, label: &str) -> String {
+        let number = match self.wrong_side {
+            true => format!("{} (WS)", label),
+            false => label.to_string(),
+        };
+        let count = self.stitches;
+        match self.shaping {
+            Shaping::CastOn if number.is_empty() => format!("Cast on {} stitches", count),
+            Shaping::CastOn => format!("{}: Cast on {} stitches", number, count),
+            Shaping::Plain => format!("{}: k{}", number, count),
+            Shaping::Purl => format!("{}: p{}", number, count),
+            Shaping::Bordered { border } => format!("{}: k{}, p{}, k{}", number, border, count - 2 * border, border),
+            Shaping::Texture { stitch, row } => format!("{}: {} ({} st)", number, stitch.written_row(row), count),
+            Shaping::Brioche { setup } => {
+                // From the wrong side the second color knits first, to start where its yarn hangs.
+                let (first, second) = match self.wrong_side {
+                    false => (color_name(0), color_name(1)),
+                    true => (color_name(1), color_name(0)),
+                };
+                match setup {
+                    true => format!("{} set-up: with {}, *sl1yo, k1 rep from * to end, slide; with {}, *brp1, sl1yo rep from * to end, turn ({} st)", number, first, second, count),
+                    false => format!("{}: with {}, *brk1, sl1yo rep from * to end, slide; with {}, *sl1yo, brp1 rep from * to end, turn ({} st)", number, first, second, count),
+                }
+            }
+            Shaping::Eyelet => {
+                let together = if self.wrong_side { "p2tog" } else { "k2tog" };
+                match count % 2 {
+                    0 => format!("{}: *yo, {} rep from * to end ({} st)", number, together, count),
+                    _ => format!("{}: *yo, {} rep from * to last st, k1 ({} st)", number, together, count),
+                }
+            }
+            Shaping::Cable { cable, round } => format!("{}: p1, {}, p1, k{} ({} st)", number, cable.rounds()[round], count - cable.width() - 2, count),
+            Shaping::Amigurumi { block, offset, decrease } => {
+                let step = if decrease { "dec" } else { "inc" };
+                let steps = match (block, offset && block >= 2) {
+                    (0, _) => format!("*{} rep from * 6 times", step),
+                    (_, false) => format!("*sc {}, {} rep from * 6 times", block, step),
+                    (_, true) => format!("sc {}, {}, *sc {}, {} rep from * 5 times, sc {}", block / 2, step, block, step, block - block / 2),
+                };
+                format!("{}: {} (total of 6 {}, {} st total)", number, steps, step, count)
+            }
+            Shaping::Alternate { inc } => format!("{}: *k1,inc rep from * to end (total of {} inc, {} st total)", number, inc, count),
+            Shaping::Spaced { inc, before, block, repeats, after } => format!("{}: k{} st, inc, * k{}, inc, rep from * {} times, k{} (total of {} inc, {} st total)",
+                                                                                   number, before,     block,               repeats,  after,         inc,    count),
+            Shaping::Even { inc } => {
+                let steps: Vec<String> = even_blocks(count - inc, inc)
+                    .iter()
+                    .map(|block| match block {
+                        0 => String::from("inc"),
+                        block => format!("k{}, inc", block),
+                    })
+                    .collect();
+                format!("{}: {} (total of {} inc, {} st total)", number, written_steps(&steps), inc, count)
+            }
+            Shaping::DecreaseAt { ref knits } => {
+                let dec = knits.len() as i32;
+                let steps: Vec<String> = knits
+                    .iter()
+                    .map(|knit| match knit {
+                        0 => String::from("k2tog"),
+                        knit => format!("k{}, k2tog", knit),
+                    })
+                    .collect();
+                let end = match count - dec - knits.iter().sum::<i32>() {
+                    0 => String::new(),
+                    rest => format!(", k{}", rest),
+                };
+                format!("{}: {}{} (total of {} dec, {} st total)", number, written_steps(&steps), end, dec, count)
+            }
+            Shaping::Ring { ref into } => {
+                let inc = count - into.len() as i32;
+                format!("{}: into the {} st of the round below, {} (total of {} inc, {} st total)", number, into.len(), ring_steps(into), inc, count)
+            }
+            Shaping::Single => format!("{}: Knit, inc. by total of {} st for total of {} st in row", number, 1, count),
+            Shaping::Decrease { dec, block: 0 } => format!("{}: *k2tog rep from * to end (total of {} dec, {} st total)", number, dec, count),
+            Shaping::Decrease { dec, block } => format!("{}: *k{}, k2tog rep from * to end (total of {} dec, {} st total)", number, block, dec, count),
+            Shaping::DecreaseSpaced { dec, block: 0, after } => format!("{}: *k2tog rep from * {} times, k{} (total of {} dec, {} st total)", number, dec, after, dec, count),
+            Shaping::DecreaseSpaced { dec, block, after } => format!("{}: *k{}, k2tog rep from * {} times, k{} (total of {} dec, {} st total)", number, block, dec, after, dec, count),
+        }
+    }
+}
+
+// FNV-1a, for ids kept in the browser's storage: unlike std's hashers, its output is the same in
+// every build.
+struct Fnv(u64);
+
+impl Hasher for Fnv {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(0x100_0000_01b3);
+        }
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write(&(i as u64).to_le_bytes());
+    }
+}
+
+#[derive(Clone, PartialEq, Hash)]
+pub struct Pattern {
+    pub rows: Vec<Row>,
+    pub numbering: Numbering,
+    // Written for working two of the piece at once, each round on one piece and then the other.
+    pub two_at_a_time: bool,
+    // The knitter's own abbreviations, used in place of the usual ones.
+    pub abbreviations: Abbreviations,
+    // The studio's own wording for some kinds of row.
+    pub phrasing: Phrasing,
+}
+
+impl Pattern {
+    // Identifies the pattern by its stitch counts and shaping, so anything remembered about it stays
+    // with the same instructions however they were produced, numbered, colored or worded.
+    pub fn id(&self) -> String {
+        let mut hasher = Fnv(0xcbf2_9ce4_8422_2325);
+        for row in &self.rows {
+            row.stitches.hash(&mut hasher);
+            row.shaping.hash(&mut hasher);
+        }
+        format!("{:016x}", hasher.finish())
+    }
+
+    // Every stitch worked, counting the cast on as a row and both passes of a brioche row.
+    pub fn total_stitches(&self) -> i64 {
+        self.rows.iter().map(Row::worked).sum()
+    }
+
+    // Stitches worked up to and including row `number`.
+    pub fn stitches_through(&self, number: usize) -> i64 {
+        self.rows
+            .iter()
+            .filter(|r| r.number <= number)
+            .map(Row::worked)
+            .sum()
+    }
+
+    // Stitches worked in each color, indexed by color.
+    pub fn stitches_by_color(&self) -> Vec<i64> {
+        let mut totals = Vec::new();
+        for row in &self.rows {
+            for (color, stitches) in row.passes() {
+                if totals.len() <= color {
+                    totals.resize(color + 1, 0);
+                }
+                totals[color] += stitches as i64;
+            }
+        }
+        totals
+    }
+
+    // Which color row `index` is worked in and what to do with the yarn before it, when the pattern
+    // uses more than one color.
+    pub fn color_note(&self, index: usize) -> Option<String> {
+        let row = &self.rows[index];
+        if !self.rows.iter().any(|r| r.color != row.color) {
+            return None;
+        }
+        let name = color_name(row.color);
+        Some(match index.checked_sub(1).map(|p| self.rows[p].color) {
+            None => format!("Use color {}", name),
+            Some(previous) if previous != row.color => format!("Change to color {}", name),
+            Some(_) => format!("Color {}", name),
+        })
+    }
+
+    // The colors across an intarsia row and the bobbins to join or break off before working it.
+    pub fn bobbin_note(&self, index: usize) -> Option<String> {
+        let row = &self.rows[index];
+        if row.segments.is_empty() {
+            return None;
+        }
+        let runs = row
+            .segments
+            .iter()
+            .map(|(color, stitches)| format!("{}{}", stitches, color_name(*color)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut notes = vec![runs];
+        let previous = index.checked_sub(1).map(|p| self.rows[p].runs());
+        let mut colors: Vec<usize> = row.segments.iter().map(|(color, _)| *color).collect();
+        colors.sort();
+        colors.dedup();
+        for color in colors {
+            let bobbins =
+                |runs: &[(usize, usize)]| runs.iter().filter(|(c, _)| *c == color).count();
+            let before = previous.as_deref().map_or(0, bobbins);
+            match bobbins(&row.segments).checked_sub(before) {
+                Some(0) => {}
+                Some(1) => notes.push(format!("join a new bobbin of {}", color_name(color))),
+                Some(n) => notes.push(format!("join {} new bobbins of {}", n, color_name(color))),
+                None => notes.push(format!(
+                    "break off {} bobbins of {}",
+                    before - bobbins(&row.segments),
+                    color_name(color)
+                )),
+            }
+        }
+        notes.push(String::from("twist the yarns at each change"));
+        Some(notes.join("; "))
+    }
+
+    // "Row" or "Round", as the pattern calls its rows.
+    pub fn word(&self) -> &'static str {
+        self.numbering.word(self.is_flat())
+    }
+
+    // Row `index` as the knitter sees it numbered, such as "Round 3"; empty for an unnumbered cast
+    // on.
+    pub fn label(&self, index: usize) -> String {
+        self.numbering
+            .label(self.rows[index].number, self.is_flat())
+    }
+
+    // Row `index` written out, numbered the way the pattern numbers its rows.
+    pub fn instruction(&self, index: usize) -> String {
+        let row = &self.rows[index];
+        let label = self.label(index);
+        let instruction = self
+            .phrasing
+            .write(row, &label)
+            .unwrap_or_else(|| row.instruction(&label));
+        let instruction = match self.two_at_a_time {
+            true => pairs::each_piece(&instruction, matches!(row.shaping, Shaping::CastOn)),
+            false => instruction,
+        };
+        self.abbreviations.apply(&instruction)
+    }
+
+    // The instruction for row `index`, with any change of color.
+    pub fn written_row(&self, index: usize) -> String {
+        let row = &self.rows[index];
+        let mut notes: Vec<String> = row.needles.iter().cloned().collect();
+        notes.extend(self.bobbin_note(index).or_else(|| self.color_note(index)));
+        if !row.bobbles.is_empty() {
+            let stitches: Vec<String> = row.bobbles.iter().map(|s| (s + 1).to_string()).collect();
+            notes.push(format!(
+                "{} in stitch {}",
+                self.abbreviations.get("MB"),
+                stitches.join(", ")
+            ));
+        }
+        match notes.is_empty() {
+            true => self.instruction(index),
+            false => format!("{} ({})", self.instruction(index), notes.join("; ")),
+        }
+    }
+
+    // Cycles through `colors` colors, changing every `rows_per_stripe` rows.
+    pub fn apply_stripes(&mut self, colors: usize, rows_per_stripe: usize) {
+        if colors < 2 || rows_per_stripe == 0 {
+            return;
+        }
+        for (i, row) in self.rows.iter_mut().enumerate() {
+            row.color = (i / rows_per_stripe) % colors;
+        }
+    }
+
+    // Whether the piece is worked back and forth rather than in the round.
+    pub fn is_flat(&self) -> bool {
+        self.rows.iter().any(|r| r.wrong_side)
+    }
+
+    // Works the plain rows in `fabric`; shaping rows stay knit rows. Brioche is only worked flat,
+    // and pieces in the round stay in stockinette. Crochet fabrics are worked in their stitch
+    // throughout.
+    pub fn apply_fabric(&mut self, fabric: Fabric) {
+        let flat = self.is_flat();
+        for row in &mut self.rows {
+            row.crochet = fabric.crochet();
+            if !matches!(row.shaping, Shaping::Plain | Shaping::Purl) {
+                continue;
+            }
+            row.shaping = match fabric {
+                // Knitting every row flat, or alternating knit and purl rounds:
+                Fabric::Garter if flat => Shaping::Plain,
+                Fabric::Garter if row.number % 2 == 0 => Shaping::Purl,
+                Fabric::Garter => Shaping::Plain,
+                Fabric::Seed => Shaping::Texture {
+                    stitch: fabric.stitch(),
+                    row: (row.number + 1) % 2,
+                },
+                Fabric::Brioche if flat => {
+                    // Brioche's two colors take the place of any stripes or intarsia.
+                    row.color = 0;
+                    row.segments.clear();
+                    Shaping::Brioche {
+                        setup: row.number == 2,
+                    }
+                }
+                Fabric::Stockinette
+                | Fabric::Brioche
+                | Fabric::SingleCrochet
+                | Fabric::HalfDoubleCrochet
+                | Fabric::DoubleCrochet => continue,
+            };
+        }
+    }
+
+    // Makes the plain rows numbered in `numbers` eyelet rows; rows with shaping are left alone.
+    pub fn apply_eyelets(&mut self, numbers: &[usize]) {
+        for row in &mut self.rows {
+            let plain = match row.shaping {
+                Shaping::Plain | Shaping::Purl => true,
+                Shaping::Texture { stitch, .. } => stitch.background,
+                _ => false,
+            };
+            if plain && numbers.contains(&row.number) {
+                row.shaping = Shaping::Eyelet;
+            }
+        }
+    }
+
+    // Works through `bands` of (color, rows) in order, starting over from the first band after the
+    // last.
+    pub fn apply_stripe_sequence(&mut self, bands: &[(usize, usize)]) {
+        let repeat: usize = bands.iter().map(|(_, rows)| rows).sum();
+        if repeat == 0 {
+            return;
+        }
+        for (i, row) in self.rows.iter_mut().enumerate() {
+            let mut position = i % repeat;
+            for &(color, rows) in bands {
+                if position < rows {
+                    row.color = color;
+                    break;
+                }
+                position -= rows;
+            }
+        }
+    }
+
+    // Works row `i` in `colors[i]`; rows beyond the end of `colors` are left as they are.
+    pub fn apply_colors(&mut self, colors: &[usize]) {
+        for (row, color) in zip(&mut self.rows, colors) {
+            row.color = *color;
+        }
+    }
+
+    // Builds a pattern that works each count as a shaping row followed by `every - 1` plain rows.
+    pub fn from_shaping_counts(
+        every: usize,
+        stitch_count_int: Vec<i32>,
+        seed: u64,
+        distribution: Distribution,
+    ) -> Pattern {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut rows = Vec::new();
+
+        // Copy the sequence and delete one element to shift:
+        let d1 = stitch_count_int.clone();
+        let mut d2 = stitch_count_int.clone();
+        d2.remove(0);
+        // diff will be x_i - x_{i-1}. Start it with None since first element has no diff:
+        let mut diff: Vec<Option<i32>> = zip(d1, d2).map(|(x, y)| Some(y - x)).collect();
+        diff.insert(0, None);
+
+        // Where the last increases were made, to keep the next ones from stacking on them:
+        let mut below = Vec::new();
+        for (i, (count, inc_by)) in zip(stitch_count_int, diff).enumerate() {
+            let shaping = match inc_by {
+                None => Shaping::CastOn,
+                Some(inc) => ring_round(i, inc, count, distribution)
+                    .unwrap_or_else(|| shaping_for_row(inc, count, distribution, &below, &mut rng)),
+            };
+            if shaping != Shaping::Plain {
+                below = shaping.increase_stitches(count);
+            }
+            rows.push(Row::new(every * i + 1, count, shaping));
+            for plain in 2..=every {
+                rows.push(Row::new(every * i + plain, count, Shaping::Plain));
+            }
+        }
+        Pattern {
+            rows,
+            numbering: Numbering::default(),
+            two_at_a_time: false,
+            abbreviations: Abbreviations::default(),
+            phrasing: Phrasing::default(),
+        }
+    }
+
+    // Closes the piece with the mirror image of its shaping: the rows after the cast on are worked
+    // again in reverse, each increase row turned into a decrease row, so both halves match stitch
+    // for stitch.
+    pub fn close_by_mirroring(&mut self) {
+        self.close_with(&self.clone());
+    }
+
+    // Closes the piece with another part worked on from its last row: `part`'s rows after its cast
+    // on, in reverse, each increase row turned into a decrease row. `part` has to widen to as many
+    // stitches as this piece ends on, so the two join stitch for stitch.
+    pub fn close_with(&mut self, part: &Pattern) {
+        for (i, row) in part.rows.iter().enumerate().skip(1).rev() {
+            let shaping = row.shaping.mirrored(row.stitches);
+            self.rows.push(Row::new(
+                self.rows.len() + 1,
+                part.rows[i - 1].stitches,
+                shaping,
+            ));
+        }
+    }
+
+    // Builds a pattern from the stitch count of every row, working out the shaping between them.
+    // The counts should already have passed `Validation::check_counts`.
+    pub fn from_counts(counts: &[i32], seed: u64, distribution: Distribution) -> Pattern {
+        let rows = zip(
+            counts,
+            shapings_for_counts(None, counts, seed, distribution),
+        )
+        .enumerate()
+        .map(|(i, (&count, shaping))| Row::new(i + 1, count, shaping))
+        .collect();
+        Pattern {
+            rows,
+            numbering: Numbering::default(),
+            two_at_a_time: false,
+            abbreviations: Abbreviations::default(),
+            phrasing: Phrasing::default(),
+        }
+    }
+
+    // The rows already `knit`, kept as they were, then this pattern's rows from the same point on.
+    // When the last row knitted ended with more or fewer stitches than this pattern has there, the
+    // difference is made up gradually over the rest, each count moved toward the planned one, so
+    // the piece still finishes as planned.
+    pub fn resume(&self, knit: &[Row], seed: u64, distribution: Distribution) -> Pattern {
+        let Some(last) = knit.last() else {
+            return self.clone();
+        };
+        let mut rows = knit.to_vec();
+        let rest = self.rows.get(knit.len()..).unwrap_or_default();
+        let off = last.stitches
+            - self
+                .rows
+                .get(knit.len() - 1)
+                .map_or(last.stitches, |r| r.stitches);
+        if off == 0 {
+            rows.extend(rest.iter().cloned());
+        } else {
+            let left = rest.len() as f64;
+            let counts: Vec<i32> = rest
+                .iter()
+                .enumerate()
+                .map(|(k, row)| {
+                    row.stitches + (f64::from(off) * (left - 1.0 - k as f64) / left).round() as i32
+                })
+                .collect();
+            let shapings = shapings_for_counts(Some(last.stitches), &counts, seed, distribution);
+            let mut below = (last.stitches, self.rows[knit.len() - 1].stitches);
+            for ((row, count), shaping) in rest.iter().zip(counts).zip(shapings) {
+                let mut row = row.clone();
+                // Rows that neither plan nor remake change the count keep their stitch pattern.
+                if count != below.0 || row.stitches != below.1 {
+                    row.shaping = shaping;
+                    row.bobbles.clear();
+                }
+                below = (count, row.stitches);
+                row.stitches = count;
+                rows.push(row);
+            }
+        }
+        Pattern {
+            rows,
+            numbering: self.numbering,
+            two_at_a_time: self.two_at_a_time,
+            abbreviations: self.abbreviations.clone(),
+            phrasing: self.phrasing.clone(),
+        }
+    }
+}
+
+// The shaping that works each of `counts` from the one before it, starting from `start` stitches
+// already on the needles, or from a cast on when nothing has been knitted yet.
+fn shapings_for_counts(
+    start: Option<i32>,
+    counts: &[i32],
+    seed: u64,
+    distribution: Distribution,
+) -> Vec<Shaping> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut below = Vec::new();
+    let mut previous = start;
+    counts
+        .iter()
+        .enumerate()
+        .map(|(i, &count)| {
+            let shaping = match previous {
+                None => Shaping::CastOn,
+                Some(previous) if count < previous => decrease_shaping(previous - count, previous),
+                // A ring only starts a piece, not a piece picked up partway.
+                Some(previous) if start.is_some() => {
+                    shaping_for_row(count - previous, count, distribution, &below, &mut rng)
+                }
+                Some(previous) => ring_round(i, count - previous, count, distribution)
+                    .unwrap_or_else(|| {
+                        shaping_for_row(count - previous, count, distribution, &below, &mut rng)
+                    }),
+            };
+            if shaping != Shaping::Plain {
+                below = shaping.increase_stitches(count);
+            }
+            previous = Some(count);
+            shaping
+        })
+        .collect()
+}
+
+// Spreads `dec` decreases as evenly as possible over a row that starts with `previous` stitches.
+fn decrease_shaping(dec: i32, previous: i32) -> Shaping {
+    let block = (previous - 2 * dec) / dec;
+    match previous - dec * (block + 2) {
+        0 => Shaping::Decrease { dec, block },
+        after => Shaping::DecreaseSpaced { dec, block, after },
+    }
+}
+
+// Casts on this small or smaller are a ring, whose first round is written out stitch by stitch.
+const RING_STITCHES: i32 = 12;
+
+// Row `i` as the first round off a ring, with its `inc` increases spread as evenly as they go over
+// the stitches of the ring; or None when it isn't, takes more than one increase a stitch, or is an
+// amigurumi round already worded exactly.
+fn ring_round(i: usize, inc: i32, count: i32, distribution: Distribution) -> Option<Shaping> {
+    let ring = count - inc;
+    let amigurumi = distribution == Distribution::Amigurumi && inc == 6 && count % 6 == 0;
+    if i != 1 || !(1..=ring).contains(&inc) || ring > RING_STITCHES || amigurumi {
+        return None;
+    }
+    let into = (0..ring)
+        .map(|s| 1 + (s + 1) * inc / ring - s * inc / ring)
+        .collect();
+    Some(Shaping::Ring { into })
+}
+
+// `below` are the stitches made by the last increases, in the row being worked into.
+fn shaping_for_row(
+    inc: i32,
+    count: i32,
+    distribution: Distribution,
+    below: &[usize],
+    rng: &mut StdRng,
+) -> Shaping {
+    if distribution == Distribution::Amigurumi && inc == 6 && count % 6 == 0 {
+        let round = count / 6;
+        Shaping::Amigurumi {
+            block: round - 2,
+            offset: round % 2 == 0,
+            decrease: false,
+        }
+    } else if inc + inc == count {
+        Shaping::Alternate { inc }
+    } else if inc > 1 && distribution == Distribution::Even {
+        Shaping::Even { inc }
+    } else if inc > 1 {
+        // Row with increases
+        // Divide in to roughly even blocks of knitting which will have increases between them:
+        let blocks = inc + 1;
+        // Figure out how many stitches in each block *before* the increases happen:
+        let block_sizes = f64::floor((f64::from(count - inc)) / f64::from(blocks)) as i32;
+        // Since we use floor, we rounded down so we may have a few stitches left after the blocks:
+        let rem = count - (blocks * block_sizes + inc);
+        // We don't want to start everything inc at the same place or we end up with too much of a pattern
+        // so pick a random amount to put at the beginning, keeping every increase off the stitches
+        // either side of an increase in the row below where we can:
+        let stacks = |before: i32| {
+            (0..inc).any(|i| {
+                let after = (before + i * block_sizes) as usize;
+                below.contains(&after) || (after > 0 && below.contains(&(after - 1)))
+            })
+        };
+        let clear: Vec<i32> = (0..(rem + block_sizes))
+            .filter(|before| !stacks(*before))
+            .collect();
+        let before_st = match clear.is_empty() {
+            true => rng.gen_range(0..(rem + block_sizes)),
+            false => clear[rng.gen_range(0..clear.len())],
+        };
+        // Figure out how many stitches that leaves at the end:
+        let after_st = rem + 2 * block_sizes - before_st;
+        Shaping::Spaced {
+            inc,
+            before: before_st,
+            block: block_sizes,
+            repeats: blocks - 2,
+            after: after_st,
+        }
+    } else if inc == 1 {
+        // Row without significant increases
+        Shaping::Single
+    } else {
+        Shaping::Plain
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counts(pattern: &Pattern) -> Vec<i32> {
+        pattern.rows.iter().map(|row| row.stitches).collect()
+    }
+
+    #[test]
+    fn ring_round_reaches_the_largest_ring() {
+        let Some(Shaping::Ring { into }) =
+            ring_round(1, 6, RING_STITCHES + 6, Distribution::default())
+        else {
+            panic!("a cast on of {} should be a ring", RING_STITCHES);
+        };
+        assert_eq!(into.len(), RING_STITCHES as usize);
+        assert_eq!(into.iter().sum::<i32>(), RING_STITCHES + 6);
+        assert!(ring_round(1, 6, RING_STITCHES + 7, Distribution::default()).is_none());
+    }
+
+    #[test]
+    fn ring_round_is_only_the_first_round() {
+        assert!(ring_round(2, 6, 12, Distribution::default()).is_none());
+        assert!(ring_round(1, 6, 12, Distribution::Amigurumi).is_none());
+    }
+
+    #[test]
+    fn crochet_rewords_stitches_joined_by_a_bare_comma() {
+        assert_eq!(
+            crochet_wording("Round 3: *k1,inc rep from * to end", "sc"),
+            "Round 3: *sc 1,inc rep from * to end"
+        );
+        assert_eq!(
+            crochet_wording("Round 9: k2tog,k4, k2tog", "hdc"),
+            "Round 9: hdc2tog,hdc 4, hdc2tog"
+        );
+    }
+
+    #[test]
+    fn crochet_rewords_the_cast_on_and_plain_rows() {
+        assert_eq!(
+            crochet_wording("Cast on 6 stitches", "sc"),
+            "Chain 6 stitches"
+        );
+        assert_eq!(crochet_wording("Round 2: k12", "dc"), "Round 2: dc 12");
+    }
+
+    fn numbers(pattern: &Pattern) -> Vec<usize> {
+        pattern.rows.iter().map(|row| row.number).collect()
+    }
+
+    #[test]
+    fn resuming_as_planned_keeps_the_plan() {
+        let plan = Pattern::from_counts(&[6, 12, 18, 24, 24, 24], 0, Distribution::default());
+        let resumed = plan.resume(&plan.rows[..3], 0, Distribution::default());
+        assert_eq!(counts(&resumed), counts(&plan));
+        assert_eq!(numbers(&resumed), [1, 2, 3, 4, 5, 6]);
+        assert!(resumed.rows == plan.rows);
+    }
+
+    #[test]
+    fn resuming_off_count_makes_it_up_over_the_rest() {
+        let plan = Pattern::from_counts(&[6, 12, 18, 24, 24, 24], 0, Distribution::default());
+        let mut knit = plan.rows[..3].to_vec();
+        knit[2].stitches = 16;
+        let resumed = plan.resume(&knit, 0, Distribution::default());
+        assert_eq!(counts(&resumed), [6, 12, 16, 23, 23, 24]);
+        assert_eq!(numbers(&resumed), [1, 2, 3, 4, 5, 6]);
+        // Each row after the last one knitted is shaped from the row below it:
+        assert!(resumed.rows[3].shaping.increase_stitches(23).len() == 7);
+        assert!(resumed.rows[4].shaping == Shaping::Plain);
+        assert!(resumed.rows[5].shaping == Shaping::Single);
+    }
+
+    #[test]
+    fn resuming_with_nothing_knitted_is_the_plan() {
+        let plan = Pattern::from_counts(&[6, 12, 18], 0, Distribution::default());
+        assert_eq!(
+            counts(&plan.resume(&[], 0, Distribution::default())),
+            [6, 12, 18]
+        );
+    }
+
+    #[test]
+    fn no_increases_make_no_blocks() {
+        assert!(even_blocks(12, 0).is_empty());
+    }
+
+    #[test]
+    fn one_increase_comes_after_every_stitch() {
+        assert_eq!(even_blocks(12, 1), [12]);
+        assert_eq!(even_blocks(0, 3), [0, 0, 0]);
+    }
+
+    #[test]
+    fn blocks_that_divide_evenly_are_equal() {
+        assert_eq!(even_blocks(12, 4), [3, 3, 3, 3]);
+    }
+
+    #[test]
+    fn blocks_that_dont_divide_differ_by_one_at_most() {
+        for (stitches, inc) in [(10, 4), (7, 3), (25, 6), (5, 8)] {
+            let blocks = even_blocks(stitches, inc);
+            assert_eq!(blocks.len(), inc as usize);
+            assert_eq!(blocks.iter().sum::<i32>(), stitches);
+            let (least, most) = (blocks.iter().min().unwrap(), blocks.iter().max().unwrap());
+            assert!(most - least <= 1, "{:?}", blocks);
+        }
+    }
+
+    #[test]
+    fn mirrored_row_knits_each_made_stitch_together() {
+        let mut checked = 0;
+        for distribution in [Distribution::Offset, Distribution::Even] {
+            for seed in 0..8 {
+                let pattern = Pattern::from_counts(&[8, 11, 16, 21, 27], seed, distribution);
+                for row in &pattern.rows[1..] {
+                    let made = row.shaping.increase_stitches(row.stitches);
+                    let Shaping::DecreaseAt { knits } = row.shaping.mirrored(row.stitches) else {
+                        continue;
+                    };
+                    // The stitches each k2tog works, counted from 0 in the row being decreased:
+                    let mut next = 0;
+                    for (stitch, knit) in zip(&made, &knits) {
+                        let first = next + *knit as usize;
+                        assert!(*stitch == first || *stitch == first + 1);
+                        next = first + 2;
+                    }
+                    assert_eq!(knits.len(), made.len());
+                    assert!(next <= row.stitches as usize);
+                    checked += 1;
+                }
+            }
+        }
+        assert!(checked > 0);
+    }
+
+    #[test]
+    fn mirrored_row_takes_out_as_many_as_were_made() {
+        for distribution in Distribution::ALL {
+            let pattern = Pattern::from_counts(&[6, 12, 18, 24, 25, 30], 3, distribution);
+            for row in &pattern.rows[1..] {
+                let made = row.shaping.increase_stitches(row.stitches).len();
+                let taken = match row.shaping.mirrored(row.stitches) {
+                    Shaping::Amigurumi { decrease, .. } => {
+                        assert!(decrease);
+                        6
+                    }
+                    mirrored => mirrored.decrease_stitches().len(),
+                };
+                assert_eq!(taken, made);
+            }
+        }
+    }
+
+    #[test]
+    fn plain_row_mirrors_as_itself() {
+        assert!(Shaping::Plain.mirrored(12) == Shaping::Plain);
+    }
+
+    #[test]
+    fn closing_by_mirroring_ends_where_it_started() {
+        let mut pattern = Pattern::from_counts(&[6, 12, 18, 24], 0, Distribution::default());
+        pattern.close_by_mirroring();
+        assert_eq!(counts(&pattern), [6, 12, 18, 24, 18, 12, 6]);
+        let numbers: Vec<usize> = pattern.rows.iter().map(|row| row.number).collect();
+        assert_eq!(numbers, [1, 2, 3, 4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn closing_with_another_part_joins_stitch_for_stitch() {
+        let mut pattern = Pattern::from_counts(&[6, 12, 12, 18], 0, Distribution::default());
+        let part = Pattern::from_counts(&[9, 18], 0, Distribution::default());
+        pattern.close_with(&part);
+        assert_eq!(counts(&pattern), [6, 12, 12, 18, 9]);
+        let last = &pattern.rows[4];
+        assert_eq!(last.shaping.decrease_stitches().len(), 9);
+    }
+}
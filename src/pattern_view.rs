@@ -0,0 +1,285 @@
+use crate::cables;
+use crate::colorwork::chart_view;
+use crate::cost;
+use crate::diagnostics::DiagnosticsPanel;
+use crate::dual_craft::DualCraft;
+use crate::export;
+use crate::fair_isle::FairIsle;
+use crate::form::parse_count;
+use crate::gauge::Gauge;
+use crate::gauge_compare::GaugeComparison;
+use crate::gradient::gradient_colors;
+use crate::held_double;
+use crate::motifs::Motifs;
+use crate::needle_inventory;
+use crate::pairs;
+use crate::params::Params;
+use crate::parse::parse_number;
+use crate::pattern::{color_name, Pattern, Shaping};
+use crate::preview::{cross_section, SolidPreview};
+use crate::ravelry::RavelryUpload;
+use crate::row_tracker::RowTracker;
+use crate::self_striping;
+use crate::shapes::hat;
+use crate::shapes::{Design, Shape};
+use crate::sizing::SizingTable;
+use crate::skill;
+use crate::split;
+use crate::stitch_table::stitch_table;
+use crate::storage;
+use crate::stripes;
+use crate::stuffing;
+use crate::timing;
+use crate::yarn::YarnEstimate;
+use yew::prelude::*;
+
+// The written pattern for a design: materials, row-by-row instructions and finishing.
+pub fn pattern_view(shape: Shape, design: &Design, params: &Params, gauge: Gauge) -> Html {
+    let estimate_note = if params.estimate_rows {
+        html! {
+            <p><em>{format!(
+                "Row gauge estimated as {} rows/{} from the stitch gauge ({} ratio {}). Knit a swatch to confirm it.",
+                params.locale.decimal(gauge.rows_per_unit, 1), &params.units, params.fabric.name(), params.locale.number(params.fabric.ratio())
+            )}</em></p>
+        }
+    } else {
+        html! {}
+    };
+    let pattern = striped_pattern(design, params);
+    // Where to start a self-striping yarn, when the start was adjusted:
+    let start_note = self_striping::plan(&design.pattern, params, gauge)
+        .filter(|plan| plan.pull_off > 0.0)
+        .map(|plan| {
+            format!(
+                "Self-striping yarn: start at the beginning of color A and pull off about {} {} before casting on, so the colors change at the ends of rows.",
+                params.locale.decimal(plan.pull_off, 1), plan.yarn_unit
+            )
+        });
+    let materials = Materials::new(design, &pattern, params, gauge);
+    let chunks = split::chunks(&pattern, params.knitters());
+    let on_download = {
+        let (design, params) = (design.clone(), params.clone());
+        Callback::from(move |_: MouseEvent| {
+            let text = export::pattern_text(shape, &design, &params, gauge);
+            export::download("pattern.txt", "text/plain", &text);
+        })
+    };
+
+    html! {
+        <div>
+            if let Some(heading) = params.metadata.heading() {
+                <div class="pattern-heading">
+                    { for heading.iter().map(|line| html! {<p>{line}</p>}) }
+                </div>
+            }
+            <p class="pattern-summary">{shape.summary(params)}</p>
+            <p class="pattern-summary">{skill::describe(&pattern)}</p>
+            <p class="pattern-summary">{timing::summary(&pattern, params.fabric, params.locale)}</p>
+            { estimate_note }
+            if let Some(chart) = &design.chart {
+                { chart_view(chart, &design.pattern, &params.palette) }
+            } else {
+                <div class="previews">
+                    { cross_section(&design.pattern, gauge, &params.units, params.locale) }
+                    <SolidPreview pattern={design.pattern.clone()} {gauge} />
+                </div>
+            }
+            <h3>{"Materials"}</h3>
+            <ul>
+                <li>{&materials.yarn}</li>
+                { for materials.set.iter().map(|line| html! {<li>{line}</li>}) }
+                { for materials.held_double.iter().map(|line| html! {<li>{line}</li>}) }
+                <ul>{ for materials.by_color.iter().map(|line| html! {<li>{line}</li>}) }</ul>
+                { for materials.cost.iter().map(|line| html! {<li>{line}</li>}) }
+                { for materials.needles.iter().map(|line| html! {<li>{line}</li>}) }
+                { for start_note.map(|line| html! {<li>{line}</li>}) }
+            </ul>
+            <h3>{"Instructions"}</h3>
+            if pattern.two_at_a_time {
+                <ul>{ for pairs::setup(&pattern).iter().map(|line| html! {<li>{line}</li>}) }</ul>
+            }
+            <details>
+                <summary>{"Abbreviations"}</summary>
+                <ul>{ for pattern.abbreviations.glossary(&pattern).iter().map(|line| html! {<li>{line}</li>}) }</ul>
+            </details>
+            <details>
+                <summary>{"Stitch count table"}</summary>
+                { stitch_table(&pattern, gauge, params.units(), &params.units, params.locale) }
+            </details>
+            if design.pattern.rows.iter().any(|row| matches!(row.shaping, Shaping::Cable { .. })) {
+                <details open=true>
+                    <summary>{format!("Cable chart: {}", params.cable.name())}</summary>
+                    { cables::chart_view(params.cable) }
+                </details>
+            }
+            if chunks.len() > 1 {
+                <details open=true>
+                    <summary>{format!("Split between {} knitters", chunks.len())}</summary>
+                    <ul>{ for (1..=chunks.len()).map(|k| html! {<li>{split::header(&pattern, &chunks, k)}</li>}) }</ul>
+                    <p>{split::joining(&pattern)}</p>
+                </details>
+            }
+            if shape == Shape::Hat {
+                <details>
+                    <summary>{"Crown decreases"}</summary>
+                    { hat::crown_view(&design.pattern, &params.palette) }
+                </details>
+            }
+            <details>
+                <summary>{"Sizing table"}</summary>
+                <SizingTable {shape} params={params.clone()} {gauge} />
+            </details>
+            <details>
+                <summary>{"Compare another gauge"}</summary>
+                <GaugeComparison {shape} params={params.clone()} {gauge} />
+            </details>
+            <details>
+                <summary>{"Knit and crochet versions side by side"}</summary>
+                <DualCraft {shape} params={params.clone()} design={design.clone()} {gauge} />
+            </details>
+            <details>
+                <summary>{"Fair Isle from a picture"}</summary>
+                <FairIsle pattern={design.pattern.clone()} palette={params.palette.clone()} />
+            </details>
+            <details>
+                <summary>{"Motifs"}</summary>
+                <Motifs pattern={pattern.clone()} {gauge} unit_label={params.units.clone()} locale={params.locale} palette={params.palette.clone()} />
+            </details>
+            <DiagnosticsPanel
+                pattern={design.pattern.clone()}
+                profile={design.profile.clone()}
+                unit_label={params.units.clone()}
+                locale={params.locale}
+            />
+            <RowTracker {pattern} {gauge} unit_label={params.units.clone()} locale={params.locale} />
+            <h3>{"Finishing"}</h3>
+            <ul>
+                { for design.finishing.iter().map(|note| html! {<li>{note}</li>}) }
+                { for materials.stuffing.iter().map(|line| html! {<li>{line}</li>}) }
+            </ul>
+            if let Some(heading) = params.metadata.heading() {
+                <p class="pattern-license">{heading.last().cloned()}</p>
+            }
+            <p><button type="button" onclick={on_download}>{"Download as text"}</button></p>
+            <details>
+                <summary>{"Send to Ravelry"}</summary>
+                <RavelryUpload {shape} design={design.clone()} params={params.clone()} {gauge} />
+            </details>
+        </div>
+    }
+}
+
+// The design's pattern with the stripes worked in.
+pub fn striped_pattern(design: &Design, params: &Params) -> Pattern {
+    let mut pattern = design.pattern.clone();
+    let bands = stripes::bands(&params.stripe_sequence);
+    let gradient = parse_count(&params.gradient_colors).filter(|colors| *colors > 1);
+    let self_striping = params
+        .gauge()
+        .and_then(|gauge| self_striping::plan(&pattern, params, gauge));
+    if let Some(colors) = gradient {
+        let blend = parse_count(&params.blend_rows).unwrap_or(0);
+        pattern.apply_colors(&gradient_colors(pattern.rows.len(), colors, blend));
+    } else if let Some(plan) = self_striping {
+        pattern.apply_colors(&plan.colors);
+    } else if bands.is_empty() {
+        pattern.apply_stripes(
+            parse_count(&params.colors).unwrap_or(1),
+            parse_count(&params.rows_per_stripe).unwrap_or(0),
+        );
+    } else {
+        pattern.apply_stripe_sequence(&bands);
+    }
+    pattern
+}
+
+// What to buy for a pattern, as lines of text.
+pub struct Materials {
+    pub yarn: String,
+    // Only when there is more than one color.
+    pub by_color: Vec<String>,
+    pub cost: Option<String>,
+    // How the gauge was estimated for yarns held together.
+    pub held_double: Option<String>,
+    pub stuffing: Option<String>,
+    // The yarn for a whole set of matching pieces, when more than one is made.
+    pub set: Option<String>,
+    // Which of the knitter's own needles to work it on.
+    pub needles: Vec<String>,
+}
+
+impl Materials {
+    pub fn new(design: &Design, pattern: &Pattern, params: &Params, gauge: Gauge) -> Materials {
+        let length_per_100g = parse_number(&params.yarn_per_100g, params.locale).ok();
+        let yarn_for = |stitches: i64| {
+            YarnEstimate::new(
+                stitches,
+                gauge.stitches_per_unit,
+                gauge.rows_per_unit,
+                &params.units,
+                length_per_100g,
+            )
+        };
+        let stitches_by_color = pattern.stitches_by_color();
+        let by_color = stitches_by_color
+            .iter()
+            .enumerate()
+            .filter(|_| stitches_by_color.len() > 1)
+            .map(|(color, stitches)| {
+                format!(
+                    "Color {}: {}",
+                    color_name(color),
+                    yarn_for(*stitches).describe(params.locale)
+                )
+            })
+            .collect();
+        let lengths_by_color: Vec<f64> = stitches_by_color
+            .iter()
+            .map(|stitches| yarn_for(*stitches).length)
+            .collect();
+        let cost = match (
+            parse_number(&params.skein_length, params.locale)
+                .ok()
+                .filter(|l| *l > 0.0),
+            parse_number(&params.skein_price, params.locale),
+        ) {
+            (Some(skein_length), Ok(price)) => Some(cost::describe(
+                &lengths_by_color,
+                skein_length,
+                price,
+                params.locale,
+            )),
+            _ => None,
+        };
+        let yarn = yarn_for(pattern.total_stitches());
+        Materials {
+            yarn: yarn.describe(params.locale),
+            by_color,
+            cost,
+            held_double: params
+                .held_double
+                .as_ref()
+                .map(|strands| held_double::note(strands, &params.units, params.locale)),
+            stuffing: design.volume.map(|volume| match design.weight {
+                Some(target) => stuffing::weighted(
+                    volume,
+                    &params.units,
+                    target,
+                    yarn.grams,
+                    design.pieces,
+                    params.locale,
+                ),
+                None => stuffing::describe(volume, &params.units, params.locale),
+            }),
+            set: (design.pieces > 1).then(|| {
+                format!(
+                    "{} for the set of {}",
+                    yarn_for(pattern.total_stitches() * design.pieces as i64)
+                        .describe(params.locale),
+                    design.pieces
+                )
+            }),
+            needles: needle_inventory::recommend(&storage::load_needles(), pattern, params, gauge),
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use crate::pattern::{even_blocks, Row, Shaping};
+use serde::{Deserialize, Serialize};
+
+// The kinds of row whose wording can be changed, each with the values its template can use.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Phrase {
+    Knit,
+    Purl,
+    Increase,
+    Decrease,
+}
+
+impl Phrase {
+    pub const ALL: [Phrase; 4] = [
+        Phrase::Knit,
+        Phrase::Purl,
+        Phrase::Increase,
+        Phrase::Decrease,
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Phrase::Knit => "knit",
+            Phrase::Purl => "purl",
+            Phrase::Increase => "inc",
+            Phrase::Decrease => "dec",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Phrase::Knit => "Knit rows",
+            Phrase::Purl => "Purl rows",
+            Phrase::Increase => "Evenly spaced increases",
+            Phrase::Decrease => "Evenly spaced decreases",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Phrase> {
+        Phrase::ALL.into_iter().find(|phrase| phrase.key() == key)
+    }
+
+    // The placeholders a template for the phrase can use, with what each stands for.
+    pub fn placeholders(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Phrase::Knit | Phrase::Purl => &[("n", "row label"), ("st", "stitches")],
+            Phrase::Increase => &[
+                ("n", "row label"),
+                ("b", "stitches between increases"),
+                ("t", "times repeated, one increase each"),
+                ("st", "stitches after the row"),
+            ],
+            Phrase::Decrease => &[
+                ("n", "row label"),
+                ("b", "stitches between decreases"),
+                ("t", "times repeated, one decrease each"),
+                ("st", "stitches after the row"),
+            ],
+        }
+    }
+
+    // The template that writes the phrase as the generator usually does.
+    pub fn usual(&self) -> &'static str {
+        match self {
+            Phrase::Knit => "{n}: k{st}",
+            Phrase::Purl => "{n}: p{st}",
+            Phrase::Increase => {
+                "{n}: *k{b}, inc rep from * {t} times (total of {t} inc, {st} st total)"
+            }
+            Phrase::Decrease => {
+                "{n}: *k{b}, k2tog rep from * to end (total of {t} dec, {st} st total)"
+            }
+        }
+    }
+
+    // The phrase `row` is written with and the values for its placeholders, when it is one whose
+    // wording can be changed.
+    fn of(row: &Row, label: &str) -> Option<(Phrase, Vec<(&'static str, String)>)> {
+        if row.crochet.is_some() {
+            return None;
+        }
+        let (phrase, block, times) = match row.shaping {
+            Shaping::Plain => (Phrase::Knit, 0, 0),
+            Shaping::Purl => (Phrase::Purl, 0, 0),
+            Shaping::Alternate { inc } => (Phrase::Increase, 1, inc),
+            Shaping::Even { inc } => {
+                // Only the rows spaced exactly evenly say it as one repeat.
+                let blocks = even_blocks(row.stitches - inc, inc);
+                match blocks[..] {
+                    [first, ..] if first > 0 && blocks.iter().all(|b| *b == first) => {
+                        (Phrase::Increase, first, inc)
+                    }
+                    _ => return None,
+                }
+            }
+            Shaping::Decrease { dec, block } if block > 0 => (Phrase::Decrease, block, dec),
+            _ => return None,
+        };
+        let label = match row.wrong_side {
+            true => format!("{} (WS)", label),
+            false => label.to_string(),
+        };
+        let values = vec![
+            ("n", label),
+            ("b", block.to_string()),
+            ("t", times.to_string()),
+            ("st", row.stitches.to_string()),
+        ];
+        Some((phrase, values))
+    }
+}
+
+// Checks `template` for `phrase`: each placeholder closed and one the phrase has.
+pub fn check(phrase: Phrase, template: &str) -> Result<(), String> {
+    fill(template, |name| {
+        phrase
+            .placeholders()
+            .iter()
+            .any(|(known, _)| *known == name)
+            .then(String::new)
+    })
+    .map(|_| ())
+    .map_err(|err| format!("{}: {}", phrase.name(), err))
+}
+
+// `template` with each "{name}" replaced by its value, or why it can't be.
+fn fill(template: &str, value: impl Fn(&str) -> Option<String>) -> Result<String, String> {
+    let mut written = String::new();
+    let mut rest = template;
+    while let Some(open) = rest.find(['{', '}']) {
+        written += &rest[..open];
+        if rest[open..].starts_with('}') {
+            return Err(String::from("a } has no { before it"));
+        }
+        let Some(close) = rest[open..].find('}') else {
+            return Err(String::from("a { has no } after it"));
+        };
+        let name = &rest[open + 1..open + close];
+        match value(name) {
+            Some(value) => written += &value,
+            None => return Err(format!("{{{}}} isn't a value the generator knows", name)),
+        }
+        rest = &rest[open + close + 1..];
+    }
+    Ok(written + rest)
+}
+
+// The studio's own wording for some kinds of row, as (phrase key, template), used in place of the
+// generator's.
+#[derive(Clone, Default, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Phrasing(pub Vec<(String, String)>);
+
+impl Phrasing {
+    // The template `phrase` is written with.
+    pub fn get(&self, phrase: Phrase) -> &str {
+        self.0
+            .iter()
+            .find(|(key, template)| key == phrase.key() && !template.trim().is_empty())
+            .map_or(phrase.usual(), |(_, template)| template)
+    }
+
+    // With `phrase` written by `template`, or as usual when it is left empty.
+    pub fn with(&self, phrase: Phrase, template: String) -> Phrasing {
+        let mut templates: Vec<(String, String)> = self
+            .0
+            .iter()
+            .filter(|(key, _)| key != phrase.key())
+            .cloned()
+            .collect();
+        if !template.trim().is_empty() {
+            templates.push((phrase.key().to_string(), template));
+        }
+        Phrasing(templates)
+    }
+
+    // `row` after `label` in the studio's wording, when there is a valid template for its kind.
+    pub fn write(&self, row: &Row, label: &str) -> Option<String> {
+        let (phrase, values) = Phrase::of(row, label)?;
+        let template = self.get(phrase);
+        if self.0.iter().all(|(key, _)| key != phrase.key()) || check(phrase, template).is_err() {
+            return None;
+        }
+        fill(template, |name| {
+            values
+                .iter()
+                .find(|(known, _)| *known == name)
+                .map(|(_, value)| value.clone())
+        })
+        .ok()
+    }
+}
@@ -0,0 +1,33 @@
+use crate::export;
+use web_sys::wasm_bindgen::JsCast;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+
+// PNGs are drawn at twice the picture's size, so they stay sharp when pasted and scaled up.
+const PIXEL_RATIO: f64 = 2.0;
+
+// Draws a picture `width` by `height` on a canvas that is never shown, on a white background, and
+// returns it as a PNG data URL.
+fn render(width: f64, height: f64, draw: impl FnOnce(&CanvasRenderingContext2d)) -> Option<String> {
+    let document = web_sys::window()?.document()?;
+    let canvas: HtmlCanvasElement = document.create_element("canvas").ok()?.dyn_into().ok()?;
+    canvas.set_width((width * PIXEL_RATIO).ceil() as u32);
+    canvas.set_height((height * PIXEL_RATIO).ceil() as u32);
+    let context: CanvasRenderingContext2d = canvas.get_context("2d").ok()??.dyn_into().ok()?;
+    context.scale(PIXEL_RATIO, PIXEL_RATIO).ok()?;
+    context.set_fill_style_str("#ffffff");
+    context.fill_rect(0.0, 0.0, width, height);
+    draw(&context);
+    canvas.to_data_url_with_type("image/png").ok()
+}
+
+// Downloads a picture drawn by `draw` as a PNG, for pasting into documents and posts.
+pub fn download(
+    filename: &str,
+    width: f64,
+    height: f64,
+    draw: impl FnOnce(&CanvasRenderingContext2d),
+) {
+    if let Some(url) = render(width, height, draw) {
+        export::download_url(filename, &url);
+    }
+}
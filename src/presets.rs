@@ -0,0 +1,268 @@
+use crate::gauge::Fabric;
+use crate::params::Params;
+use crate::parse::parse_number;
+use crate::shapes::Shape;
+use crate::storage;
+use crate::units::Unit;
+use serde::{Deserialize, Serialize};
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+// Part of the form saved to fill back in later, like a favourite yarn's gauge or the size of a
+// tennis ball.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub enum PresetValues {
+    Gauge {
+        units: String,
+        stitches_per_unit: String,
+        rows_per_unit: String,
+        estimate_rows: bool,
+        fabric: Fabric,
+    },
+    // Each dimension has its unit written after it, so it reads correctly whatever the form's units.
+    Size {
+        shape: Shape,
+        dimensions: Vec<String>,
+    },
+    // A set of matching balls weighted with pellets, as for juggling.
+    BallSet {
+        diameter: String,
+        balls: String,
+        weight: String,
+    },
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Preset {
+    pub name: String,
+    pub values: PresetValues,
+}
+
+impl Preset {
+    pub fn gauge(name: String, params: &Params) -> Preset {
+        Preset {
+            name,
+            values: PresetValues::Gauge {
+                units: params.units.clone(),
+                stitches_per_unit: params.stitches_per_unit.clone(),
+                rows_per_unit: params.rows_per_unit.clone(),
+                estimate_rows: params.estimate_rows,
+                fabric: params.fabric,
+            },
+        }
+    }
+
+    pub fn size(name: String, shape: Shape, params: &Params) -> Preset {
+        let dimensions = shape
+            .dimensions(params)
+            .into_iter()
+            .map(|dimension| {
+                let dimension = dimension.trim();
+                let has_unit = !dimension.ends_with(|c: char| c.is_ascii_digit());
+                if dimension.is_empty() || has_unit || params.units.trim().is_empty() {
+                    dimension.to_string()
+                } else {
+                    format!("{} {}", dimension, params.units.trim())
+                }
+            })
+            .collect();
+        Preset {
+            name,
+            values: PresetValues::Size { shape, dimensions },
+        }
+    }
+
+    // The presets that come with the generator, offered after the knitter's own.
+    pub fn built_in() -> Vec<Preset> {
+        vec![Preset {
+            name: String::from("Juggling balls, set of 3"),
+            values: PresetValues::BallSet {
+                diameter: String::from("7 cm"),
+                balls: String::from("3"),
+                weight: String::from("110"),
+            },
+        }]
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self.values {
+            PresetValues::Gauge { .. } => "Gauge",
+            PresetValues::Size { .. } => "Size",
+            PresetValues::BallSet { .. } => "Set",
+        }
+    }
+
+    pub fn applies_to(&self, shape: Shape) -> bool {
+        match &self.values {
+            PresetValues::Gauge { .. } => true,
+            PresetValues::Size { shape: s, .. } => *s == shape,
+            PresetValues::BallSet { .. } => shape == Shape::Sphere,
+        }
+    }
+
+    // Fills the preset into the form, leaving everything else as it was. A gauge saved in other
+    // units is converted to the form's units when both are known.
+    pub fn apply(&self, params: &Params) -> Params {
+        match &self.values {
+            PresetValues::Gauge {
+                units,
+                stitches_per_unit,
+                rows_per_unit,
+                estimate_rows,
+                fabric,
+            } => {
+                let mut params = Params {
+                    estimate_rows: *estimate_rows,
+                    fabric: *fabric,
+                    ..params.clone()
+                };
+                match (Unit::from_label(units), params.units()) {
+                    (Some(from), Some(to)) if from != to => {
+                        // Gauges are per unit, so they convert the opposite way to lengths:
+                        let factor = Unit::convert(1.0, to, from);
+                        let locale = params.locale;
+                        let convert = |text: &String| match parse_number(text, locale) {
+                            Ok(value) => locale.number((value * factor * 100.0).round() / 100.0),
+                            Err(_) => text.clone(),
+                        };
+                        params.stitches_per_unit = convert(stitches_per_unit);
+                        params.rows_per_unit = convert(rows_per_unit);
+                    }
+                    _ => {
+                        params.units = units.clone();
+                        params.stitches_per_unit = stitches_per_unit.clone();
+                        params.rows_per_unit = rows_per_unit.clone();
+                    }
+                }
+                params
+            }
+            PresetValues::Size { shape, dimensions } => shape.with_dimensions(params, dimensions),
+            PresetValues::BallSet {
+                diameter,
+                balls,
+                weight,
+            } => {
+                let mut params = params.clone();
+                params.sphere.diameter = diameter.clone();
+                params.sphere.depth = String::new();
+                params.sphere.balls = balls.clone();
+                params.sphere.weight = weight.clone();
+                params
+            }
+        }
+    }
+}
+
+pub enum PresetsMessage {
+    SetName(String),
+    SaveGauge,
+    SaveSize,
+    Delete(String),
+}
+
+#[derive(Properties, PartialEq)]
+pub struct PresetsProps {
+    pub shape: Shape,
+    pub params: Params,
+    pub on_apply: Callback<Preset>,
+}
+
+// Saves the gauge or size under a name, and fills saved ones back in from a dropdown.
+pub struct PresetsPanel {
+    presets: Vec<Preset>,
+    name: String,
+}
+
+impl Component for PresetsPanel {
+    type Message = PresetsMessage;
+    type Properties = PresetsProps;
+
+    fn create(_ctx: &yew::Context<Self>) -> Self {
+        Self {
+            presets: storage::load_presets(),
+            name: String::new(),
+        }
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> Html {
+        let shape = ctx.props().shape;
+        let applicable: Vec<Preset> = self
+            .presets
+            .iter()
+            .cloned()
+            .chain(Preset::built_in())
+            .filter(|p| p.applies_to(shape))
+            .collect();
+        let on_apply = {
+            let on_apply = ctx.props().on_apply.clone();
+            let applicable = applicable.clone();
+            Callback::from(move |e: Event| {
+                let select_el: HtmlSelectElement = e.target_unchecked_into();
+                let chosen = select_el.value().parse::<usize>().ok();
+                if let Some(preset) = chosen.and_then(|i| applicable.get(i)) {
+                    on_apply.emit(preset.clone());
+                }
+                // Back to the prompt, so the same preset can be chosen again:
+                select_el.set_value("");
+            })
+        };
+        let on_name_input = ctx.link().callback(move |e: InputEvent| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            PresetsMessage::SetName(input_el.value())
+        });
+        let no_name = self.name.trim().is_empty();
+        let presets = self.presets.iter().map(|preset| {
+            let name = preset.name.clone();
+            let on_delete = ctx
+                .link()
+                .callback(move |_: MouseEvent| PresetsMessage::Delete(name.clone()));
+            html! {
+                <li>
+                    {format!("{} ({}) ", preset.name, preset.kind().to_lowercase())}
+                    <button type="button" onclick={on_delete}>{"Delete"}</button>
+                </li>
+            }
+        });
+
+        html! {
+            <span class="presets">
+                <h3>{"Presets"}</h3>
+                <span>
+                    <select aria-label="Apply a preset" onchange={on_apply} disabled={applicable.is_empty()}>
+                        <option value="" selected=true>{"Apply a preset…"}</option>
+                        { for applicable.iter().enumerate().map(|(i, preset)| html! {
+                            <option value={i.to_string()}>{format!("{}: {}", preset.kind(), preset.name)}</option>
+                        }) }
+                    </select>
+                </span>
+                <span>
+                    <input aria-label="Preset name" type="text" placeholder="Preset name" value={self.name.clone()} oninput={on_name_input}/>
+                    <button type="button" onclick={ctx.link().callback(|_| PresetsMessage::SaveGauge)} disabled={no_name}>{"Save gauge"}</button>
+                    <button type="button" onclick={ctx.link().callback(|_| PresetsMessage::SaveSize)} disabled={no_name}>{"Save size"}</button>
+                </span>
+                if !self.presets.is_empty() {
+                    <details>
+                        <summary>{"Saved presets"}</summary>
+                        <ul>{ for presets }</ul>
+                    </details>
+                }
+            </span>
+        }
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        let name = self.name.trim().to_string();
+        let props = ctx.props();
+        match msg {
+            PresetsMessage::SetName(val) => self.name = val,
+            PresetsMessage::SaveGauge => {
+                self.presets = storage::save_preset(Preset::gauge(name, &props.params));
+            }
+            PresetsMessage::SaveSize => {
+                self.presets = storage::save_preset(Preset::size(name, props.shape, &props.params));
+            }
+            PresetsMessage::Delete(name) => self.presets = storage::delete_preset(&name),
+        }
+        true
+    }
+}
@@ -0,0 +1,198 @@
+use crate::gauge::Gauge;
+use crate::locale::Locale;
+use crate::pattern::Pattern;
+use crate::png;
+use std::f64::consts::PI;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+// The preview is scaled to fit a square this many pixels across.
+const SIZE: f64 = 240.0;
+
+// The light theme's colors for the silhouette, for drawing it as a picture to download.
+const FILL: &str = "#f3e6d8";
+const STROKE: &str = "#8a6d4d";
+
+// A side-on silhouette of the finished piece, drawn from the width of each row, with the rows
+// as horizontal lines. Row 1 is at the bottom.
+pub fn cross_section(pattern: &Pattern, gauge: Gauge, unit_label: &str, locale: Locale) -> Html {
+    let row_height = 1.0 / gauge.rows_per_unit;
+    let widths: Vec<f64> = pattern
+        .rows
+        .iter()
+        .map(|row| f64::from(row.stitches) / gauge.stitches_per_unit / PI)
+        .collect();
+    let widest = widths.iter().cloned().fold(0.0, f64::max);
+    let height = row_height * widths.len() as f64;
+    if widest <= 0.0 || height <= 0.0 {
+        return html! {};
+    }
+    let scale = SIZE / widest.max(height);
+    let (svg_width, svg_height) = (widest * scale, height * scale);
+    // Each row is drawn at its middle, centred horizontally:
+    let rows: Vec<(f64, f64, f64)> = widths
+        .iter()
+        .enumerate()
+        .map(|(i, width)| {
+            let y = svg_height - (i as f64 + 0.5) * row_height * scale;
+            let half = width * scale / 2.0;
+            (svg_width / 2.0 - half, svg_width / 2.0 + half, y)
+        })
+        .collect();
+    let outline = rows
+        .iter()
+        .map(|(left, _, y)| (*left, *y))
+        .chain(rows.iter().rev().map(|(_, right, y)| (*right, *y)))
+        .map(|(x, y)| format!("{:.1},{:.1}", x, y))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let on_download = {
+        let rows = rows.clone();
+        Callback::from(move |_: MouseEvent| {
+            png::download("preview.png", svg_width, svg_height, |context| {
+                context.set_fill_style_str(FILL);
+                context.set_stroke_style_str(STROKE);
+                context.set_line_width(0.5);
+                context.begin_path();
+                let outline = rows
+                    .iter()
+                    .map(|(left, _, y)| (*left, *y))
+                    .chain(rows.iter().rev().map(|(_, right, y)| (*right, *y)));
+                for (x, y) in outline {
+                    context.line_to(x, y);
+                }
+                context.close_path();
+                context.fill();
+                context.stroke();
+                for (left, right, y) in &rows {
+                    context.begin_path();
+                    context.move_to(*left, *y);
+                    context.line_to(*right, *y);
+                    context.stroke();
+                }
+            });
+        })
+    };
+    let lines = rows.iter().map(|(left, right, y)| {
+        html! {
+            <line x1={format!("{:.1}", left)} x2={format!("{:.1}", right)}
+                  y1={format!("{:.1}", y)} y2={format!("{:.1}", y)} />
+        }
+    });
+
+    html! {
+        <figure class="cross-section">
+            <svg width={format!("{:.0}", svg_width)} height={format!("{:.0}", svg_height)}
+                 viewBox={format!("0 0 {:.1} {:.1}", svg_width, svg_height)}>
+                <polygon points={outline} />
+                { for lines }
+            </svg>
+            <figcaption>
+                {format!(
+                    "{} {} wide, {} {} tall ",
+                    locale.decimal(widest, 1), unit_label, locale.decimal(height, 1), unit_label
+                )}
+                <button type="button" onclick={on_download}>{"Download (PNG)"}</button>
+            </figcaption>
+        </figure>
+    }
+}
+
+// Rings and meridians drawn in the 3D preview; more would only clutter it.
+const MAX_RINGS: usize = 30;
+const MERIDIANS: usize = 12;
+
+#[derive(Properties, PartialEq)]
+pub struct SolidPreviewProps {
+    pub pattern: Pattern,
+    pub gauge: Gauge,
+}
+
+// A wireframe of the finished piece as a surface of revolution, built from the radius of each row
+// and viewed from an adjustable tilt and turn.
+#[function_component(SolidPreview)]
+pub fn solid_preview(props: &SolidPreviewProps) -> Html {
+    let tilt = use_state(|| 25.0_f64);
+    let turn = use_state(|| 0.0_f64);
+    let gauge = props.gauge;
+    let row_height = 1.0 / gauge.rows_per_unit;
+    // (radius, height) of each row, around an axis through the middle:
+    let rings: Vec<(f64, f64)> = props
+        .pattern
+        .rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let radius = f64::from(row.stitches) / gauge.stitches_per_unit / (2.0 * PI);
+            (radius, (i as f64 + 0.5) * row_height)
+        })
+        .collect();
+    let widest = rings.iter().map(|(r, _)| *r).fold(0.0, f64::max);
+    let tallest = rings.last().map_or(0.0, |(_, z)| *z + row_height / 2.0);
+    if widest <= 0.0 {
+        return html! {};
+    }
+
+    let (sin_t, cos_t) = tilt.to_radians().sin_cos();
+    // Looking down at the piece, the far side of each ring appears higher up:
+    let up = |z: f64, depth: f64| z * cos_t + depth * sin_t;
+    let scale = SIZE / (2.0 * widest).max(tallest * cos_t + 2.0 * widest * sin_t);
+    let top = (tallest * cos_t + widest * sin_t) * scale;
+    let (svg_width, svg_height) = (2.0 * widest * scale, top + widest * sin_t * scale);
+    let to_svg = |x: f64, y: f64| (svg_width / 2.0 + x * scale, top - y * scale);
+
+    let step = rings.len().div_ceil(MAX_RINGS).max(1);
+    let ellipses = rings.iter().step_by(step).map(|(radius, z)| {
+        let (cx, cy) = to_svg(0.0, up(*z, 0.0));
+        html! {
+            <ellipse cx={format!("{:.1}", cx)} cy={format!("{:.1}", cy)}
+                     rx={format!("{:.1}", radius * scale)} ry={format!("{:.1}", radius * sin_t * scale)} />
+        }
+    });
+    let meridians = (0..MERIDIANS).map(|m| {
+        let angle = (m as f64 / MERIDIANS as f64) * 2.0 * PI + turn.to_radians();
+        let (sin_a, cos_a) = angle.sin_cos();
+        let points = rings
+            .iter()
+            .map(|(radius, z)| to_svg(radius * cos_a, up(*z, radius * sin_a)))
+            .map(|(x, y)| format!("{:.1},{:.1}", x, y))
+            .collect::<Vec<_>>()
+            .join(" ");
+        // Meridians on the far side are drawn fainter:
+        let class = if sin_a > 0.0 { "back" } else { "front" };
+        html! {<polyline {class} {points} />}
+    });
+
+    let on_tilt = {
+        let tilt = tilt.clone();
+        Callback::from(move |e: InputEvent| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            tilt.set(input_el.value_as_number());
+        })
+    };
+    let on_turn = {
+        let turn = turn.clone();
+        Callback::from(move |e: InputEvent| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            turn.set(input_el.value_as_number());
+        })
+    };
+
+    html! {
+        <figure class="solid-preview">
+            <svg width={format!("{:.0}", svg_width)} height={format!("{:.0}", svg_height)}
+                 viewBox={format!("0 0 {:.1} {:.1}", svg_width, svg_height)}>
+                { for ellipses }
+                { for meridians }
+            </svg>
+            <figcaption>
+                <label>{"Tilt "}
+                    <input type="range" min="0" max="90" value={tilt.to_string()} oninput={on_tilt}/>
+                </label>
+                <label>{" Turn "}
+                    <input type="range" min="0" max="360" value={turn.to_string()} oninput={on_turn}/>
+                </label>
+            </figcaption>
+        </figure>
+    }
+}
@@ -0,0 +1,195 @@
+use crate::diff::changelog;
+use crate::grading::sized_pattern;
+use crate::params::Params;
+use crate::pattern::Pattern;
+use crate::shapes::Shape;
+use crate::storage::{self, Project, Revision};
+use web_sys::js_sys::Date;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+pub enum ProjectsMessage {
+    SetName(String),
+    Save,
+    Delete(String),
+    Rename(String),
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ProjectsProps {
+    pub shape: Shape,
+    pub params: Params,
+    pub on_load: Callback<Project>,
+}
+
+fn pattern(shape: Shape, params: &Params) -> Option<Pattern> {
+    let gauge = params.gauge()?;
+    sized_pattern(shape, params, gauge, shape.size(params)).ok()
+}
+
+// What saving `after` over `before` changes, in the shape, the gauge and the pattern itself.
+fn changes(before: (Shape, &Params), after: (Shape, &Params)) -> Vec<String> {
+    let mut changes = Vec::new();
+    if before.0 != after.0 {
+        changes.push(format!("shape {}→{}", before.0.name(), after.0.name()));
+    }
+    let gauge =
+        |params: &Params| format!("{} st/{}", params.stitches_per_unit.trim(), params.units);
+    if gauge(before.1) != gauge(after.1) {
+        changes.push(format!("gauge {}→{}", gauge(before.1), gauge(after.1)));
+    }
+    match (pattern(before.0, before.1), pattern(after.0, after.1)) {
+        (Some(before), Some(after)) => changes.extend(changelog(&before, &after)),
+        (None, Some(_)) => changes.push(String::from("the earlier settings didn't make a pattern")),
+        (_, None) => changes.push(String::from("the new settings don't make a pattern")),
+    }
+    if changes.is_empty() {
+        changes.push(String::from("settings changed, pattern the same"));
+    }
+    changes
+}
+
+// Saves the current parameters under a name and lists saved projects to load, rename or delete.
+pub struct ProjectsPanel {
+    projects: Vec<Project>,
+    name: String,
+    // Why the last rename was refused.
+    error: Option<String>,
+}
+
+impl Component for ProjectsPanel {
+    type Message = ProjectsMessage;
+    type Properties = ProjectsProps;
+
+    fn create(_ctx: &yew::Context<Self>) -> Self {
+        Self {
+            projects: storage::load_projects(),
+            name: String::new(),
+            error: None,
+        }
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> Html {
+        let on_name_input = ctx.link().callback(move |e: InputEvent| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            ProjectsMessage::SetName(input_el.value())
+        });
+        let on_save = ctx.link().callback(|_: MouseEvent| ProjectsMessage::Save);
+        let projects = self.projects.iter().map(|project| {
+            let on_load = {
+                let project = project.clone();
+                ctx.props()
+                    .on_load
+                    .reform(move |_: MouseEvent| project.clone())
+            };
+            let name = project.name.clone();
+            let on_rename = ctx
+                .link()
+                .callback(move |_: MouseEvent| ProjectsMessage::Rename(name.clone()));
+            let name = project.name.clone();
+            let on_delete = ctx
+                .link()
+                .callback(move |_: MouseEvent| ProjectsMessage::Delete(name.clone()));
+            let revisions = project.revisions.iter().rev().map(|revision| {
+                let on_load = {
+                    let earlier = Project {
+                        name: project.name.clone(),
+                        shape: revision.shape,
+                        params: revision.params.clone(),
+                        revisions: Vec::new(),
+                    };
+                    ctx.props()
+                        .on_load
+                        .reform(move |_: MouseEvent| earlier.clone())
+                };
+                html! {
+                    <li>
+                        {format!(
+                            "Saved over {}: {} ",
+                            revision.replaced.get(..16).unwrap_or_default().replace('T', " "),
+                            revision.changes.join("; ")
+                        )}
+                        <button type="button" onclick={on_load}>{"Load"}</button>
+                    </li>
+                }
+            });
+            html! {
+                <li>
+                    {format!("{} ({}) ", project.name, project.shape.name())}
+                    <button type="button" onclick={on_load}>{"Load"}</button>
+                    <button type="button" onclick={on_rename}>{"Rename"}</button>
+                    <button type="button" onclick={on_delete}>{"Delete"}</button>
+                    if !project.revisions.is_empty() {
+                        <details>
+                            <summary>{format!("Earlier versions ({})", project.revisions.len())}</summary>
+                            <ul>{ for revisions }</ul>
+                        </details>
+                    }
+                </li>
+            }
+        });
+
+        html! {
+            <span class="projects">
+                <h3>{"Projects"}</h3>
+                <span>
+                    <input aria-label="Project name" type="text" placeholder="Project name" value={self.name.clone()} oninput={on_name_input}/>
+                    <button type="button" onclick={on_save} disabled={self.name.trim().is_empty()}>{"Save"}</button>
+                </span>
+                if let Some(error) = &self.error {
+                    <p class="field-error" role="alert">{error}</p>
+                }
+                <ul>{ for projects }</ul>
+            </span>
+        }
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        self.error = None;
+        match msg {
+            ProjectsMessage::SetName(val) => self.name = val,
+            ProjectsMessage::Save => {
+                let (shape, params) = (ctx.props().shape, &ctx.props().params);
+                let name = self.name.trim().to_string();
+                // Saving over a project keeps what it was as a revision:
+                let mut revisions = Vec::new();
+                if let Some(old) = self.projects.iter().find(|p| p.name == name) {
+                    revisions = old.revisions.clone();
+                    if old.shape != shape || old.params != *params {
+                        revisions.push(Revision {
+                            shape: old.shape,
+                            params: old.params.clone(),
+                            replaced: String::from(Date::new_0().to_iso_string()),
+                            changes: changes((old.shape, &old.params), (shape, params)),
+                        });
+                    }
+                }
+                self.projects = storage::save_project(Project {
+                    name,
+                    shape,
+                    params: params.clone(),
+                    revisions,
+                });
+            }
+            ProjectsMessage::Delete(name) => self.projects = storage::delete_project(&name),
+            ProjectsMessage::Rename(name) => {
+                let new_name = web_sys::window()
+                    .and_then(|w| {
+                        w.prompt_with_message_and_default("Rename project to:", &name)
+                            .ok()
+                    })
+                    .flatten()
+                    .map(|n| n.trim().to_string())
+                    .filter(|n| !n.is_empty() && *n != name);
+                let Some(new_name) = new_name else {
+                    return true;
+                };
+                match storage::rename_project(&name, &new_name) {
+                    Ok(projects) => self.projects = projects,
+                    Err(err) => self.error = Some(err),
+                }
+            }
+        }
+        true
+    }
+}
@@ -0,0 +1,179 @@
+use crate::export;
+use crate::gauge::Gauge;
+use crate::params::Params;
+use crate::shapes::{Design, Shape};
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::js_sys::{encode_uri_component, Object, Reflect, JSON};
+use web_sys::wasm_bindgen::{JsCast, JsValue};
+use web_sys::{Headers, HtmlInputElement, Request, RequestInit, Response};
+use yew::prelude::*;
+
+const API: &str = "https://api.ravelry.com";
+
+// Ravelry's ids for the crafts a project is made in.
+const CROCHET: u32 = 1;
+const KNITTING: u32 = 2;
+
+// The knitter's Ravelry username and an OAuth access token for it, from an app registered with
+// Ravelry's API. Kept in this browser only and sent nowhere but Ravelry.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RavelryAccount {
+    pub username: String,
+    pub token: String,
+}
+
+impl RavelryAccount {
+    fn is_complete(&self) -> bool {
+        !self.username.trim().is_empty() && !self.token.trim().is_empty()
+    }
+}
+
+// The address of a new project, or why it couldn't be made.
+async fn create_project(
+    account: RavelryAccount,
+    name: String,
+    craft: u32,
+    notes: String,
+) -> Result<String, String> {
+    let username = String::from(encode_uri_component(account.username.trim()));
+    let project = Object::new();
+    let _ = Reflect::set(&project, &"name".into(), &name.into());
+    let _ = Reflect::set(&project, &"craft_id".into(), &craft.into());
+    let _ = Reflect::set(&project, &"notes".into(), &notes.into());
+    let body =
+        JSON::stringify(&project).map_err(|_| String::from("Couldn't write the request."))?;
+
+    let headers = Headers::new().map_err(|_| String::from("Couldn't write the request."))?;
+    let _ = headers.set("Authorization", &format!("Bearer {}", account.token.trim()));
+    let _ = headers.set("Content-Type", "application/json");
+    let init = RequestInit::new();
+    init.set_method("POST");
+    init.set_headers_headers(&headers);
+    init.set_body(&body);
+    let url = format!("{}/projects/{}/create.json", API, username);
+    let request = Request::new_with_str_and_init(&url, &init)
+        .map_err(|_| String::from("Couldn't write the request."))?;
+
+    let window = web_sys::window().ok_or_else(|| String::from("No browser window."))?;
+    let response: Response = JsFuture::from(window.fetch_with_request(&request))
+        .await
+        .and_then(JsValue::dyn_into)
+        .map_err(|_| String::from("Couldn't reach Ravelry. Check your connection."))?;
+    match response.status() {
+        401 | 403 => {
+            return Err(String::from(
+                "Ravelry didn't accept the token; it may have expired.",
+            ))
+        }
+        status if !response.ok() => return Err(format!("Ravelry answered with error {}.", status)),
+        _ => {}
+    }
+    let json = JsFuture::from(
+        response
+            .json()
+            .map_err(|_| String::from("Couldn't read Ravelry's answer."))?,
+    )
+    .await
+    .map_err(|_| String::from("Couldn't read Ravelry's answer."))?;
+    let permalink = Reflect::get(&json, &"project".into())
+        .and_then(|project| Reflect::get(&project, &"permalink".into()))
+        .ok()
+        .and_then(|permalink| permalink.as_string())
+        .ok_or_else(|| String::from("Ravelry made the project but didn't say where."))?;
+    Ok(format!(
+        "https://www.ravelry.com/projects/{}/{}",
+        username, permalink
+    ))
+}
+
+#[derive(Properties, PartialEq)]
+pub struct RavelryProps {
+    pub shape: Shape,
+    pub design: Design,
+    pub params: Params,
+    pub gauge: Gauge,
+}
+
+// Starts a project in the knitter's Ravelry notebook for the pattern, named for it, with the
+// whole written pattern in its notes to edit there.
+#[function_component(RavelryUpload)]
+pub fn ravelry_upload(props: &RavelryProps) -> Html {
+    let account = use_state(storage::load_ravelry);
+    let sending = use_state(|| false);
+    let result = use_state(|| None::<Result<String, String>>);
+    let on_input = |set: fn(&mut RavelryAccount, String)| {
+        let account = account.clone();
+        Callback::from(move |e: InputEvent| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            let mut changed = (*account).clone();
+            set(&mut changed, input_el.value());
+            storage::save_ravelry(&changed);
+            account.set(changed);
+        })
+    };
+    let on_forget = {
+        let account = account.clone();
+        Callback::from(move |_: MouseEvent| {
+            storage::save_ravelry(&RavelryAccount::default());
+            account.set(RavelryAccount::default());
+        })
+    };
+    let on_send = {
+        let (shape, design, params, gauge) = (
+            props.shape,
+            props.design.clone(),
+            props.params.clone(),
+            props.gauge,
+        );
+        let (account, sending, result) = (account.clone(), sending.clone(), result.clone());
+        Callback::from(move |_: MouseEvent| {
+            let name = match params.metadata.title.trim() {
+                "" => format!("{} from the pattern generator", shape.name()),
+                title => title.to_string(),
+            };
+            let craft = match params.fabric.crochet() {
+                Some(_) => CROCHET,
+                None => KNITTING,
+            };
+            let notes = export::pattern_text(shape, &design, &params, gauge);
+            let (account, sending, result) = ((*account).clone(), sending.clone(), result.clone());
+            sending.set(true);
+            spawn_local(async move {
+                result.set(Some(create_project(account, name, craft, notes).await));
+                sending.set(false);
+            });
+        })
+    };
+    let outcome = match &*result {
+        None => html! {},
+        Some(Ok(url)) => html! {
+            <p role="status">{"Project started: "}<a href={url.clone()} target="_blank" rel="noopener">{url}</a></p>
+        },
+        Some(Err(err)) => html! {<p class="field-error" role="alert">{err}</p>},
+    };
+
+    html! {
+        <div class="ravelry">
+            <span>
+                <label>{"Ravelry username: "}</label>
+                <input aria-label="Ravelry username" type="text" value={account.username.clone()} oninput={on_input(|account, value| account.username = value)}/>
+            </span>
+            <span>
+                <label>{"Access token: "}</label>
+                <input aria-label="Ravelry access token" type="password" autocomplete="off" value={account.token.clone()} oninput={on_input(|account, value| account.token = value)}/>
+            </span>
+            <p>
+                <button type="button" onclick={on_send} disabled={!account.is_complete() || *sending}>
+                    { if *sending { "Sending…" } else { "Start a Ravelry project" } }
+                </button>
+                <button type="button" onclick={on_forget} disabled={*account == RavelryAccount::default()}>{"Forget account"}</button>
+            </p>
+            { outcome }
+            <span class="field-note">
+                {"The token comes from an app registered at ravelry.com/pro/developer, and is kept in this browser only. The project gets the pattern's name, craft and written instructions in its notes."}
+            </span>
+        </div>
+    }
+}
@@ -0,0 +1,40 @@
+use crate::storage::{Recent, Session};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct RecentPanelProps {
+    pub recent: Vec<Recent>,
+    pub on_load: Callback<Session>,
+}
+
+// The last few patterns generated, newest first, to go back to with a click.
+#[function_component(RecentPanel)]
+pub fn recent_panel(props: &RecentPanelProps) -> Html {
+    if props.recent.is_empty() {
+        return html! {};
+    }
+    let items = props.recent.iter().map(|recent| {
+        let session = recent.session.clone();
+        let on_load = props.on_load.reform(move |_: MouseEvent| session.clone());
+        let size = recent
+            .session
+            .shape
+            .dimensions(&recent.session.params)
+            .join(" × ");
+        html! {
+            <li>
+                <button type="button" onclick={on_load}>
+                    {format!("{} {} {}", recent.session.shape.name(), size, recent.session.params.units)}
+                </button>
+                {format!(" {} rows, up to {} st", recent.rows, recent.widest)}
+            </li>
+        }
+    });
+
+    html! {
+        <details class="panel recent">
+            <summary><h3>{"Recent"}</h3></summary>
+            <ul>{ for items }</ul>
+        </details>
+    }
+}
@@ -0,0 +1,106 @@
+use crate::pattern::{Pattern, Row};
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct ResumeFormProps {
+    pub pattern: Pattern,
+    // The rows already knitted, the last with the stitches actually on the needles, to keep while
+    // the rest is worked out again from there.
+    pub on_resume: Callback<Vec<Row>>,
+}
+
+// The rows up to and including row `number` as already knitted, ending with `stitches` on the
+// needles.
+pub fn knitted_rows(pattern: &Pattern, number: usize, stitches: i32) -> Vec<Row> {
+    let mut knit: Vec<Row> = pattern
+        .rows
+        .iter()
+        .take_while(|row| row.number <= number)
+        .cloned()
+        .collect();
+    if let Some(last) = knit.last_mut() {
+        last.stitches = stitches;
+    }
+    knit
+}
+
+// For picking up a piece partway, as when adapting or repairing one already on the needles: the
+// knitter enters the row they have finished and the stitches they have, and the rest of the
+// pattern is worked out again from there.
+#[function_component(ResumeForm)]
+pub fn resume_form(props: &ResumeFormProps) -> Html {
+    let pattern = &props.pattern;
+    let row = use_state(String::new);
+    let stitches = use_state(String::new);
+    let on_input = |state: &UseStateHandle<String>| {
+        let state = state.clone();
+        Callback::from(move |e: InputEvent| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            state.set(input_el.value());
+        })
+    };
+
+    let word = pattern.word().to_lowercase();
+    let number = row
+        .trim()
+        .parse()
+        .ok()
+        .map(|shown| pattern.numbering.row(shown))
+        .filter(|number| (1..pattern.rows.len()).contains(number));
+    let count = stitches
+        .trim()
+        .parse::<i32>()
+        .ok()
+        .filter(|count| *count > 0);
+    let result = match (number, count) {
+        (None, _) => html! {
+            <p class="field-note">{format!(
+                "Enter the last {} you finished, before the pattern's last.",
+                word
+            )}</p>
+        },
+        (_, None) => html! {
+            <p class="field-note">{"Enter how many stitches are on the needles."}</p>
+        },
+        (Some(number), Some(count)) => {
+            let planned = pattern.rows[number - 1].stitches;
+            let on_resume = {
+                let (on_resume, knit) = (
+                    props.on_resume.clone(),
+                    knitted_rows(pattern, number, count),
+                );
+                Callback::from(move |_: MouseEvent| on_resume.emit(knit.clone()))
+            };
+            html! {
+                <>
+                    if count != planned {
+                        <p>{format!(
+                            "The pattern has {} stitches there; the difference of {} is made up a little at a time over the {}s left.",
+                            planned,
+                            (count - planned).abs(),
+                            word
+                        )}</p>
+                    }
+                    <button type="button" onclick={on_resume}>
+                        {format!("Work out the rest from {} {}", word, row.trim())}
+                    </button>
+                </>
+            }
+        }
+    };
+
+    html! {
+        <div class="tension-wizard">
+            <span>
+                <label>{format!("Last {} finished: ", word)}</label>
+                <input aria-label={format!("Last {} finished", word)} type="text" size="4" value={(*row).clone()} oninput={on_input(&row)}/>
+            </span>
+            <span>
+                <label>{"Stitches on the needles: "}</label>
+                <input aria-label="Stitches on the needles" type="text" size="4" value={(*stitches).clone()} oninput={on_input(&stitches)}/>
+            </span>
+            { result }
+        </div>
+    }
+}
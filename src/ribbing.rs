@@ -0,0 +1,126 @@
+use crate::form::parse_count;
+use crate::params::Params;
+use crate::pattern::{Pattern, Shaping};
+use crate::stitchlib::{self, StitchPattern};
+use serde::{Deserialize, Serialize};
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum Rib {
+    #[default]
+    None,
+    K1P1,
+    K2P2,
+}
+
+impl Rib {
+    pub const ALL: [Rib; 3] = [Rib::None, Rib::K1P1, Rib::K2P2];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Rib::None => "none",
+            Rib::K1P1 => "k1p1",
+            Rib::K2P2 => "k2p2",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.stitch().map_or("No ribbing", |stitch| stitch.name)
+    }
+
+    pub fn from_key(key: &str) -> Option<Rib> {
+        Rib::ALL.into_iter().find(|r| r.key() == key)
+    }
+
+    fn stitch(&self) -> Option<&'static StitchPattern> {
+        stitchlib::find(self.key())
+    }
+
+    // The cast on must be a multiple of this for the rib to repeat evenly around.
+    pub fn multiple(&self) -> i32 {
+        self.stitch().map_or(1, |stitch| stitch.width)
+    }
+}
+
+// Ribbing at the edges of a tube: the first rounds after casting on, and for tubes that end
+// in a bind off, the last rounds too.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RibParams {
+    pub rib: Rib,
+    pub first_rows: String,
+    pub last_rows: String,
+}
+
+// Works the plain rounds at the start and end of a pattern in rib.
+pub fn apply(pattern: &mut Pattern, params: &RibParams) {
+    let Some(stitch) = params.rib.stitch() else {
+        return;
+    };
+    let first = parse_count(&params.first_rows).unwrap_or(0);
+    let last = parse_count(&params.last_rows).unwrap_or(0);
+    let plain = |shaping: &Shaping| *shaping == Shaping::Plain;
+    let start = pattern.rows.iter_mut().skip(1).take(first);
+    for row in start.take_while(|row| plain(&row.shaping)) {
+        row.shaping = Shaping::Texture { stitch, row: 0 };
+    }
+    let end = pattern.rows.iter_mut().skip(first + 1).rev().take(last);
+    for row in end.take_while(|row| plain(&row.shaping)) {
+        row.shaping = Shaping::Texture { stitch, row: 0 };
+    }
+}
+
+// Choosing the rib and how many rounds of it; `closed` shapes end in a crown rather than a bind
+// off, so they only have a brim.
+pub fn form(params: &Params, closed: bool, on_change: Callback<RibParams>) -> Html {
+    let rib_params = params.rib.clone();
+    let on_rib_change = {
+        let on_change = on_change.clone();
+        Callback::from(move |e: Event| {
+            let select_el: HtmlSelectElement = e.target_unchecked_into();
+            if let Some(rib) = Rib::from_key(&select_el.value()) {
+                on_change.emit(RibParams {
+                    rib,
+                    ..rib_params.clone()
+                });
+            }
+        })
+    };
+    let rib_params = params.rib.clone();
+    let on_first_input = on_change.reform(move |e: InputEvent| {
+        let input_el: HtmlInputElement = e.target_unchecked_into();
+        RibParams {
+            first_rows: input_el.value(),
+            ..rib_params.clone()
+        }
+    });
+    let rib_params = params.rib.clone();
+    let on_last_input = on_change.reform(move |e: InputEvent| {
+        let input_el: HtmlInputElement = e.target_unchecked_into();
+        RibParams {
+            last_rows: input_el.value(),
+            ..rib_params.clone()
+        }
+    });
+    let ribs = Rib::ALL.iter().map(|rib| {
+        html! {
+            <option value={rib.key()} selected={*rib == params.rib.rib}>{rib.name()}</option>
+        }
+    });
+    html! {
+        <span>
+            <label>{"Ribbing: "}</label>
+            <select aria-label="Ribbing" onchange={on_rib_change}>{ for ribs }</select>
+            if params.rib.rib != Rib::None {
+                <label>{if closed { " for the first " } else { " first " }}</label>
+                <input aria-label="Ribbed rows at the start" type="number" min="0" size="3" value={params.rib.first_rows.clone()} oninput={on_first_input}/>
+                if !closed {
+                    <label>{" and last "}</label>
+                    <input aria-label="Ribbed rows at the end" type="number" min="0" size="3" value={params.rib.last_rows.clone()} oninput={on_last_input}/>
+                }
+                <label>{" rounds"}</label>
+            }
+        </span>
+    }
+}
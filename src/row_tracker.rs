@@ -0,0 +1,271 @@
+use crate::explain::explain_row;
+use crate::gauge::Gauge;
+use crate::knitting_mode::KnittingMode;
+use crate::locale::Locale;
+use crate::parse::parse_number;
+use crate::pattern::Pattern;
+use crate::storage;
+use crate::timing;
+use std::collections::BTreeMap;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+pub enum RowTrackerMessage {
+    Toggle(usize),
+    Reset,
+    Next,
+    Previous,
+    SetKnittingMode(bool),
+    EditNote(Option<usize>),
+    SetNote(usize, String),
+    SetSpeed(String),
+    SetExplain(bool),
+}
+
+#[derive(Properties, PartialEq)]
+pub struct RowTrackerProps {
+    pub pattern: Pattern,
+    pub gauge: Gauge,
+    pub unit_label: String,
+    pub locale: Locale,
+}
+
+// The row-by-row instructions with a checkbox and a note on each row. Progress and notes are saved
+// for each pattern, so closing the page and coming back to the same pattern picks up where you left
+// off.
+pub struct RowTracker {
+    pattern_id: String,
+    // Rows up to and including this number are done.
+    done: usize,
+    knitting_mode: bool,
+    notes: BTreeMap<usize, String>,
+    // The row whose note is open for editing.
+    editing: Option<usize>,
+    // Stitches per minute, as typed, for estimating the time left.
+    speed: String,
+    // Whether to say why each shaping row does what it does.
+    explain: bool,
+}
+
+impl RowTracker {
+    // How far through the pattern the knitter is, and how long the rest should take.
+    fn progress(&self, pattern: &Pattern, ctx: &yew::Context<Self>) -> Html {
+        let total = pattern.total_stitches();
+        if total == 0 {
+            return html! {};
+        }
+        let done = pattern.stitches_through(self.done);
+        let remaining = total - done;
+        let on_speed_input = ctx.link().callback(|e: InputEvent| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            RowTrackerMessage::SetSpeed(input_el.value())
+        });
+        let time_left = parse_number(&self.speed, ctx.props().locale)
+            .ok()
+            .filter(|speed| *speed > 0.0)
+            .map(|speed| format!("{} left", timing::describe(remaining, speed)));
+
+        html! {
+            <p class="progress">
+                {format!(
+                    "{:.0}% done, {} stitches to go. ",
+                    100.0 * done as f64 / total as f64,
+                    remaining
+                )}
+                <label>{"Stitches per minute: "}</label>
+                <input aria-label="Stitches per minute" type="text" size="4" placeholder="e.g. 20" value={self.speed.clone()} oninput={on_speed_input}/>
+                { for time_left.map(|t| format!(" {}", t)) }
+            </p>
+        }
+    }
+}
+
+impl Component for RowTracker {
+    type Message = RowTrackerMessage;
+    type Properties = RowTrackerProps;
+
+    fn create(ctx: &yew::Context<Self>) -> Self {
+        let pattern_id = ctx.props().pattern.id();
+        Self {
+            done: storage::load_progress(&pattern_id),
+            notes: storage::load_notes(&pattern_id),
+            pattern_id,
+            knitting_mode: false,
+            editing: None,
+            speed: storage::load_speed(),
+            explain: storage::load_explain(),
+        }
+    }
+
+    fn changed(&mut self, ctx: &yew::Context<Self>, _old_props: &Self::Properties) -> bool {
+        let pattern_id = ctx.props().pattern.id();
+        if pattern_id != self.pattern_id {
+            self.done = storage::load_progress(&pattern_id);
+            self.notes = storage::load_notes(&pattern_id);
+            self.editing = None;
+            self.pattern_id = pattern_id;
+        }
+        true
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> Html {
+        let pattern = &ctx.props().pattern;
+        let instructions = pattern.rows.iter().enumerate().map(|(i, row)| {
+            let number = row.number;
+            let on_edit_note = ctx.link().callback(move |_: MouseEvent| {
+                RowTrackerMessage::EditNote(Some(number))
+            });
+            let on_note_input = ctx.link().callback(move |e: InputEvent| {
+                let input_el: HtmlInputElement = e.target_unchecked_into();
+                RowTrackerMessage::SetNote(number, input_el.value())
+            });
+            let on_note_done = ctx
+                .link()
+                .callback(|_: MouseEvent| RowTrackerMessage::EditNote(None));
+            let explanation = self
+                .explain
+                .then(|| explain_row(pattern, i, ctx.props().gauge, &ctx.props().unit_label, ctx.props().locale))
+                .flatten();
+            let note = self.notes.get(&number);
+            let note = if self.editing == Some(number) {
+                html! {
+                    <div class="row-note">
+                        <input aria-label={format!("Note for {}", pattern.label(i))} type="text" placeholder="Note for this row" value={note.cloned().unwrap_or_default()} oninput={on_note_input}/>
+                        <button type="button" onclick={on_note_done}>{"Done"}</button>
+                    </div>
+                }
+            } else if let Some(note) = note {
+                html! {<button type="button" class="row-note" onclick={on_edit_note}><em>{note}</em></button>}
+            } else {
+                html! {<button type="button" class="add-note" onclick={on_edit_note}>{"Note"}</button>}
+            };
+            let onchange = ctx
+                .link()
+                .callback(move |_: Event| RowTrackerMessage::Toggle(number));
+            let class = if number == self.done + 1 {
+                "current-row"
+            } else if number <= self.done {
+                "done-row"
+            } else {
+                ""
+            };
+            let current = (number == self.done + 1).then_some("step");
+            html! {
+                <li {class} aria-current={current}>
+                    <label>
+                        <input type="checkbox" checked={number <= self.done} {onchange}/>
+                        {pattern.written_row(i)}
+                    </label>
+                    if let Some(explanation) = explanation {
+                        <div class="row-explanation">{explanation}</div>
+                    }
+                    {note}
+                </li>
+            }
+        });
+        let total = pattern.rows.last().map_or(0, |row| row.number);
+        let on_reset = ctx
+            .link()
+            .callback(|_: MouseEvent| RowTrackerMessage::Reset);
+        let current = pattern
+            .rows
+            .iter()
+            .position(|row| row.number == self.done + 1);
+        if self.knitting_mode {
+            return html! {
+                <KnittingMode
+                    instruction={current.map(|i| pattern.instruction(i))}
+                    note={current.and_then(|i| self.notes.get(&pattern.rows[i].number).cloned())}
+                    done={self.done.min(total)}
+                    {total}
+                    on_next={ctx.link().callback(|_| RowTrackerMessage::Next)}
+                    on_previous={ctx.link().callback(|_| RowTrackerMessage::Previous)}
+                    on_exit={ctx.link().callback(|_| RowTrackerMessage::SetKnittingMode(false))}
+                />
+            };
+        }
+        let on_explain_toggle = ctx.link().callback(|e: Event| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            RowTrackerMessage::SetExplain(input_el.checked())
+        });
+        let on_knitting_mode = ctx
+            .link()
+            .callback(|_: MouseEvent| RowTrackerMessage::SetKnittingMode(true));
+        // Kept in view while scrolling, so the row being worked is always at hand:
+        let current_bar = current.map(|i| {
+            let number = pattern.rows[i].number;
+            let on_done = ctx
+                .link()
+                .callback(move |_: MouseEvent| RowTrackerMessage::Toggle(number));
+            html! {
+                <div class="current-row-bar">
+                    <span>{pattern.instruction(i)}</span>
+                    <button type="button" onclick={on_done}>{"Done"}</button>
+                </div>
+            }
+        });
+
+        html! {
+            <div>
+                <p>
+                    <button type="button" onclick={on_knitting_mode}>{"Knitting mode"}</button>
+                    <label>
+                        <input type="checkbox" checked={self.explain} onchange={on_explain_toggle}/>
+                        {" Explain the shaping"}
+                    </label>
+                </p>
+                if self.done > 0 {
+                    <p>
+                        {format!("{} of {} {}s done ", self.done.min(total), total, pattern.word().to_lowercase())}
+                        <button type="button" onclick={on_reset}>{"Start over"}</button>
+                    </p>
+                }
+                { self.progress(pattern, ctx) }
+                <ul class="row-tracker">{ for instructions }</ul>
+                { for current_bar }
+            </div>
+        }
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        let total = ctx.props().pattern.rows.last().map_or(0, |row| row.number);
+        self.done = match msg {
+            // Ticking a row finishes every row before it; unticking it goes back to the row before.
+            RowTrackerMessage::Toggle(number) if number <= self.done => number - 1,
+            RowTrackerMessage::Toggle(number) => number,
+            RowTrackerMessage::Reset => 0,
+            RowTrackerMessage::Next => (self.done + 1).min(total),
+            RowTrackerMessage::Previous => self.done.saturating_sub(1),
+            RowTrackerMessage::SetKnittingMode(on) => {
+                self.knitting_mode = on;
+                return true;
+            }
+            RowTrackerMessage::EditNote(number) => {
+                self.editing = number;
+                return true;
+            }
+            RowTrackerMessage::SetExplain(explain) => {
+                storage::save_explain(explain);
+                self.explain = explain;
+                return true;
+            }
+            RowTrackerMessage::SetSpeed(speed) => {
+                storage::save_speed(&speed);
+                self.speed = speed;
+                return true;
+            }
+            RowTrackerMessage::SetNote(number, note) => {
+                if note.trim().is_empty() {
+                    self.notes.remove(&number);
+                } else {
+                    self.notes.insert(number, note);
+                }
+                storage::save_notes(&self.pattern_id, &self.notes);
+                return true;
+            }
+        };
+        storage::save_progress(&self.pattern_id, self.done);
+        storage::record_knitting(&ctx.props().pattern, self.done);
+        true
+    }
+}
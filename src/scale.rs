@@ -0,0 +1,67 @@
+use crate::locale::Locale;
+use crate::params::Params;
+use crate::parse::parse_number;
+use crate::shapes::Shape;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct ScaleControlProps {
+    pub shape: Shape,
+    pub params: Params,
+    // Called with the params for the scaled shape.
+    pub on_scale: Callback<Params>,
+}
+
+// The percentage entered, as "150" or "150%".
+fn percent(text: &str, locale: Locale) -> Option<f64> {
+    parse_number(text.trim().trim_end_matches('%'), locale)
+        .ok()
+        .filter(|percent| *percent > 0.0)
+}
+
+// Makes the whole piece bigger or smaller by a percentage at the same gauge, showing the finished
+// size it will come out before it is applied.
+#[function_component(ScaleControl)]
+pub fn scale_control(props: &ScaleControlProps) -> Html {
+    let text = use_state(String::new);
+    let on_input = {
+        let text = text.clone();
+        Callback::from(move |e: InputEvent| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            text.set(input_el.value());
+        })
+    };
+    let scaled = percent(&text, props.params.locale)
+        .and_then(|percent| props.shape.scaled(&props.params, percent));
+    let on_scale = {
+        let (scaled, text, on_scale) = (scaled.clone(), text.clone(), props.on_scale.clone());
+        Callback::from(move |_: MouseEvent| {
+            if let Some(scaled) = scaled.clone() {
+                text.set(String::new());
+                on_scale.emit(scaled);
+            }
+        })
+    };
+    let note = match (&scaled, text.trim()) {
+        (_, "") => None,
+        (Some(scaled), _) => Some(props.shape.summary(scaled)),
+        (None, _) if percent(&text, props.params.locale).is_none() => {
+            Some(String::from("Enter a percentage, such as 150."))
+        }
+        (None, _) => Some(format!(
+            "Enter the {} first.",
+            props.shape.size_name().to_lowercase()
+        )),
+    };
+
+    html! {
+        <span>
+            <label>{"Scale by: "}</label>
+            <input aria-label="Scale by percent" type="text" size="4" placeholder="150" value={(*text).clone()} oninput={on_input}/>
+            {"% "}
+            <button type="button" onclick={on_scale} disabled={scaled.is_none()}>{"Scale"}</button>
+            { for note.map(|note| html! {<span class="field-note">{note}</span>}) }
+        </span>
+    }
+}
@@ -0,0 +1,88 @@
+use crate::form::parse_count;
+use crate::gauge::Gauge;
+use crate::params::Params;
+use crate::parse::parse_number;
+use crate::pattern::Pattern;
+use crate::yarn::yarn_per_stitch;
+
+// Starting points tried when looking for the one that lines the color changes up with rows.
+const START_STEPS: usize = 200;
+
+// Where the colors of a self-striping yarn fall on a pattern.
+pub struct Plan {
+    // The color each row is mostly worked in.
+    pub colors: Vec<usize>,
+    // Yarn to pull off and discard before casting on, in yarn units (yards or meters).
+    pub pull_off: f64,
+    pub yarn_unit: String,
+}
+
+// Yarn used by the end of each row, in yarn units.
+fn row_ends(pattern: &Pattern, per_stitch: f64) -> Vec<f64> {
+    pattern
+        .rows
+        .iter()
+        .scan(0.0, |used, row| {
+            *used += f64::from(row.stitches) * per_stitch;
+            Some(*used)
+        })
+        .collect()
+}
+
+// How far, in total, the color changes fall from the nearest end of a row when the yarn starts
+// `start` into its first color.
+fn misalignment(ends: &[f64], section: f64, start: f64) -> f64 {
+    let total = ends.last().copied().unwrap_or(0.0);
+    (1..)
+        .map(|k| k as f64 * section - start)
+        .take_while(|change| *change < total)
+        .filter(|change| *change > 0.0)
+        .map(|change| {
+            ends.iter()
+                .map(|end| (end - change).abs())
+                .fold(f64::INFINITY, f64::min)
+        })
+        .sum()
+}
+
+// Follows the yarn through the pattern, changing color every `section` of yarn and cycling
+// through `sections` colors. When `align` is set the yarn is started part way into its first
+// color so that the changes fall as near the ends of rows as they can, giving clean stripes.
+pub fn plan(pattern: &Pattern, params: &Params, gauge: Gauge) -> Option<Plan> {
+    let section = parse_number(&params.self_striping_length, params.locale)
+        .ok()
+        .filter(|l| *l > 0.0)?;
+    let sections = parse_count(&params.self_striping_colors)
+        .filter(|c| *c > 1)
+        .unwrap_or(2);
+    let (yarn_unit, per_yarn_unit) = params
+        .units()
+        .map_or((params.units.as_str(), 1.0), |units| units.yarn_unit());
+    let per_stitch = yarn_per_stitch(gauge.stitches_per_unit, gauge.rows_per_unit) / per_yarn_unit;
+    let ends = row_ends(pattern, per_stitch);
+    let pull_off = if params.self_striping_align {
+        (0..START_STEPS)
+            .map(|step| step as f64 * section / START_STEPS as f64)
+            .min_by(|a, b| {
+                misalignment(&ends, section, *a).total_cmp(&misalignment(&ends, section, *b))
+            })
+            .unwrap_or(0.0)
+    } else {
+        0.0
+    };
+    // Each row takes the color at the middle of its yarn:
+    let colors = pattern
+        .rows
+        .iter()
+        .zip(&ends)
+        .map(|(row, end)| {
+            let middle = end - f64::from(row.stitches) * per_stitch / 2.0 + pull_off;
+            (middle / section) as usize % sections
+        })
+        .collect();
+    Some(Plan {
+        colors,
+        pull_off,
+        yarn_unit: yarn_unit.to_string(),
+    })
+}
@@ -0,0 +1,159 @@
+use super::Design;
+use crate::abbreviations::Abbreviations;
+use crate::cables;
+use crate::finishing::{self, BlockingForm};
+use crate::fit;
+use crate::form::field_message;
+use crate::gauge::Gauge;
+use crate::locale::Locale;
+use crate::number_input::{NumberInput, Scale};
+use crate::numbering::Numbering;
+use crate::params::Params;
+use crate::parse::{parse_length, ParseError};
+use crate::pattern::{Pattern, Row, Shaping};
+use crate::phrasing::Phrasing;
+use crate::ribbing;
+use crate::units::Unit;
+use crate::validate::{Field, Validation};
+use serde::{Deserialize, Serialize};
+use yew::prelude::*;
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CylinderParams {
+    pub circumference: String,
+    pub length: String,
+}
+
+impl CylinderParams {
+    pub fn circumference(&self, units: Option<Unit>, locale: Locale) -> Result<f64, ParseError> {
+        parse_length(&self.circumference, units, locale)
+    }
+
+    pub fn length(&self, units: Option<Unit>, locale: Locale) -> Result<f64, ParseError> {
+        parse_length(&self.length, units, locale)
+    }
+}
+
+// Rounded to a whole number of `multiple`s, so ribbing and stitch patterns repeat evenly around.
+pub fn cast_on(circumference: f64, stitches_per_unit: f64, multiple: i32) -> i32 {
+    fit::nearest(circumference * stitches_per_unit, multiple, 1)
+}
+
+pub fn total_rows(length: f64, rows_per_unit: f64) -> i32 {
+    ((length * rows_per_unit).round() as i32).max(1)
+}
+
+// A straight tube worked in the round: cast on, then knit until it is long enough.
+pub fn pattern(circumference: f64, length: f64, gauge: Gauge, multiple: i32) -> Pattern {
+    let stitches = cast_on(circumference, gauge.stitches_per_unit, multiple);
+    let mut rows = vec![Row::new(1, stitches, Shaping::CastOn)];
+    for number in 2..=total_rows(length, gauge.rows_per_unit) as usize {
+        rows.push(Row::new(number, stitches, Shaping::Plain));
+    }
+    Pattern {
+        rows,
+        numbering: Numbering::default(),
+        two_at_a_time: false,
+        abbreviations: Abbreviations::default(),
+        phrasing: Phrasing::default(),
+    }
+}
+
+pub fn validate(params: &Params, gauge: Option<Gauge>) -> Validation {
+    let mut validation = Validation::default();
+    let circumference = params
+        .cylinder
+        .circumference(params.units(), params.locale)
+        .ok();
+    let length = params.cylinder.length(params.units(), params.locale).ok();
+    validation.require_positive(Field::Circumference, circumference, "Circumference");
+    validation.require_positive(Field::Length, length, "Length");
+    if let (Some(circumference), Some(length), Some(gauge), false) =
+        (circumference, length, gauge, validation.has_errors())
+    {
+        validation.check_size(
+            circumference * gauge.stitches_per_unit,
+            length * gauge.rows_per_unit,
+        );
+        let multiple = fit::multiple(params, params.rib.rib.multiple());
+        validation.check_first_row(Some(cast_on(
+            circumference,
+            gauge.stitches_per_unit,
+            multiple,
+        )));
+    }
+    validation
+}
+
+pub fn design(params: &Params, gauge: Gauge) -> Option<Design> {
+    let circumference = params
+        .cylinder
+        .circumference(params.units(), params.locale)
+        .ok()?;
+    let length = params.cylinder.length(params.units(), params.locale).ok()?;
+    let mut finishing = vec![String::from("Bind off all stitches loosely.")];
+    finishing.extend(finishing::notes(
+        params.fiber,
+        BlockingForm::Tube {
+            circumference,
+            length,
+        },
+        &params.units,
+        params.locale,
+    ));
+    let multiple = fit::multiple(params, params.rib.rib.multiple());
+    let mut pattern = pattern(circumference, length, gauge, multiple);
+    ribbing::apply(&mut pattern, &params.rib);
+    cables::apply(&mut pattern, params.cable);
+    Some(Design {
+        pattern,
+        finishing,
+        volume: None,
+        weight: None,
+        pieces: 1,
+        chart: None,
+        profile: Vec::new(),
+    })
+}
+
+pub fn form(params: &Params, validation: &Validation, on_change: Callback<CylinderParams>) -> Html {
+    let circumference = params.cylinder.circumference(params.units(), params.locale);
+    let length = params.cylinder.length(params.units(), params.locale);
+    let cylinder = params.cylinder.clone();
+    let on_circumference_input = on_change.reform(move |circumference| CylinderParams {
+        circumference,
+        ..cylinder.clone()
+    });
+    let cylinder = params.cylinder.clone();
+    let on_length_input = on_change.reform(move |length| CylinderParams {
+        length,
+        ..cylinder.clone()
+    });
+    html! {
+        <>
+            <NumberInput
+                label={"Circumference: "}
+                placeholder={"Around the tube"}
+                value={params.cylinder.circumference.clone()}
+                current={circumference.as_ref().ok().copied()}
+                scale={Scale::for_length(params.units(), 60.0)}
+                locale={params.locale}
+                oninput={on_circumference_input}
+            >
+                { field_message(&params.cylinder.circumference, &circumference, &params.units, params.locale, validation, Field::Circumference) }
+            </NumberInput>
+            <NumberInput
+                label={"Length: "}
+                placeholder={"Length of tube"}
+                value={params.cylinder.length.clone()}
+                current={length.as_ref().ok().copied()}
+                scale={Scale::for_length(params.units(), 60.0)}
+                locale={params.locale}
+                oninput={on_length_input}
+            >
+                { field_message(&params.cylinder.length, &length, &params.units, params.locale, validation, Field::Length) }
+            </NumberInput>
+        </>
+    }
+}
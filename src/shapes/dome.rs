@@ -0,0 +1,281 @@
+use super::sphere::{self, Cadence, RowSpacing};
+use super::Design;
+use crate::diagnostics::Sample;
+use crate::finishing::{self, BlockingForm};
+use crate::form::field_message;
+use crate::gauge::Gauge;
+use crate::locale::Locale;
+use crate::number_input::{NumberInput, Scale};
+use crate::params::Params;
+use crate::parse::{parse_length, parse_number, ParseError};
+use crate::pattern::{Pattern, Row, Shaping};
+use crate::stuffing;
+use crate::units::Unit;
+use crate::validate::{Field, Validation};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+
+// How the flat base closes the dome.
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum Base {
+    // Worked straight on from the dome's last round, decreasing to the center.
+    #[default]
+    KnitOn,
+    // Worked separately from the center out and sewn to the dome's bound-off edge.
+    Sewn,
+}
+
+impl Base {
+    pub const ALL: [Base; 2] = [Base::KnitOn, Base::Sewn];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Base::KnitOn => "knit-on",
+            Base::Sewn => "sewn",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Base::KnitOn => "Worked on from the dome",
+            Base::Sewn => "Made separately and sewn on",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Base> {
+        Base::ALL.into_iter().find(|b| b.key() == key)
+    }
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DomeParams {
+    pub diameter: String,
+    pub cadence: Cadence,
+    pub base: Base,
+    // What it should weigh in grams, made up with plastic pellets; left empty to stuff with
+    // fiberfill.
+    pub weight: String,
+}
+
+impl DomeParams {
+    pub fn diameter(&self, units: Option<Unit>, locale: Locale) -> Result<f64, ParseError> {
+        parse_length(&self.diameter, units, locale)
+    }
+
+    pub fn weight(&self, locale: Locale) -> Result<f64, ParseError> {
+        parse_number(&self.weight, locale)
+    }
+}
+
+// Each shaping round of the dome, from the top down to the edge: a hemisphere.
+fn dome_samples(diameter: f64, gauge: Gauge, every: i32) -> Vec<Sample> {
+    sphere::samples(
+        diameter,
+        diameter,
+        gauge.rows_per_unit,
+        gauge.stitches_per_unit,
+        every,
+        RowSpacing::Even,
+        |stitches| (stitches.round() as i32).max(1),
+    )
+}
+
+// Stitch count for each shaping round of the dome.
+fn dome_counts(diameter: f64, gauge: Gauge, every: i32) -> Vec<i32> {
+    dome_samples(diameter, gauge, every)
+        .iter()
+        .map(|sample| sample.rounded)
+        .collect()
+}
+
+// Stitch count for each round of a flat circle worked from its center out, a round's height wider
+// each time, ending on exactly `edge` stitches so it joins the dome's edge stitch for stitch.
+fn base_counts(edge: i32, gauge: Gauge) -> Vec<i32> {
+    let per_round = 2.0 * PI * gauge.stitches_per_unit / gauge.rows_per_unit;
+    let rounds = ((f64::from(edge) / per_round).round() as i32).max(1);
+    (1..=rounds)
+        .map(|round| {
+            ((f64::from(edge) * f64::from(round) / f64::from(rounds)).round() as i32).max(1)
+        })
+        .collect()
+}
+
+pub fn validate(params: &Params, gauge: Option<Gauge>) -> Validation {
+    let mut validation = Validation::default();
+    let diameter = params.dome.diameter(params.units(), params.locale).ok();
+    validation.require_positive(Field::Diameter, diameter, "Diameter");
+    validation.require_positive(
+        Field::Weight,
+        params.dome.weight(params.locale).ok(),
+        "Weight",
+    );
+    if let (Some(diameter), Some(gauge), false) = (diameter, gauge, validation.has_errors()) {
+        let every = params.dome.cadence.rows();
+        // The dome's quarter circle of rounds, then the base's radius of them:
+        let rounds =
+            every * sphere::shaping_rows_in_hemisphere(diameter, gauge.rows_per_unit, every);
+        validation.check_size(
+            sphere::max_stitches(diameter, diameter, gauge.stitches_per_unit),
+            f64::from(rounds) + diameter / 2.0 * gauge.rows_per_unit,
+        );
+        if !validation.has_errors() {
+            validation.check_first_row(dome_counts(diameter, gauge, every).first().copied());
+        }
+    }
+    validation
+}
+
+pub fn design(params: &Params, gauge: Gauge) -> Option<Design> {
+    let diameter = params.dome.diameter(params.units(), params.locale).ok()?;
+    let every = params.dome.cadence.rows();
+    let mut pattern = Pattern::from_shaping_counts(
+        every as usize,
+        dome_counts(diameter, gauge, every),
+        params.seed,
+        params.distribution,
+    );
+    let edge = pattern.rows.last()?.stitches;
+    let base = Pattern::from_shaping_counts(
+        1,
+        base_counts(edge, gauge),
+        params.seed,
+        params.distribution,
+    );
+    let mut finishing = finishing::notes(
+        params.fiber,
+        BlockingForm::Ball { diameter },
+        &params.units,
+        params.locale,
+    );
+    match params.dome.base {
+        Base::KnitOn => {
+            // A purl round turns the edge crisply from the dome to the base:
+            if params.fabric.crochet().is_none() {
+                let number = pattern.rows.len() + 1;
+                pattern.rows.push(Row::new(number, edge, Shaping::Purl));
+            }
+            pattern.close_with(&base);
+            finishing.push(format!(
+                "Block and fill the dome while the base is still open wide enough, then cut the yarn leaving a long tail, thread it through the remaining {} stitches and pull tight.",
+                base.rows[0].stitches
+            ));
+        }
+        Base::Sewn => {
+            let mut base = base;
+            base.apply_fabric(params.fabric);
+            base.numbering = params.numbering;
+            base.abbreviations = params.abbreviations.clone();
+            finishing.insert(
+                0,
+                format!(
+                    "Bind off the {} stitches, leaving a long tail for sewing.",
+                    edge
+                ),
+            );
+            finishing.push(String::from("Base, worked separately from the center out:"));
+            let word = base.word();
+            finishing.extend((0..base.rows.len()).map(|i| {
+                base.written_row(i)
+                    .replacen(word, &format!("Base {}", word.to_lowercase()), 1)
+            }));
+            finishing.push(format!(
+                "Bind off the base's {} stitches. Hold it against the dome's bound-off edge, wrong sides together, and sew them stitch for stitch, filling the dome before the seam closes.",
+                edge
+            ));
+        }
+    }
+    Some(Design {
+        pattern,
+        finishing,
+        volume: Some(stuffing::sphere_volume(diameter) / 2.0),
+        weight: params.dome.weight(params.locale).ok(),
+        pieces: 1,
+        chart: None,
+        profile: dome_samples(diameter, gauge, every),
+    })
+}
+
+pub fn form(params: &Params, validation: &Validation, on_change: Callback<DomeParams>) -> Html {
+    let diameter = params.dome.diameter(params.units(), params.locale);
+    let weight = params.dome.weight(params.locale);
+    let dome = params.dome.clone();
+    let on_diameter_input = on_change.reform(move |diameter| DomeParams {
+        diameter,
+        ..dome.clone()
+    });
+    let dome = params.dome.clone();
+    let on_weight_input = on_change.reform(move |weight| DomeParams {
+        weight,
+        ..dome.clone()
+    });
+    let on_cadence_change = {
+        let (dome, on_change) = (params.dome.clone(), on_change.clone());
+        Callback::from(move |e: Event| {
+            let select_el: HtmlSelectElement = e.target_unchecked_into();
+            if let Some(cadence) = Cadence::from_key(&select_el.value()) {
+                on_change.emit(DomeParams {
+                    cadence,
+                    ..dome.clone()
+                });
+            }
+        })
+    };
+    let on_base_change = {
+        let dome = params.dome.clone();
+        Callback::from(move |e: Event| {
+            let select_el: HtmlSelectElement = e.target_unchecked_into();
+            if let Some(base) = Base::from_key(&select_el.value()) {
+                on_change.emit(DomeParams {
+                    base,
+                    ..dome.clone()
+                });
+            }
+        })
+    };
+    html! {
+        <>
+            <NumberInput
+                label={"Diameter: "}
+                placeholder={"Across the base"}
+                value={params.dome.diameter.clone()}
+                current={diameter.as_ref().ok().copied()}
+                scale={Scale::for_length(params.units(), 12.0)}
+                locale={params.locale}
+                oninput={on_diameter_input}
+            >
+                { field_message(&params.dome.diameter, &diameter, &params.units, params.locale, validation, Field::Diameter) }
+            </NumberInput>
+            <span>
+                <label>{"Shape: "}</label>
+                <select aria-label="Shape" onchange={on_cadence_change}>
+                    { for Cadence::ALL.iter().map(|c| html! {
+                        <option value={c.key()} selected={*c == params.dome.cadence}>{c.name()}</option>
+                    }) }
+                </select>
+            </span>
+            <span>
+                <label>{"Base: "}</label>
+                <select aria-label="Base" onchange={on_base_change}>
+                    { for Base::ALL.iter().map(|b| html! {
+                        <option value={b.key()} selected={*b == params.dome.base}>{b.name()}</option>
+                    }) }
+                </select>
+            </span>
+            <NumberInput
+                label={"Weight (g): "}
+                placeholder={"Stuff with fiberfill"}
+                value={params.dome.weight.clone()}
+                current={weight.as_ref().ok().copied()}
+                scale={Scale { min: 50.0, max: 500.0, step: 10.0 }}
+                locale={params.locale}
+                oninput={on_weight_input}
+            >
+                { field_message(&params.dome.weight, &weight, "g", params.locale, validation, Field::Weight) }
+                <span class="field-note">{"Made up with plastic pellets in a liner, for a paperweight or a pincushion that stays put."}</span>
+            </NumberInput>
+        </>
+    }
+}
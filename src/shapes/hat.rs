@@ -0,0 +1,349 @@
+use super::Design;
+use crate::abbreviations::Abbreviations;
+use crate::cables;
+use crate::colorwork::{chart_view, Chart, YARN_COLORS};
+use crate::finishing::{self, BlockingForm};
+use crate::fit;
+use crate::form::field_message;
+use crate::gauge::Gauge;
+use crate::locale::Locale;
+use crate::number_input::{NumberInput, Scale};
+use crate::numbering::Numbering;
+use crate::palette::Palette;
+use crate::params::Params;
+use crate::parse::{parse_length, ParseError};
+use crate::pattern::{Pattern, Row, Shaping};
+use crate::phrasing::Phrasing;
+use crate::ribbing::{self, RibParams};
+use crate::units::Unit;
+use crate::validate::{Field, Validation};
+use serde::{Deserialize, Serialize};
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+
+// How the crown is decreased, each with its own placing of the decreases.
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum CrownStyle {
+    // Eight wedges with a decrease at the end of each every other round, in straight lines.
+    #[default]
+    Wedges,
+    // Six sections whose decreases move along each time, so the lines spiral round the crown.
+    Swirl,
+    // Five points decreased at both edges, the pairs of lines meeting in a star.
+    Star,
+    // Worked straight up, then knit two together all round twice and gathered closed.
+    Gathered,
+}
+
+impl CrownStyle {
+    pub const ALL: [CrownStyle; 4] = [
+        CrownStyle::Wedges,
+        CrownStyle::Swirl,
+        CrownStyle::Star,
+        CrownStyle::Gathered,
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            CrownStyle::Wedges => "wedges",
+            CrownStyle::Swirl => "swirl",
+            CrownStyle::Star => "star",
+            CrownStyle::Gathered => "gathered",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CrownStyle::Wedges => "8 wedges",
+            CrownStyle::Swirl => "Spiral swirl",
+            CrownStyle::Star => "5-point star",
+            CrownStyle::Gathered => "Gathered",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<CrownStyle> {
+        CrownStyle::ALL.into_iter().find(|s| s.key() == key)
+    }
+
+    // The crown is decreased in this many sections, so the cast on is kept to a multiple of it.
+    pub fn sections(&self) -> i32 {
+        match self {
+            CrownStyle::Wedges => 8,
+            CrownStyle::Swirl => 6,
+            CrownStyle::Star => 5,
+            CrownStyle::Gathered => 4,
+        }
+    }
+
+    // Whether the crown is done, with `width` stitches left in each section after `turn` decrease
+    // rounds.
+    fn finished(&self, width: i32, turn: i32) -> bool {
+        match self {
+            CrownStyle::Wedges | CrownStyle::Swirl => width <= 1,
+            CrownStyle::Star => width < 4,
+            CrownStyle::Gathered => turn == 2,
+        }
+    }
+
+    // The crown rounds off `cast_on` stitches, as (stitches, shaping), down to the stitches gathered
+    // closed at the top.
+    pub fn crown(&self, cast_on: i32) -> Vec<(i32, Shaping)> {
+        let sections = self.sections();
+        let mut rounds = Vec::new();
+        let mut stitches = cast_on;
+        let mut turn = 0;
+        while !self.finished(stitches / sections, turn) {
+            let width = stitches / sections;
+            let shaping = match self {
+                CrownStyle::Wedges => Shaping::Decrease {
+                    dec: sections,
+                    block: width - 2,
+                },
+                CrownStyle::Swirl => {
+                    // Every decrease one stitch further back each time, wrapping round within
+                    // its section:
+                    let mut knits = vec![width - 2; sections as usize];
+                    knits[0] = (width - 2 - turn).rem_euclid(width - 1);
+                    Shaping::DecreaseAt { knits }
+                }
+                // A decrease at both edges of each point:
+                CrownStyle::Star => Shaping::DecreaseAt {
+                    knits: (0..sections).flat_map(|_| [0, width - 4]).collect(),
+                },
+                CrownStyle::Gathered => Shaping::Decrease {
+                    dec: stitches / 2,
+                    block: 0,
+                },
+            };
+            stitches -= shaping.decrease_stitches().len() as i32;
+            turn += 1;
+            rounds.push((stitches, shaping));
+            if !self.finished(stitches / sections, turn) {
+                rounds.push((stitches, Shaping::Plain));
+            }
+        }
+        rounds
+    }
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HatParams {
+    pub circumference: String,
+    pub height: String,
+    pub crown: CrownStyle,
+}
+
+impl HatParams {
+    pub fn circumference(&self, units: Option<Unit>, locale: Locale) -> Result<f64, ParseError> {
+        parse_length(&self.circumference, units, locale)
+    }
+
+    pub fn height(&self, units: Option<Unit>, locale: Locale) -> Result<f64, ParseError> {
+        parse_length(&self.height, units, locale)
+    }
+}
+
+// A multiple of the crown's sections, and of any stitch pattern repeat too, with at least four
+// stitches in each section.
+pub fn cast_on(params: &Params, circumference: f64, stitches_per_unit: f64) -> i32 {
+    let sections = params.hat.crown.sections();
+    let multiple = fit::multiple(params, sections);
+    fit::nearest(circumference * stitches_per_unit, multiple, 4 * sections)
+}
+
+pub fn crown_rows(params: &Params, cast_on: i32) -> i32 {
+    params.hat.crown.crown(cast_on).len() as i32
+}
+
+// A tube worked in the round up to the crown, then decreased in the crown's style.
+pub fn pattern(params: &Params, circumference: f64, height: f64, gauge: Gauge) -> Pattern {
+    let cast_on = cast_on(params, circumference, gauge.stitches_per_unit);
+    let total_rows = (height * gauge.rows_per_unit).round() as i32;
+    let body_rows = (total_rows - crown_rows(params, cast_on)).max(2) as usize;
+
+    let mut rows = vec![Row::new(1, cast_on, Shaping::CastOn)];
+    for number in 2..=body_rows {
+        rows.push(Row::new(number, cast_on, Shaping::Plain));
+    }
+    for (stitches, shaping) in params.hat.crown.crown(cast_on) {
+        rows.push(Row::new(rows.len() + 1, stitches, shaping));
+    }
+    Pattern {
+        rows,
+        numbering: Numbering::default(),
+        two_at_a_time: false,
+        abbreviations: Abbreviations::default(),
+        phrasing: Phrasing::default(),
+    }
+}
+
+pub fn validate(params: &Params, gauge: Option<Gauge>) -> Validation {
+    let mut validation = Validation::default();
+    let circumference = params.hat.circumference(params.units(), params.locale).ok();
+    let height = params.hat.height(params.units(), params.locale).ok();
+    validation.require_positive(Field::Circumference, circumference, "Circumference");
+    validation.require_positive(Field::Length, height, "Height");
+    if let (Some(circumference), Some(height), Some(gauge), false) =
+        (circumference, height, gauge, validation.has_errors())
+    {
+        validation.check_size(
+            circumference * gauge.stitches_per_unit,
+            height * gauge.rows_per_unit,
+        );
+        if !validation.has_errors() {
+            let crown = crown_rows(
+                params,
+                cast_on(params, circumference, gauge.stitches_per_unit),
+            );
+            if height * gauge.rows_per_unit < f64::from(crown + 2) {
+                validation.warning(
+                    Field::Length,
+                    format!(
+                        "The crown alone is about {} {} tall, so the hat will come out taller than this",
+                        params.locale.decimal(f64::from(crown) / gauge.rows_per_unit, 1),
+                        params.units
+                    ),
+                );
+            }
+        }
+    }
+    validation
+}
+
+pub fn design(params: &Params, gauge: Gauge) -> Option<Design> {
+    let circumference = params
+        .hat
+        .circumference(params.units(), params.locale)
+        .ok()?;
+    let height = params.hat.height(params.units(), params.locale).ok()?;
+    // The cast on is a multiple of the crown's sections, so any rib fits:
+    let mut pattern = pattern(params, circumference, height, gauge);
+    let mut finishing = vec![format!(
+        "Cut the yarn leaving a long tail, thread it through the remaining {} stitches and pull tight.",
+        pattern.rows.last().map_or(0, |row| row.stitches)
+    )];
+    finishing.extend(finishing::notes(
+        params.fiber,
+        BlockingForm::Hat { circumference },
+        &params.units,
+        params.locale,
+    ));
+    ribbing::apply(
+        &mut pattern,
+        &RibParams {
+            last_rows: String::new(),
+            ..params.rib.clone()
+        },
+    );
+    cables::apply(&mut pattern, params.cable);
+    Some(Design {
+        pattern,
+        finishing,
+        volume: None,
+        weight: None,
+        pieces: 1,
+        chart: None,
+        profile: Vec::new(),
+    })
+}
+
+// The crown as a chart, with the stitch each decrease makes in color B, to show the lines the
+// decreases make. A couple of the body's rounds are kept below it.
+pub fn crown_view(pattern: &Pattern, palette: &Palette) -> Html {
+    let Some(first) = pattern
+        .rows
+        .iter()
+        .position(|row| !row.shaping.decrease_stitches().is_empty())
+    else {
+        return html! {};
+    };
+    let crown = Pattern {
+        rows: pattern.rows[first.saturating_sub(2)..].to_vec(),
+        ..pattern.clone()
+    };
+    let rows = crown
+        .rows
+        .iter()
+        .map(|row| {
+            let mut colors = vec![0; row.stitches.max(0) as usize];
+            for stitch in row.shaping.decrease_stitches() {
+                if let Some(color) = colors.get_mut(stitch) {
+                    *color = 1;
+                }
+            }
+            colors
+        })
+        .collect();
+    let chart = Chart {
+        rows,
+        palette: YARN_COLORS[..2].iter().map(|c| c.to_string()).collect(),
+    };
+    html! {
+        <>
+            { chart_view(&chart, &crown, palette) }
+            <p class="field-note">{"The stitches in B are made by decreases, and line up as the crown's decrease lines."}</p>
+        </>
+    }
+}
+
+pub fn form(params: &Params, validation: &Validation, on_change: Callback<HatParams>) -> Html {
+    let circumference = params.hat.circumference(params.units(), params.locale);
+    let height = params.hat.height(params.units(), params.locale);
+    let hat = params.hat.clone();
+    let on_circumference_input = on_change.reform(move |circumference| HatParams {
+        circumference,
+        ..hat.clone()
+    });
+    let hat = params.hat.clone();
+    let on_height_input = on_change.reform(move |height| HatParams {
+        height,
+        ..hat.clone()
+    });
+    let on_crown_change = {
+        let hat = params.hat.clone();
+        Callback::from(move |e: Event| {
+            let select_el: HtmlSelectElement = e.target_unchecked_into();
+            if let Some(crown) = CrownStyle::from_key(&select_el.value()) {
+                on_change.emit(HatParams {
+                    crown,
+                    ..hat.clone()
+                });
+            }
+        })
+    };
+    html! {
+        <>
+            <NumberInput
+                label={"Circumference: "}
+                placeholder={"Around the brim"}
+                value={params.hat.circumference.clone()}
+                current={circumference.as_ref().ok().copied()}
+                scale={Scale::for_length(params.units(), 30.0)}
+                locale={params.locale}
+                oninput={on_circumference_input}
+            >
+                { field_message(&params.hat.circumference, &circumference, &params.units, params.locale, validation, Field::Circumference) }
+            </NumberInput>
+            <NumberInput
+                label={"Height: "}
+                placeholder={"Brim to crown"}
+                value={params.hat.height.clone()}
+                current={height.as_ref().ok().copied()}
+                scale={Scale::for_length(params.units(), 15.0)}
+                locale={params.locale}
+                oninput={on_height_input}
+            >
+                { field_message(&params.hat.height, &height, &params.units, params.locale, validation, Field::Length) }
+            </NumberInput>
+            <span>
+                <label>{"Crown: "}</label>
+                <select aria-label="Crown" onchange={on_crown_change}>
+                    { for CrownStyle::ALL.iter().map(|c| html! {
+                        <option value={c.key()} selected={*c == params.hat.crown}>{c.name()}</option>
+                    }) }
+                </select>
+            </span>
+        </>
+    }
+}
@@ -0,0 +1,392 @@
+pub mod cylinder;
+pub mod dome;
+pub mod hat;
+pub mod panel;
+pub mod sphere;
+pub mod swatch;
+
+use crate::bobbles;
+use crate::colorwork::Chart;
+use crate::diagnostics::Sample;
+use crate::fit;
+use crate::form::parse_count;
+use crate::gauge::{Fabric, Gauge};
+use crate::needles;
+use crate::params::Params;
+use crate::parse::{parse_length, parse_number, ParseError};
+use crate::pattern::{Distribution, Pattern};
+use crate::validate::Validation;
+use serde::{Deserialize, Serialize};
+use yew_router::prelude::*;
+
+#[derive(Clone, Copy, PartialEq, Routable)]
+pub enum Route {
+    #[at("/")]
+    Sphere,
+    #[at("/dome")]
+    Dome,
+    #[at("/cylinder")]
+    Cylinder,
+    #[at("/hat")]
+    Hat,
+    #[at("/panel")]
+    Panel,
+    #[at("/swatch")]
+    Swatch,
+    #[at("/view")]
+    View,
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Shape {
+    Sphere,
+    // A hemisphere closed by a flat circle, for a paperweight or pincushion.
+    Dome,
+    Cylinder,
+    Hat,
+    Panel,
+    Swatch,
+}
+
+// A generated pattern plus the shape-specific parts of its write-up.
+#[derive(Clone, PartialEq)]
+pub struct Design {
+    pub pattern: Pattern,
+    pub finishing: Vec<String>,
+    // For closed shapes that need stuffing, in cubic units.
+    pub volume: Option<f64>,
+    // What each piece should weigh in grams, made up with pellets in place of fiberfill.
+    pub weight: Option<f64>,
+    // How many matching pieces are made, as for a set of juggling balls.
+    pub pieces: usize,
+    // For flat pieces, the color of every stitch, drawn in place of the round previews.
+    pub chart: Option<Chart>,
+    // The geometry the shaping rows were worked out from, for shapes worked out from a profile.
+    pub profile: Vec<Sample>,
+}
+
+impl Shape {
+    pub const ALL: [Shape; 6] = [
+        Shape::Sphere,
+        Shape::Dome,
+        Shape::Cylinder,
+        Shape::Hat,
+        Shape::Panel,
+        Shape::Swatch,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Shape::Sphere => "Sphere",
+            Shape::Dome => "Dome",
+            Shape::Cylinder => "Cylinder",
+            Shape::Hat => "Hat",
+            Shape::Panel => "Flat panel",
+            Shape::Swatch => "Gauge swatch",
+        }
+    }
+
+    pub fn route(&self) -> Route {
+        match self {
+            Shape::Sphere => Route::Sphere,
+            Shape::Dome => Route::Dome,
+            Shape::Cylinder => Route::Cylinder,
+            Shape::Hat => Route::Hat,
+            Shape::Panel => Route::Panel,
+            Shape::Swatch => Route::Swatch,
+        }
+    }
+
+    // Whether increases or bobbles are placed at random, so the seed changes the pattern.
+    pub fn uses_seed(&self, params: &Params) -> bool {
+        let offset = params.distribution == Distribution::Offset;
+        (matches!(self, Shape::Sphere | Shape::Dome) && offset)
+            || parse_number(&params.bobble_density, params.locale).is_ok()
+    }
+
+    pub fn from_route(route: &Route) -> Shape {
+        match route {
+            Route::Sphere => Shape::Sphere,
+            Route::Dome => Shape::Dome,
+            Route::Cylinder => Shape::Cylinder,
+            Route::Hat => Shape::Hat,
+            Route::Panel => Shape::Panel,
+            Route::Swatch => Shape::Swatch,
+            // The read-only view shows whichever shape its link names, and isn't a generator tab.
+            Route::View => Shape::Sphere,
+        }
+    }
+
+    // The dimension that sets the size of the piece, which the pattern can be graded across.
+    pub fn size_name(&self) -> &'static str {
+        match self {
+            Shape::Sphere | Shape::Dome => "Diameter",
+            Shape::Cylinder | Shape::Hat => "Circumference",
+            Shape::Panel => "Width",
+            Shape::Swatch => "Size",
+        }
+    }
+
+    pub fn size<'a>(&self, params: &'a Params) -> &'a str {
+        match self {
+            Shape::Sphere => &params.sphere.diameter,
+            Shape::Dome => &params.dome.diameter,
+            Shape::Cylinder => &params.cylinder.circumference,
+            Shape::Hat => &params.hat.circumference,
+            Shape::Panel => &params.panel.width,
+            Shape::Swatch => &params.swatch.width,
+        }
+    }
+
+    pub fn with_size(&self, params: &Params, size: &str) -> Params {
+        let mut params = params.clone();
+        match self {
+            Shape::Sphere => params.sphere.diameter = size.to_string(),
+            Shape::Dome => params.dome.diameter = size.to_string(),
+            Shape::Cylinder => params.cylinder.circumference = size.to_string(),
+            Shape::Hat => params.hat.circumference = size.to_string(),
+            Shape::Panel => params.panel.width = size.to_string(),
+            Shape::Swatch => params.swatch.width = size.to_string(),
+        }
+        params
+    }
+
+    // Everything entered for the shape's size, in the order the form shows it.
+    pub fn dimensions(&self, params: &Params) -> Vec<String> {
+        match self {
+            Shape::Sphere => vec![params.sphere.diameter.clone()],
+            Shape::Dome => vec![params.dome.diameter.clone()],
+            Shape::Cylinder => vec![
+                params.cylinder.circumference.clone(),
+                params.cylinder.length.clone(),
+            ],
+            Shape::Hat => vec![params.hat.circumference.clone(), params.hat.height.clone()],
+            Shape::Panel => vec![params.panel.width.clone(), params.panel.length.clone()],
+            Shape::Swatch => vec![params.swatch.width.clone()],
+        }
+    }
+
+    pub fn with_dimensions(&self, params: &Params, dimensions: &[String]) -> Params {
+        let mut params = params.clone();
+        let mut dimensions = dimensions.iter().cloned();
+        let mut next = |field: &mut String| {
+            if let Some(value) = dimensions.next() {
+                *field = value;
+            }
+        };
+        match self {
+            Shape::Sphere => next(&mut params.sphere.diameter),
+            Shape::Dome => next(&mut params.dome.diameter),
+            Shape::Cylinder => {
+                next(&mut params.cylinder.circumference);
+                next(&mut params.cylinder.length);
+            }
+            Shape::Hat => {
+                next(&mut params.hat.circumference);
+                next(&mut params.hat.height);
+            }
+            Shape::Panel => {
+                next(&mut params.panel.width);
+                next(&mut params.panel.length);
+            }
+            Shape::Swatch => next(&mut params.swatch.width),
+        }
+        params
+    }
+
+    // The same shape `percent` percent of the size, with every length entered for it scaled alike:
+    // its dimensions, an oval's depth and the panel's color blocks. None when a dimension isn't a
+    // length.
+    pub fn scaled(&self, params: &Params, percent: f64) -> Option<Params> {
+        let scale = |text: &str| {
+            parse_length(text, params.units(), params.locale)
+                .ok()
+                .map(|length| format!("{}", (length * percent).round() / 100.0))
+        };
+        let dimensions = self
+            .dimensions(params)
+            .iter()
+            .map(|dimension| scale(dimension))
+            .collect::<Option<Vec<String>>>()?;
+        let mut scaled = self.with_dimensions(params, &dimensions);
+        // Optional lengths are scaled when they are given:
+        let optional = |field: &mut String| {
+            if let Some(length) = scale(field) {
+                *field = length;
+            }
+        };
+        match self {
+            Shape::Sphere => optional(&mut scaled.sphere.depth),
+            Shape::Panel => {
+                for block in &mut scaled.panel.blocks {
+                    optional(&mut block.left);
+                    optional(&mut block.bottom);
+                    optional(&mut block.width);
+                    optional(&mut block.height);
+                }
+            }
+            Shape::Dome | Shape::Cylinder | Shape::Hat | Shape::Swatch => {}
+        }
+        Some(scaled)
+    }
+
+    // The finished size for the top of the pattern, with each dimension in both metric and
+    // imperial units.
+    pub fn summary(&self, params: &Params) -> String {
+        let units = params.units();
+        let dimensions: Vec<(&str, Result<f64, ParseError>)> = match self {
+            Shape::Sphere => vec![
+                ("diameter", params.sphere.diameter(units, params.locale)),
+                ("depth", params.sphere.depth(units, params.locale)),
+            ],
+            Shape::Dome => vec![
+                ("diameter", params.dome.diameter(units, params.locale)),
+                (
+                    "height",
+                    params.dome.diameter(units, params.locale).map(|d| d / 2.0),
+                ),
+            ],
+            Shape::Cylinder => vec![
+                (
+                    "circumference",
+                    params.cylinder.circumference(units, params.locale),
+                ),
+                ("length", params.cylinder.length(units, params.locale)),
+            ],
+            Shape::Hat => vec![
+                (
+                    "circumference",
+                    params.hat.circumference(units, params.locale),
+                ),
+                ("height", params.hat.height(units, params.locale)),
+            ],
+            Shape::Panel => vec![
+                ("width", params.panel.width(units, params.locale)),
+                ("length", params.panel.length(units, params.locale)),
+            ],
+            Shape::Swatch => vec![("square", params.swatch.width(units, params.locale))],
+        };
+        let sizes: Vec<String> = dimensions
+            .into_iter()
+            .filter_map(|(name, value)| {
+                Some(format!(
+                    "{} {}",
+                    name,
+                    params.locale.dual(value.ok()?, units, &params.units)
+                ))
+            })
+            .collect();
+        format!("{}: {}", self.name(), sizes.join(", "))
+    }
+
+    // Knitted pieces worked in the round, which can be made two at once on one circular needle and
+    // get notes on which needles to use.
+    pub fn knitted_in_the_round(&self, params: &Params) -> bool {
+        matches!(
+            self,
+            Shape::Sphere | Shape::Dome | Shape::Cylinder | Shape::Hat
+        ) && params.fabric.crochet().is_none()
+    }
+
+    // Typical small, medium and large sizes in inches.
+    pub fn preset_sizes(&self) -> [f64; 3] {
+        match self {
+            Shape::Sphere => [3.0, 4.0, 6.0],
+            // Pincushions and paperweights:
+            Shape::Dome => [2.5, 3.5, 5.0],
+            Shape::Cylinder => [4.0, 6.0, 8.0],
+            // Child, teen and adult heads:
+            Shape::Hat => [18.0, 20.0, 22.0],
+            // Scarf, cushion cover and baby blanket:
+            Shape::Panel => [8.0, 16.0, 30.0],
+            // Quick, usual and for a stitch pattern with a long repeat:
+            Shape::Swatch => [4.0, 6.0, 8.0],
+        }
+    }
+
+    // The width the cast on should make, for shapes whose width is set by the cast on.
+    pub fn cast_on_width(&self, params: &Params) -> Option<f64> {
+        let width = match self {
+            Shape::Sphere | Shape::Dome => return None,
+            Shape::Cylinder => params.cylinder.circumference(params.units(), params.locale),
+            Shape::Hat => params.hat.circumference(params.units(), params.locale),
+            Shape::Panel => params.panel.width(params.units(), params.locale),
+            Shape::Swatch => params.swatch.width(params.units(), params.locale),
+        };
+        width.ok()
+    }
+
+    pub fn cast_on(&self, params: &Params, gauge: Gauge) -> Option<i32> {
+        let width = self.cast_on_width(params)?;
+        let spu = gauge.stitches_per_unit;
+        Some(match self {
+            Shape::Sphere | Shape::Dome => return None,
+            Shape::Cylinder => {
+                cylinder::cast_on(width, spu, fit::multiple(params, params.rib.rib.multiple()))
+            }
+            Shape::Hat => hat::cast_on(params, width, spu),
+            Shape::Panel => panel::cast_on(params, width, spu),
+            Shape::Swatch => swatch::cast_on(width, spu),
+        })
+    }
+
+    // Checks the shape's dimensions, and once the gauge is known, the size of the pattern.
+    pub fn validate(&self, params: &Params, gauge: Option<Gauge>) -> Validation {
+        let mut validation = match self {
+            Shape::Sphere => sphere::validate(params, gauge),
+            Shape::Dome => dome::validate(params, gauge),
+            Shape::Cylinder => cylinder::validate(params, gauge),
+            Shape::Hat => hat::validate(params, gauge),
+            Shape::Panel => panel::validate(params, gauge),
+            Shape::Swatch => swatch::validate(params, gauge),
+        };
+        validation.check_bobbles(parse_number(&params.bobble_density, params.locale).ok());
+        // Spheres and domes are shaped all over, so have no repeat to fit:
+        if !matches!(self, Shape::Sphere | Shape::Dome) {
+            validation.check_repeat(parse_count(&params.repeat));
+        }
+        validation
+    }
+
+    // Only call once `validate` has passed without errors.
+    pub fn design(&self, params: &Params, gauge: Gauge) -> Option<Design> {
+        let mut design = match self {
+            Shape::Sphere => sphere::design(params, gauge),
+            Shape::Dome => dome::design(params, gauge),
+            Shape::Cylinder => cylinder::design(params, gauge),
+            Shape::Hat => hat::design(params, gauge),
+            Shape::Panel => panel::design(params, gauge),
+            Shape::Swatch => swatch::design(params, gauge),
+        }?;
+        self.finish(&mut design, params, gauge);
+        Some(design)
+    }
+
+    // Works the fabric, wording, needles, eyelets and bobbles into a design's rows, whether they
+    // were worked out from the shape or typed in by hand.
+    pub fn finish(&self, design: &mut Design, params: &Params, gauge: Gauge) {
+        design.pattern.apply_fabric(params.fabric);
+        // Brioche takes the place of a panel's color blocks, so chart what is actually worked.
+        if design.chart.is_some() && params.fabric == Fabric::Brioche {
+            design.chart = Some(Chart::from_pattern(&design.pattern));
+        }
+        design.pattern.numbering = params.numbering;
+        design.pattern.two_at_a_time = params.two_at_a_time && self.knitted_in_the_round(params);
+        design.pattern.abbreviations = params.abbreviations.clone();
+        design.pattern.phrasing = params.phrasing.clone();
+        // Two at a time has its own needle set-up:
+        if self.knitted_in_the_round(params) && !params.two_at_a_time {
+            needles::apply(&mut design.pattern, params.needles, gauge, params.units());
+        }
+        let eyelets: Vec<usize> = params
+            .eyelet_rows
+            .split(',')
+            .filter_map(|number| number.trim().parse().ok())
+            .map(|number| params.numbering.row(number))
+            .collect();
+        design.pattern.apply_eyelets(&eyelets);
+        if let Ok(density) = parse_number(&params.bobble_density, params.locale) {
+            bobbles::place(&mut design.pattern, density, params.seed);
+        }
+    }
+}
@@ -0,0 +1,337 @@
+use super::Design;
+use crate::abbreviations::Abbreviations;
+use crate::colorwork::{runs, Chart, YARN_COLORS};
+use crate::finishing::{self, BlockingForm};
+use crate::fit;
+use crate::form::field_message;
+use crate::gauge::{Fabric, Gauge};
+use crate::locale::Locale;
+use crate::number_input::{NumberInput, Scale};
+use crate::numbering::Numbering;
+use crate::params::Params;
+use crate::parse::{parse_length, ParseError};
+use crate::pattern::{color_name, Pattern, Row, Shaping};
+use crate::phrasing::Phrasing;
+use crate::units::Unit;
+use crate::validate::{Field, Validation};
+use serde::{Deserialize, Serialize};
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+// A rectangle of color knitted into the panel, measured from its bottom left corner as seen from
+// the right side.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Block {
+    pub color: usize,
+    pub left: String,
+    pub bottom: String,
+    pub width: String,
+    pub height: String,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PanelParams {
+    pub width: String,
+    pub length: String,
+    pub blocks: Vec<Block>,
+}
+
+impl PanelParams {
+    pub fn width(&self, units: Option<Unit>, locale: Locale) -> Result<f64, ParseError> {
+        parse_length(&self.width, units, locale)
+    }
+
+    pub fn length(&self, units: Option<Unit>, locale: Locale) -> Result<f64, ParseError> {
+        parse_length(&self.length, units, locale)
+    }
+}
+
+// Blocks written as "B1,2,3,4;C..." (color, left, bottom, width, height) for links.
+pub fn blocks_to_query(blocks: &[Block]) -> String {
+    blocks
+        .iter()
+        .map(|b| {
+            format!(
+                "{}{}|{}|{}|{}",
+                color_name(b.color),
+                b.left,
+                b.bottom,
+                b.width,
+                b.height
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+pub fn blocks_from_query(query: &str) -> Vec<Block> {
+    query
+        .split(';')
+        .filter_map(|block| {
+            let letter = block.chars().next()?;
+            let color = (0..YARN_COLORS.len()).find(|c| color_name(*c).starts_with(letter))?;
+            let values = &block[letter.len_utf8()..];
+            // Links made before the bar was used part the values with commas:
+            let part = match values.contains('|') {
+                true => '|',
+                false => ',',
+            };
+            let mut values = values.split(part).map(String::from);
+            Some(Block {
+                color,
+                left: values.next()?,
+                bottom: values.next()?,
+                width: values.next()?,
+                height: values.next()?,
+            })
+        })
+        .collect()
+}
+
+// Brioche is worked over pairs of stitches, so it needs an even count.
+pub fn cast_on(params: &Params, width: f64, stitches_per_unit: f64) -> i32 {
+    let base = match params.fabric {
+        Fabric::Brioche => 2,
+        _ => 1,
+    };
+    fit::nearest(width * stitches_per_unit, fit::multiple(params, base), base)
+}
+
+pub fn total_rows(length: f64, rows_per_unit: f64) -> i32 {
+    ((length * rows_per_unit).round() as i32).max(1)
+}
+
+// The color of every stitch, row 1 first and each row from the left as seen from the right side.
+// Blocks that can't be read are left out, and later blocks are laid over earlier ones.
+fn chart(params: &Params, stitches: usize, rows: usize, gauge: Gauge) -> Chart {
+    let mut chart = vec![vec![0; stitches]; rows];
+    let units = params.units();
+    for block in &params.panel.blocks {
+        let measure = |value: &str| {
+            parse_length(value, units, params.locale)
+                .ok()
+                .filter(|v| *v >= 0.0)
+        };
+        let (Some(left), Some(bottom), Some(width), Some(height)) = (
+            measure(&block.left),
+            measure(&block.bottom),
+            measure(&block.width),
+            measure(&block.height),
+        ) else {
+            continue;
+        };
+        let to_stitch = |x: f64| ((x * gauge.stitches_per_unit).round() as usize).min(stitches);
+        let to_row = |y: f64| ((y * gauge.rows_per_unit).round() as usize).min(rows);
+        for row in &mut chart[to_row(bottom)..to_row(bottom + height)] {
+            row[to_stitch(left)..to_stitch(left + width)].fill(block.color);
+        }
+    }
+    Chart {
+        rows: chart,
+        palette: YARN_COLORS.iter().map(|c| c.to_string()).collect(),
+    }
+}
+
+// A rectangle worked flat in stockinette, with any color blocks worked in intarsia. Right side
+// rows are worked from right to left across the chart and wrong side rows back again.
+pub fn pattern(chart: &Chart) -> Pattern {
+    let rows = chart
+        .rows
+        .iter()
+        .enumerate()
+        .map(|(i, colors)| {
+            let number = i + 1;
+            let shaping = match number {
+                1 => Shaping::CastOn,
+                n if n % 2 == 0 => Shaping::Purl,
+                _ => Shaping::Plain,
+            };
+            let mut row = Row::new(number, colors.len() as i32, shaping);
+            row.wrong_side = number % 2 == 0;
+            let mut segments = runs(colors);
+            if !row.wrong_side {
+                segments.reverse();
+            }
+            if segments.len() > 1 {
+                row.segments = segments;
+            } else {
+                row.color = colors.first().copied().unwrap_or(0);
+            }
+            row
+        })
+        .collect();
+    Pattern {
+        rows,
+        numbering: Numbering::default(),
+        two_at_a_time: false,
+        abbreviations: Abbreviations::default(),
+        phrasing: Phrasing::default(),
+    }
+}
+
+pub fn validate(params: &Params, gauge: Option<Gauge>) -> Validation {
+    let mut validation = Validation::default();
+    let width = params.panel.width(params.units(), params.locale).ok();
+    let length = params.panel.length(params.units(), params.locale).ok();
+    validation.require_positive(Field::Width, width, "Width");
+    validation.require_positive(Field::Length, length, "Length");
+    if let (Some(width), Some(length), Some(gauge), false) =
+        (width, length, gauge, validation.has_errors())
+    {
+        validation.check_size(
+            width * gauge.stitches_per_unit,
+            length * gauge.rows_per_unit,
+        );
+    }
+    validation
+}
+
+pub fn design(params: &Params, gauge: Gauge) -> Option<Design> {
+    let width = params.panel.width(params.units(), params.locale).ok()?;
+    let length = params.panel.length(params.units(), params.locale).ok()?;
+    let chart = chart(
+        params,
+        cast_on(params, width, gauge.stitches_per_unit) as usize,
+        total_rows(length, gauge.rows_per_unit) as usize,
+        gauge,
+    );
+    let mut finishing = vec![String::from("Bind off all stitches.")];
+    if chart.rows.iter().any(|row| runs(row).len() > 1) {
+        finishing.push(String::from(
+            "Weave in each bobbin's ends along the edge of its own color block.",
+        ));
+    }
+    finishing.extend(finishing::notes(
+        params.fiber,
+        BlockingForm::Flat { width, length },
+        &params.units,
+        params.locale,
+    ));
+    Some(Design {
+        pattern: pattern(&chart),
+        finishing,
+        volume: None,
+        weight: None,
+        pieces: 1,
+        chart: Some(chart),
+        profile: Vec::new(),
+    })
+}
+
+// The list of color blocks, each edited in place.
+fn blocks_form(panel: &PanelParams, on_change: &Callback<PanelParams>) -> Html {
+    let blocks = panel.blocks.iter().enumerate().map(|(i, block)| {
+        let edit = |update: fn(&mut Block, String)| {
+            let panel = panel.clone();
+            on_change.reform(move |e: InputEvent| {
+                let input_el: HtmlInputElement = e.target_unchecked_into();
+                let mut panel = panel.clone();
+                update(&mut panel.blocks[i], input_el.value());
+                panel
+            })
+        };
+        let on_color_change = {
+            let (panel, on_change) = (panel.clone(), on_change.clone());
+            Callback::from(move |e: Event| {
+                let select_el: HtmlSelectElement = e.target_unchecked_into();
+                if let Ok(color) = select_el.value().parse() {
+                    let mut panel = panel.clone();
+                    panel.blocks[i].color = color;
+                    on_change.emit(panel);
+                }
+            })
+        };
+        let on_remove = {
+            let panel = panel.clone();
+            on_change.reform(move |_: MouseEvent| {
+                let mut panel = panel.clone();
+                panel.blocks.remove(i);
+                panel
+            })
+        };
+        let colors = (1..YARN_COLORS.len()).map(|color| {
+            html! {
+                <option value={color.to_string()} selected={color == block.color}>
+                    {format!("Color {}", color_name(color))}
+                </option>
+            }
+        });
+        html! {
+            <li>
+                <select aria-label="Block color" onchange={on_color_change}>{ for colors }</select>
+                <label>{" from left "}</label>
+                <input aria-label="Block from left" type="text" size="4" value={block.left.clone()} oninput={edit(|b, v| b.left = v)}/>
+                <label>{" from bottom "}</label>
+                <input aria-label="Block from bottom" type="text" size="4" value={block.bottom.clone()} oninput={edit(|b, v| b.bottom = v)}/>
+                <label>{" width "}</label>
+                <input aria-label="Block width" type="text" size="4" value={block.width.clone()} oninput={edit(|b, v| b.width = v)}/>
+                <label>{" height "}</label>
+                <input aria-label="Block height" type="text" size="4" value={block.height.clone()} oninput={edit(|b, v| b.height = v)}/>
+                {" "}
+                <button type="button" onclick={on_remove}>{"Remove"}</button>
+            </li>
+        }
+    });
+    let on_add = {
+        let panel = panel.clone();
+        on_change.reform(move |_: MouseEvent| {
+            let mut panel = panel.clone();
+            panel.blocks.push(Block {
+                color: 1,
+                ..Block::default()
+            });
+            panel
+        })
+    };
+    html! {
+        <div class="color-blocks">
+            <label>{"Color blocks (intarsia):"}</label>
+            <ol>{ for blocks }</ol>
+            <button type="button" onclick={on_add}>{"Add block"}</button>
+        </div>
+    }
+}
+
+pub fn form(params: &Params, validation: &Validation, on_change: Callback<PanelParams>) -> Html {
+    let width = params.panel.width(params.units(), params.locale);
+    let length = params.panel.length(params.units(), params.locale);
+    let panel = params.panel.clone();
+    let on_width_input = on_change.reform(move |width| PanelParams {
+        width,
+        ..panel.clone()
+    });
+    let panel = params.panel.clone();
+    let on_length_input = on_change.reform(move |length| PanelParams {
+        length,
+        ..panel.clone()
+    });
+    html! {
+        <>
+            <NumberInput
+                label={"Width: "}
+                placeholder={"Across the panel"}
+                value={params.panel.width.clone()}
+                current={width.as_ref().ok().copied()}
+                scale={Scale::for_length(params.units(), 60.0)}
+                locale={params.locale}
+                oninput={on_width_input}
+            >
+                { field_message(&params.panel.width, &width, &params.units, params.locale, validation, Field::Width) }
+            </NumberInput>
+            <NumberInput
+                label={"Length: "}
+                placeholder={"Cast on edge to bind off"}
+                value={params.panel.length.clone()}
+                current={length.as_ref().ok().copied()}
+                scale={Scale::for_length(params.units(), 60.0)}
+                locale={params.locale}
+                oninput={on_length_input}
+            >
+                { field_message(&params.panel.length, &length, &params.units, params.locale, validation, Field::Length) }
+            </NumberInput>
+            { blocks_form(&params.panel, &on_change) }
+        </>
+    }
+}
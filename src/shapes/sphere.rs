@@ -0,0 +1,592 @@
+use super::Design;
+use crate::counts;
+use crate::diagnostics::Sample;
+use crate::finishing::{self, BlockingForm};
+use crate::form::{field_message, parse_count};
+use crate::gauge::Gauge;
+use crate::locale::Locale;
+use crate::number_input::{NumberInput, Scale};
+use crate::params::Params;
+use crate::parse::{parse_length, parse_number, ParseError};
+use crate::pattern::{Distribution, Pattern};
+use crate::stuffing;
+use crate::units::Unit;
+use crate::validate::{Field, Validation};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+// How often the sphere is shaped: every row, for crochet and tight curves, or every other or every
+// third row with plain rows between.
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum Cadence {
+    Row,
+    #[default]
+    Other,
+    Third,
+}
+
+impl Cadence {
+    pub const ALL: [Cadence; 3] = [Cadence::Row, Cadence::Other, Cadence::Third];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Cadence::Row => "1",
+            Cadence::Other => "2",
+            Cadence::Third => "3",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Cadence::Row => "Every row",
+            Cadence::Other => "Every other row",
+            Cadence::Third => "Every third row",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Cadence> {
+        Cadence::ALL.into_iter().find(|c| c.key() == key)
+    }
+
+    // Rows from one shaping row to the next.
+    pub fn rows(&self) -> i32 {
+        match self {
+            Cadence::Row => 1,
+            Cadence::Other => 2,
+            Cadence::Third => 3,
+        }
+    }
+}
+
+// Where the shaping rows fall between the pole and the equator.
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum RowSpacing {
+    // Equal steps of angle.
+    #[default]
+    Even,
+    // Closer together near the pole, where the circumference grows fastest, and further apart
+    // toward the equator, so no one row has to take a burst of increases.
+    Adaptive,
+    // Exactly a row gauge's worth of fabric apart, measured along the surface, rather than
+    // stretched to fit a whole number of shaping rows.
+    ArcLength,
+}
+
+impl RowSpacing {
+    pub const ALL: [RowSpacing; 3] = [
+        RowSpacing::Even,
+        RowSpacing::Adaptive,
+        RowSpacing::ArcLength,
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            RowSpacing::Even => "even",
+            RowSpacing::Adaptive => "adaptive",
+            RowSpacing::ArcLength => "arc",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RowSpacing::Even => "Evenly",
+            RowSpacing::Adaptive => "Closer near the poles",
+            RowSpacing::ArcLength => "By length along the surface",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<RowSpacing> {
+        RowSpacing::ALL.into_iter().find(|s| s.key() == key)
+    }
+
+    // The angle from the pole of the shaping row `t` (0 to 1) of the way to the equator. Adaptive
+    // steps are half the even step at the pole and one and a half times it at the equator.
+    fn angle(&self, t: f64) -> f64 {
+        let t = match self {
+            RowSpacing::Even | RowSpacing::ArcLength => t,
+            RowSpacing::Adaptive => t - 0.5 * (PI * t).sin() / PI,
+        };
+        t * PI / 2.0
+    }
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SphereParams {
+    pub diameter: String,
+    // Front to back, for an oval squashed from the front; left empty for a round ball.
+    pub depth: String,
+    pub cadence: Cadence,
+    pub spacing: RowSpacing,
+    // How many matching balls to make, as for a juggling set; left empty for one.
+    pub balls: String,
+    // What each ball should weigh in grams, made up with plastic pellets; left empty to stuff with
+    // fiberfill.
+    pub weight: String,
+}
+
+impl SphereParams {
+    pub fn diameter(&self, units: Option<Unit>, locale: Locale) -> Result<f64, ParseError> {
+        parse_length(&self.diameter, units, locale)
+    }
+
+    pub fn depth(&self, units: Option<Unit>, locale: Locale) -> Result<f64, ParseError> {
+        parse_length(&self.depth, units, locale)
+    }
+
+    pub fn balls(&self) -> usize {
+        parse_count(&self.balls).unwrap_or(1).max(1)
+    }
+
+    pub fn weight(&self, locale: Locale) -> Result<f64, ParseError> {
+        parse_number(&self.weight, locale)
+    }
+}
+
+// The perimeter of an ellipse with semi-axes `a` and `b`, by Ramanujan's second approximation,
+// which is exact for a circle and within a few parts per million for any egg or oval toy.
+pub fn ellipse_perimeter(a: f64, b: f64) -> f64 {
+    if a + b <= 0.0 {
+        return 0.0;
+    }
+    let h = ((a - b) / (a + b)).powi(2);
+    PI * (a + b) * (1.0 + 3.0 * h / (10.0 + (4.0 - 3.0 * h).sqrt()))
+}
+
+pub fn validate(params: &Params, gauge: Option<Gauge>) -> Validation {
+    let mut validation = Validation::default();
+    let diameter = params.sphere.diameter(params.units(), params.locale).ok();
+    let every = params.sphere.cadence.rows();
+    validation.require_positive(Field::Diameter, diameter, "Diameter");
+    let depth = params.sphere.depth(params.units(), params.locale).ok();
+    validation.require_positive(Field::Depth, depth, "Depth");
+    validation.require_positive(
+        Field::Weight,
+        params.sphere.weight(params.locale).ok(),
+        "Weight",
+    );
+    if let (Some(diameter), Some(gauge), false) = (diameter, gauge, validation.has_errors()) {
+        let depth = depth.unwrap_or(diameter);
+        validation.check_size(
+            max_stitches(diameter, depth, gauge.stitches_per_unit),
+            f64::from(2 * every * shaping_rows_in_hemisphere(diameter, gauge.rows_per_unit, every)),
+        );
+        if !validation.has_errors() && amigurumi(params) {
+            // An oval keeps its proportions, so it is the width that comes out different.
+            let made = f64::from(
+                amigurumi_counts(diameter, depth, gauge)
+                    .last()
+                    .copied()
+                    .unwrap_or(0),
+            ) / max_stitches(1.0, depth / diameter, gauge.stitches_per_unit);
+            if (made - diameter).abs() >= 0.05 {
+                validation.warning(
+                    Field::Diameter,
+                    format!(
+                        "Six increases a round makes a ball {} {} across, {} {} {} than entered.",
+                        params.locale.decimal(made, 1),
+                        params.units,
+                        params.locale.decimal((made - diameter).abs(), 1),
+                        params.units,
+                        if made > diameter { "larger" } else { "smaller" }
+                    ),
+                );
+            }
+        } else if !validation.has_errors() {
+            let counts = rounded_counts(params, diameter, depth, gauge);
+            validation.check_first_row(counts.first().copied());
+            if let (Some(least), Some(widest)) = (parse_count(&params.min_cast_on), counts.last()) {
+                if least as i32 >= *widest {
+                    validation.warning(
+                        Field::Diameter,
+                        format!(
+                            "The widest round has only {} stitches, so starting with at least {} leaves no shaping; allow a smaller cast on or make the ball larger.",
+                            widest, least
+                        ),
+                    );
+                }
+            }
+            let capped = capped_counts(params, counts.clone());
+            if let (Some(target), Some(reached)) = (counts.last(), capped.last()) {
+                if reached < target {
+                    validation.warning(
+                        Field::Diameter,
+                        format!(
+                            "With increases capped, the widest round only reaches {} of {} stitches; allow more increases per row for the full size.",
+                            reached, target
+                        ),
+                    );
+                }
+            }
+        }
+    }
+    validation
+}
+
+pub fn design(params: &Params, gauge: Gauge) -> Option<Design> {
+    let diameter = params.sphere.diameter(params.units(), params.locale).ok()?;
+    let depth = params
+        .sphere
+        .depth(params.units(), params.locale)
+        .unwrap_or(diameter);
+    let mut pattern = match amigurumi(params) {
+        true => Pattern::from_shaping_counts(
+            1,
+            amigurumi_counts(diameter, depth, gauge),
+            params.seed,
+            params.distribution,
+        ),
+        false => Pattern::from_shaping_counts(
+            params.sphere.cadence.rows() as usize,
+            capped_counts(params, rounded_counts(params, diameter, depth, gauge)),
+            params.seed,
+            params.distribution,
+        ),
+    };
+    // The second half is the first worked backwards, so the two match stitch for stitch.
+    pattern.close_by_mirroring();
+    let mut finishing = finishing::notes(
+        params.fiber,
+        BlockingForm::Ball { diameter },
+        &params.units,
+        params.locale,
+    );
+    finishing.push(format!(
+        "Block and stuff the ball while the opening is still wide enough, then cut the yarn leaving a long tail, thread it through the remaining {} stitches and pull tight.",
+        pattern.rows.last().map_or(0, |row| row.stitches)
+    ));
+    let balls = params.sphere.balls();
+    if balls > 1 {
+        finishing.push(format!(
+            "Make {} balls the same way, weighing the filling for each so the set matches.",
+            balls
+        ));
+    }
+    Some(Design {
+        pattern,
+        finishing,
+        // An oval is a ball squashed from the front, so its volume shrinks in proportion.
+        volume: Some(stuffing::sphere_volume(diameter) * depth / diameter),
+        weight: params.sphere.weight(params.locale).ok(),
+        pieces: balls,
+        chart: None,
+        profile: match amigurumi(params) {
+            true => Vec::new(),
+            false => sampled(params, diameter, depth, gauge),
+        },
+    })
+}
+
+pub fn form(params: &Params, validation: &Validation, on_change: Callback<SphereParams>) -> Html {
+    let diameter = params.sphere.diameter(params.units(), params.locale);
+    let on_diameter_input = {
+        let sphere = params.sphere.clone();
+        on_change.reform(move |diameter| SphereParams {
+            diameter,
+            ..sphere.clone()
+        })
+    };
+    let depth = params.sphere.depth(params.units(), params.locale);
+    let on_depth_input = {
+        let sphere = params.sphere.clone();
+        on_change.reform(move |depth| SphereParams {
+            depth,
+            ..sphere.clone()
+        })
+    };
+    let weight = params.sphere.weight(params.locale);
+    let on_weight_input = {
+        let sphere = params.sphere.clone();
+        on_change.reform(move |weight| SphereParams {
+            weight,
+            ..sphere.clone()
+        })
+    };
+    let on_balls_input = {
+        let (sphere, on_change) = (params.sphere.clone(), on_change.clone());
+        Callback::from(move |e: InputEvent| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            on_change.emit(SphereParams {
+                balls: input_el.value(),
+                ..sphere.clone()
+            });
+        })
+    };
+    let on_cadence_change = {
+        let (sphere, on_change) = (params.sphere.clone(), on_change.clone());
+        Callback::from(move |e: Event| {
+            let select_el: HtmlSelectElement = e.target_unchecked_into();
+            if let Some(cadence) = Cadence::from_key(&select_el.value()) {
+                on_change.emit(SphereParams {
+                    cadence,
+                    ..sphere.clone()
+                });
+            }
+        })
+    };
+    let on_spacing_change = {
+        let sphere = params.sphere.clone();
+        Callback::from(move |e: Event| {
+            let select_el: HtmlSelectElement = e.target_unchecked_into();
+            if let Some(spacing) = RowSpacing::from_key(&select_el.value()) {
+                on_change.emit(SphereParams {
+                    spacing,
+                    ..sphere.clone()
+                });
+            }
+        })
+    };
+    html! {
+        <>
+        <NumberInput
+            label={"Diameter: "}
+            placeholder={"Diameter of sphere"}
+            value={params.sphere.diameter.clone()}
+            current={diameter.as_ref().ok().copied()}
+            scale={Scale::for_length(params.units(), 24.0)}
+            locale={params.locale}
+            oninput={on_diameter_input}
+        >
+            { field_message(&params.sphere.diameter, &diameter, &params.units, params.locale, validation, Field::Diameter) }
+        </NumberInput>
+        <NumberInput
+            label={"Depth: "}
+            placeholder={"Same as diameter for a round ball"}
+            value={params.sphere.depth.clone()}
+            current={depth.as_ref().ok().copied()}
+            scale={Scale::for_length(params.units(), 24.0)}
+            locale={params.locale}
+            oninput={on_depth_input}
+        >
+            { field_message(&params.sphere.depth, &depth, &params.units, params.locale, validation, Field::Depth) }
+        </NumberInput>
+        <span>
+            <label>{"Balls in the set: "}</label>
+            <input aria-label="Balls in the set" type="number" min="1" placeholder="1" value={params.sphere.balls.clone()} oninput={on_balls_input}/>
+        </span>
+        <NumberInput
+            label={"Weight of each ball (g): "}
+            placeholder={"Stuff with fiberfill"}
+            value={params.sphere.weight.clone()}
+            current={weight.as_ref().ok().copied()}
+            scale={Scale { min: 50.0, max: 200.0, step: 5.0 }}
+            locale={params.locale}
+            oninput={on_weight_input}
+        >
+            { field_message(&params.sphere.weight, &weight, "g", params.locale, validation, Field::Weight) }
+            <span class="field-note">{"Made up with plastic pellets in a liner, as for juggling balls."}</span>
+        </NumberInput>
+        <span>
+            <label>{"Shape: "}</label>
+            <select aria-label="Shape" onchange={on_cadence_change}>
+                { for Cadence::ALL.iter().map(|c| html! {
+                    <option value={c.key()} selected={*c == params.sphere.cadence}>{c.name()}</option>
+                }) }
+            </select>
+        </span>
+        <span>
+            <label>{"Space the shaping rows: "}</label>
+            <select aria-label="Space the shaping rows" onchange={on_spacing_change}>
+                { for RowSpacing::ALL.iter().map(|s| html! {
+                    <option value={s.key()} selected={*s == params.sphere.spacing}>{s.name()}</option>
+                }) }
+            </select>
+        </span>
+        </>
+    }
+}
+
+// The amigurumi recipe is for crochet, so knitted spheres keep to the geometry.
+fn amigurumi(params: &Params) -> bool {
+    params.distribution == Distribution::Amigurumi && params.fabric.crochet().is_some()
+}
+
+// The classic amigurumi ball: six stitches to start, six more every round up to the multiple of
+// six nearest the equator, then even rounds for the rest of the hemisphere.
+fn amigurumi_counts(diameter: f64, depth: f64, gauge: Gauge) -> Vec<i32> {
+    let widest = max_stitches(diameter, depth, gauge.stitches_per_unit);
+    let widest = ((widest / 6.0).round() as i32).max(1);
+    let rounds = shaping_rows_in_hemisphere(diameter, gauge.rows_per_unit, 1).max(widest);
+    (1..=rounds).map(|round| 6 * round.min(widest)).collect()
+}
+
+// The shaping rows of the hemisphere, each rounded to whole stitches the way the params ask.
+fn sampled(params: &Params, diameter: f64, depth: f64, gauge: Gauge) -> Vec<Sample> {
+    let multiple = parse_count(&params.rounding_multiple).map_or(1, |m| m as i32);
+    samples(
+        diameter,
+        depth,
+        gauge.rows_per_unit,
+        gauge.stitches_per_unit,
+        params.sphere.cadence.rows(),
+        params.sphere.spacing,
+        |stitches| params.rounding.apply(stitches, multiple),
+    )
+}
+
+// Rows rounded to whole stitches the way the params ask, smoothed if asked, and started on no
+// fewer than `params.min_cast_on` stitches. The amigurumi recipe always starts on its ring of six.
+fn rounded_counts(params: &Params, diameter: f64, depth: f64, gauge: Gauge) -> Vec<i32> {
+    let counts: Vec<i32> = sampled(params, diameter, depth, gauge)
+        .iter()
+        .map(|sample| sample.rounded)
+        .collect();
+    let counts = match params.smooth_counts {
+        true => counts::smooth(&counts),
+        false => counts,
+    };
+    match parse_count(&params.min_cast_on) {
+        Some(least) => counts::raise_start(&counts, least as i32),
+        None => counts,
+    }
+}
+
+// The counts with the increases in any row capped at one per `params.increase_cap` stitches.
+fn capped_counts(params: &Params, counts: Vec<i32>) -> Vec<i32> {
+    match parse_count(&params.increase_cap).filter(|per| *per > 0) {
+        Some(per) => counts::cap_increases(&counts, per as i32),
+        None => counts,
+    }
+}
+
+// Number of shaping rows, each followed by `every - 1` plain rows, from the pole to the equator.
+pub fn shaping_rows_in_hemisphere(diameter: f64, rows_per_unit: f64, every: i32) -> i32 {
+    let r = diameter / 2.0;
+    let circle_dist = 2.0 * PI * r / 4.0;
+    let rough_rows_in_hemisphere = circle_dist * rows_per_unit;
+    (rough_rows_in_hemisphere / f64::from(every)).ceil() as i32
+}
+
+// Stitch count of the widest (equator) row.
+pub fn max_stitches(diameter: f64, depth: f64, stitches_per_unit: f64) -> f64 {
+    ellipse_perimeter(diameter / 2.0, depth / 2.0) * stitches_per_unit
+}
+
+// Each shaping row of the hemisphere of an oval `depth` from front to back, starting at the pole,
+// with shaping rows `every` rows apart placed by `spacing`, and each row's length rounded to whole
+// stitches by `round`, along with the geometry its count was worked out from.
+pub fn samples(
+    diameter: f64,
+    depth: f64,
+    rows_per_unit: f64,
+    stitches_per_unit: f64,
+    every: i32,
+    spacing: RowSpacing,
+    round: impl Fn(f64) -> i32,
+) -> Vec<Sample> {
+    let r = diameter / 2.0;
+    let shaping_rows = shaping_rows_in_hemisphere(diameter, rows_per_unit, every);
+
+    let angles: Vec<f64> = match spacing {
+        // An oval's profile is taken halfway between its side and its front.
+        RowSpacing::ArcLength => {
+            arc_length_angles(r, (r + depth / 2.0) / 2.0, f64::from(every) / rows_per_unit)
+        }
+        _ => (1..=shaping_rows)
+            .map(|x| spacing.angle(f64::from(x) / f64::from(shaping_rows)))
+            .collect(),
+    };
+    // Each row is an ellipse, or a circle when the depth is the diameter:
+    let radius_of_row: Vec<f64> = angles.iter().map(|a| r * f64::sin(*a)).collect();
+    let row_length: Vec<f64> = radius_of_row
+        .iter()
+        .map(|r| ellipse_perimeter(*r, r * depth / diameter))
+        .collect();
+    let stitch_count: Vec<f64> = row_length.iter().map(|rl| stitches_per_unit * rl).collect();
+    (0..angles.len())
+        .map(|i| Sample {
+            angle: angles[i],
+            radius: radius_of_row[i],
+            circumference: row_length[i],
+            raw: stitch_count[i],
+            rounded: round(stitch_count[i]),
+        })
+        .collect()
+}
+
+// Angles from the pole of rows `step` apart along the surface of a hemisphere whose profile is a
+// quarter ellipse, `r` from pole to equator and `across` from the axis to the equator. The
+// profile's length is integrated numerically, and the last row is moved to the equator, or one
+// added there, whichever leaves the last gap closer to a full step.
+fn arc_length_angles(r: f64, across: f64, step: f64) -> Vec<f64> {
+    const STEPS: usize = 1000;
+    let d_angle = PI / 2.0 / STEPS as f64;
+    let speed = |angle: f64| (across * angle.cos()).hypot(r * angle.sin());
+    let mut angles = Vec::new();
+    let (mut length, mut next) = (0.0, step);
+    for i in 0..STEPS {
+        let angle = i as f64 * d_angle;
+        let d_length = d_angle * (speed(angle) + speed(angle + d_angle)) / 2.0;
+        while length + d_length >= next {
+            angles.push(angle + d_angle * (next - length) / d_length);
+            next += step;
+        }
+        length += d_length;
+    }
+    if length - (next - step) < step / 2.0 && !angles.is_empty() {
+        angles.pop();
+    }
+    angles.push(PI / 2.0);
+    angles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn circle_perimeter_is_two_pi_r() {
+        for r in [0.5, 1.0, 7.0, 120.0] {
+            assert!((ellipse_perimeter(r, r) - 2.0 * PI * r).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn oval_perimeter_lies_between_its_circles() {
+        let perimeter = ellipse_perimeter(10.0, 5.0);
+        assert!(perimeter > 2.0 * PI * 5.0 && perimeter < 2.0 * PI * 10.0);
+        assert_eq!(ellipse_perimeter(10.0, 5.0), ellipse_perimeter(5.0, 10.0));
+    }
+
+    #[test]
+    fn flat_ellipse_perimeter_is_a_number() {
+        assert_eq!(ellipse_perimeter(0.0, 0.0), 0.0);
+        // Flattened to a line, the perimeter is there and back along it:
+        assert!((ellipse_perimeter(10.0, 0.0) - 40.0).abs() < 0.1);
+        assert!(ellipse_perimeter(1e-12, 1e12).is_finite());
+    }
+
+    #[test]
+    fn angles_rise_from_the_pole_to_the_equator() {
+        for (r, across) in [(10.0, 10.0), (10.0, 4.0), (4.0, 10.0)] {
+            let angles = arc_length_angles(r, across, 0.7);
+            assert!(angles[0] > 0.0);
+            assert!(
+                angles.windows(2).all(|pair| pair[0] < pair[1]),
+                "{:?}",
+                angles
+            );
+            assert_eq!(angles.last(), Some(&(PI / 2.0)));
+        }
+    }
+
+    #[test]
+    fn angles_on_a_circle_are_a_step_apart() {
+        let angles = arc_length_angles(10.0, 10.0, 1.0);
+        assert_eq!(angles.len(), 16);
+        for (k, angle) in angles[..15].iter().enumerate() {
+            assert!((angle - (k + 1) as f64 / 10.0).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn flat_profile_angles_are_numbers() {
+        for (r, across) in [(0.0, 10.0), (10.0, 0.0), (0.0, 0.0)] {
+            let angles = arc_length_angles(r, across, 1.0);
+            assert!(angles.iter().all(|angle| angle.is_finite()), "{:?}", angles);
+            assert_eq!(angles.last(), Some(&(PI / 2.0)));
+        }
+    }
+}
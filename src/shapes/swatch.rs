@@ -0,0 +1,164 @@
+use super::Design;
+use crate::abbreviations::Abbreviations;
+use crate::colorwork::Chart;
+use crate::finishing::{self, BlockingForm};
+use crate::form::field_message;
+use crate::gauge::{Fabric, Gauge};
+use crate::locale::Locale;
+use crate::number_input::{NumberInput, Scale};
+use crate::numbering::Numbering;
+use crate::params::Params;
+use crate::parse::{parse_length, ParseError};
+use crate::pattern::{Pattern, Row, Shaping};
+use crate::phrasing::Phrasing;
+use crate::units::Unit;
+use crate::validate::{Field, Validation};
+use serde::{Deserialize, Serialize};
+use yew::prelude::*;
+
+// Garter stitch at the edges keeps a stockinette swatch from curling, so it lies flat to measure.
+pub const BORDER_ROWS: usize = 4;
+pub const BORDER_STITCHES: i32 = 3;
+
+// A square knitted to check the gauge before starting a project.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SwatchParams {
+    pub width: String,
+}
+
+impl Default for SwatchParams {
+    fn default() -> Self {
+        Self {
+            width: String::from("15 cm"),
+        }
+    }
+}
+
+impl SwatchParams {
+    pub fn width(&self, units: Option<Unit>, locale: Locale) -> Result<f64, ParseError> {
+        parse_length(&self.width, units, locale)
+    }
+}
+
+pub fn cast_on(width: f64, stitches_per_unit: f64) -> i32 {
+    ((width * stitches_per_unit).round() as i32).max(1)
+}
+
+// As many rows as stitches make a square, plus the garter rows at the top and bottom.
+pub fn total_rows(width: f64, rows_per_unit: f64) -> i32 {
+    ((width * rows_per_unit).round() as i32).max(1) + 2 * BORDER_ROWS as i32
+}
+
+// A square worked flat: garter rows, stockinette with garter edges, then garter rows again.
+// Other fabrics lie flat by themselves, so their rows are left plain for `apply_fabric` to work.
+pub fn pattern(stitches: i32, rows: i32, fabric: Fabric) -> Pattern {
+    let rows = rows as usize;
+    let bordered = fabric == Fabric::Stockinette && stitches > 2 * BORDER_STITCHES;
+    let rows = (1..=rows)
+        .map(|number| {
+            let garter = number <= BORDER_ROWS + 1 || number > rows - BORDER_ROWS;
+            let shaping = match number {
+                1 => Shaping::CastOn,
+                n if n % 2 == 1 => Shaping::Plain,
+                _ if garter && bordered => Shaping::Plain,
+                _ if bordered => Shaping::Bordered {
+                    border: BORDER_STITCHES,
+                },
+                _ => Shaping::Purl,
+            };
+            let mut row = Row::new(number, stitches, shaping);
+            row.wrong_side = number % 2 == 0;
+            row
+        })
+        .collect();
+    Pattern {
+        rows,
+        numbering: Numbering::default(),
+        two_at_a_time: false,
+        abbreviations: Abbreviations::default(),
+        phrasing: Phrasing::default(),
+    }
+}
+
+pub fn validate(params: &Params, gauge: Option<Gauge>) -> Validation {
+    let mut validation = Validation::default();
+    let width = params.swatch.width(params.units(), params.locale).ok();
+    validation.require_positive(Field::Width, width, "Size");
+    if let (Some(width), Some(gauge), false) = (width, gauge, validation.has_errors()) {
+        validation.check_size(width * gauge.stitches_per_unit, width * gauge.rows_per_unit);
+    }
+    validation
+}
+
+// How to measure the finished swatch and what to do when it doesn't match: counted over the
+// middle two thirds, away from the edges, the way yarn labels give gauge over 10 cm in a 15 cm
+// swatch.
+fn measuring(params: &Params, width: f64, gauge: Gauge) -> Vec<String> {
+    let span = width * 2.0 / 3.0;
+    let span_text = params.locale.dual(span, params.units(), &params.units);
+    let tool = match params.fabric.crochet() {
+        Some(_) => "hook",
+        None => "needles",
+    };
+    vec![
+        format!(
+            "Once dry, lay the swatch on a flat surface without stretching it. Lay a ruler across the middle, away from the edges, and count the stitches in {}, including any half stitch. Your gauge gives about {} stitches.",
+            span_text,
+            params.locale.decimal(span * gauge.stitches_per_unit, 1)
+        ),
+        format!(
+            "Lay the ruler up the middle and count the rows in {}. Your gauge gives about {} rows.",
+            span_text,
+            params.locale.decimal(span * gauge.rows_per_unit, 1)
+        ),
+        format!(
+            "More stitches than that means you work more tightly than the gauge: swatch again with larger {}. Fewer stitches means swatch again with smaller {}. Or enter the gauge you measured and the pattern will be worked out for it.",
+            tool, tool
+        ),
+    ]
+}
+
+pub fn design(params: &Params, gauge: Gauge) -> Option<Design> {
+    let width = params.swatch.width(params.units(), params.locale).ok()?;
+    let pattern = pattern(
+        cast_on(width, gauge.stitches_per_unit),
+        total_rows(width, gauge.rows_per_unit),
+        params.fabric,
+    );
+    let mut finishing = vec![String::from("Bind off all stitches loosely.")];
+    finishing.extend(finishing::notes(
+        params.fiber,
+        BlockingForm::Swatch,
+        &params.units,
+        params.locale,
+    ));
+    finishing.extend(measuring(params, width, gauge));
+    Some(Design {
+        chart: Some(Chart::from_pattern(&pattern)),
+        pattern,
+        finishing,
+        volume: None,
+        weight: None,
+        pieces: 1,
+        profile: Vec::new(),
+    })
+}
+
+pub fn form(params: &Params, validation: &Validation, on_change: Callback<SwatchParams>) -> Html {
+    let width = params.swatch.width(params.units(), params.locale);
+    let on_width_input = on_change.reform(|width| SwatchParams { width });
+    html! {
+        <NumberInput
+            label={"Size: "}
+            placeholder={"Across and up the square"}
+            value={params.swatch.width.clone()}
+            current={width.as_ref().ok().copied()}
+            scale={Scale::for_length(params.units(), 30.0)}
+            locale={params.locale}
+            oninput={on_width_input}
+        >
+            { field_message(&params.swatch.width, &width, &params.units, params.locale, validation, Field::Width) }
+        </NumberInput>
+    }
+}
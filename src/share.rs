@@ -0,0 +1,406 @@
+use crate::abbreviations::Abbreviations;
+use crate::cables::Cable;
+use crate::counts::Rounding;
+use crate::finishing::Fiber;
+use crate::gauge::Fabric;
+use crate::metadata::{License, Metadata};
+use crate::needles::NeedleStyle;
+use crate::numbering::{Numbering, RowWord};
+use crate::palette::{Palette, PalettePreset};
+use crate::params::Params;
+use crate::pattern::Distribution;
+use crate::phrasing::{Phrase, Phrasing};
+use crate::ribbing::Rib;
+use crate::shapes::dome::Base;
+use crate::shapes::hat::CrownStyle;
+use crate::shapes::panel;
+use crate::shapes::sphere::{Cadence, RowSpacing};
+use crate::shapes::{Route, Shape};
+use crate::stripes;
+use crate::tally::Tally;
+use serde::{Deserialize, Serialize};
+use yew_router::Routable;
+
+// The parameters for one shape flattened into a URL query, so a link reproduces the same pattern.
+// Empty fields are left out to keep links short.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShareQuery {
+    // Which shape a read-only view shows; the generator takes it from the route instead.
+    shape: Option<Shape>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    units: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    st: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    rows: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    estimate: bool,
+    // The two yarns' stitch gauges, as "28,20", when the gauge is for them held together.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    held: String,
+    fabric: Option<Fabric>,
+    fiber: Option<Fiber>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    per_100g: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    skein: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    price: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    colors: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    stripe: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    sequence: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    gradient: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    blend: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    self_striping: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    self_striping_colors: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    align: bool,
+    seed: Option<u64>,
+    spacing: Option<Distribution>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    cap: String,
+    rounding: Option<Rounding>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    round_to: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    smooth: bool,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    max_st: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    min_co: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    sizes: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    knitters: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    diameter: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    depth: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    balls: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    weight: String,
+    cadence: Option<Cadence>,
+    row_spacing: Option<RowSpacing>,
+    base: Option<Base>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    circumference: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    length: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    height: String,
+    crown: Option<CrownStyle>,
+    rib: Option<Rib>,
+    cable: Option<Cable>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    eyelets: String,
+    row_word: Option<RowWord>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    skip_cast_on: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    chart_rtl: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pair: bool,
+    needles: Option<NeedleStyle>,
+    palette: Option<PalettePreset>,
+    // Yarn colors and symbols, separated by commas.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    yarn_colors: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    symbols: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    show_symbols: bool,
+    // The knitter's own abbreviations, as "inc=M1,k=K".
+    #[serde(skip_serializing_if = "String::is_empty")]
+    abbr: String,
+    // The studio's own wording, one "knit={n}: K{st}" to a line, as templates have commas in them.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    phrases: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    bobbles: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    repeat: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    rib_first: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    rib_last: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    width: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    blocks: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    title: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    author: String,
+    license: Option<License>,
+    tally: Option<Tally>,
+}
+
+impl ShareQuery {
+    pub fn new(shape: Shape, params: &Params) -> ShareQuery {
+        let mut query = ShareQuery {
+            units: params.units.clone(),
+            st: params.stitches_per_unit.clone(),
+            rows: params.rows_per_unit.clone(),
+            estimate: params.estimate_rows,
+            held: params
+                .held_double
+                .as_ref()
+                .map_or(String::new(), |(first, second)| {
+                    format!("{}|{}", first, second)
+                }),
+            fabric: Some(params.fabric),
+            fiber: Some(params.fiber),
+            per_100g: params.yarn_per_100g.clone(),
+            skein: params.skein_length.clone(),
+            price: params.skein_price.clone(),
+            colors: params.colors.clone(),
+            stripe: params.rows_per_stripe.clone(),
+            sequence: stripes::to_query(&params.stripe_sequence),
+            gradient: params.gradient_colors.clone(),
+            blend: params.blend_rows.clone(),
+            self_striping: params.self_striping_length.clone(),
+            self_striping_colors: params.self_striping_colors.clone(),
+            align: params.self_striping_align,
+            seed: Some(params.seed),
+            spacing: Some(params.distribution),
+            cap: params.increase_cap.clone(),
+            rounding: Some(params.rounding),
+            round_to: params.rounding_multiple.clone(),
+            smooth: params.smooth_counts,
+            max_st: params.max_stitches.clone(),
+            min_co: params.min_cast_on.clone(),
+            sizes: params.sizes.clone(),
+            knitters: params.knitters.clone(),
+            eyelets: params.eyelet_rows.clone(),
+            row_word: Some(params.numbering.word),
+            skip_cast_on: params.numbering.skip_cast_on,
+            chart_rtl: params.numbering.charts_right_to_left,
+            pair: params.two_at_a_time,
+            needles: Some(params.needles),
+            palette: Some(params.palette.preset),
+            yarn_colors: params.palette.colors.join(","),
+            symbols: params.palette.symbols.join(","),
+            show_symbols: params.palette.show_symbols,
+            abbr: params
+                .abbreviations
+                .0
+                .iter()
+                .map(|(from, to)| format!("{}={}", from, to))
+                .collect::<Vec<_>>()
+                .join(","),
+            phrases: params
+                .phrasing
+                .0
+                .iter()
+                .map(|(phrase, template)| format!("{}={}", phrase, template))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            bobbles: params.bobble_density.clone(),
+            repeat: params.repeat.clone(),
+            title: params.metadata.title.clone(),
+            author: params.metadata.author.clone(),
+            license: Some(params.metadata.license),
+            tally: Some(params.tally),
+            ..ShareQuery::default()
+        };
+        match shape {
+            Shape::Sphere => {
+                query.diameter = params.sphere.diameter.clone();
+                query.depth = params.sphere.depth.clone();
+                query.balls = params.sphere.balls.clone();
+                query.weight = params.sphere.weight.clone();
+                query.cadence = Some(params.sphere.cadence);
+                query.row_spacing = Some(params.sphere.spacing);
+            }
+            Shape::Dome => {
+                query.diameter = params.dome.diameter.clone();
+                query.weight = params.dome.weight.clone();
+                query.cadence = Some(params.dome.cadence);
+                query.base = Some(params.dome.base);
+            }
+            Shape::Cylinder => {
+                query.circumference = params.cylinder.circumference.clone();
+                query.length = params.cylinder.length.clone();
+                query.rib_last = params.rib.last_rows.clone();
+            }
+            Shape::Hat => {
+                query.circumference = params.hat.circumference.clone();
+                query.height = params.hat.height.clone();
+                query.crown = Some(params.hat.crown);
+            }
+            Shape::Panel => {
+                query.width = params.panel.width.clone();
+                query.length = params.panel.length.clone();
+                query.blocks = panel::blocks_to_query(&params.panel.blocks);
+            }
+            Shape::Swatch => query.width = params.swatch.width.clone(),
+        }
+        if matches!(shape, Shape::Cylinder | Shape::Hat) && params.rib.rib != Rib::None {
+            query.rib = Some(params.rib.rib);
+            query.rib_first = params.rib.first_rows.clone();
+        }
+        if matches!(shape, Shape::Cylinder | Shape::Hat) && params.cable != Cable::None {
+            query.cable = Some(params.cable);
+        }
+        query
+    }
+
+    // A link to the read-only view of the pattern, which has no route of its own for the shape.
+    pub fn view(shape: Shape, params: &Params) -> ShareQuery {
+        ShareQuery {
+            shape: Some(shape),
+            ..ShareQuery::new(shape, params)
+        }
+    }
+
+    pub fn shape(&self) -> Option<Shape> {
+        self.shape
+    }
+
+    // The full address of the read-only view of the pattern, for sharing outside the app.
+    pub fn view_url(shape: Shape, params: &Params) -> Option<String> {
+        let location = web_sys::window()?.location();
+        let (origin, path) = (location.origin().ok()?, location.pathname().ok()?);
+        let query = serde_urlencoded::to_string(ShareQuery::view(shape, params)).ok()?;
+        Some(format!(
+            "{}{}#{}?{}",
+            origin,
+            path,
+            Route::View.to_path(),
+            query
+        ))
+    }
+
+    // The parameters this query describes; anything it leaves out is taken from `params`.
+    pub fn apply(self, shape: Shape, params: &Params) -> Params {
+        let defaults = Params::default();
+        let mut params = Params {
+            units: self.units,
+            stitches_per_unit: self.st,
+            rows_per_unit: self.rows,
+            estimate_rows: self.estimate,
+            held_double: self
+                .held
+                .split_once('|')
+                // Links made before the bar was used part the two with a comma:
+                .or_else(|| self.held.split_once(','))
+                .map(|(first, second)| (first.to_string(), second.to_string())),
+            fabric: self.fabric.unwrap_or(defaults.fabric),
+            fiber: self.fiber.unwrap_or(defaults.fiber),
+            yarn_per_100g: self.per_100g,
+            skein_length: self.skein,
+            skein_price: self.price,
+            colors: self.colors,
+            rows_per_stripe: self.stripe,
+            stripe_sequence: stripes::from_query(&self.sequence),
+            gradient_colors: self.gradient,
+            blend_rows: self.blend,
+            self_striping_length: self.self_striping,
+            self_striping_colors: self.self_striping_colors,
+            self_striping_align: self.align,
+            seed: self.seed.unwrap_or(defaults.seed),
+            distribution: self.spacing.unwrap_or(defaults.distribution),
+            increase_cap: self.cap,
+            rounding: self.rounding.unwrap_or(defaults.rounding),
+            rounding_multiple: self.round_to,
+            smooth_counts: self.smooth,
+            max_stitches: self.max_st,
+            min_cast_on: self.min_co,
+            sizes: self.sizes,
+            knitters: self.knitters,
+            eyelet_rows: self.eyelets,
+            numbering: Numbering {
+                word: self.row_word.unwrap_or_default(),
+                skip_cast_on: self.skip_cast_on,
+                charts_right_to_left: self.chart_rtl,
+            },
+            two_at_a_time: self.pair,
+            needles: self.needles.unwrap_or_default(),
+            palette: Palette {
+                preset: self.palette.unwrap_or_default(),
+                colors: split_list(&self.yarn_colors),
+                symbols: split_list(&self.symbols),
+                show_symbols: self.show_symbols,
+            },
+            abbreviations: Abbreviations(
+                split_list(&self.abbr)
+                    .iter()
+                    .filter_map(|entry| entry.split_once('='))
+                    .map(|(from, to)| (from.to_string(), to.to_string()))
+                    .collect(),
+            ),
+            phrasing: Phrasing(
+                self.phrases
+                    .lines()
+                    .filter_map(|line| line.split_once('='))
+                    .filter(|(phrase, _)| Phrase::from_key(phrase).is_some())
+                    .map(|(phrase, template)| (phrase.to_string(), template.to_string()))
+                    .collect(),
+            ),
+            bobble_density: self.bobbles,
+            repeat: self.repeat,
+            metadata: Metadata {
+                title: self.title,
+                author: self.author,
+                license: self.license.unwrap_or_default(),
+            },
+            tally: self.tally.unwrap_or_default(),
+            ..params.clone()
+        };
+        match shape {
+            Shape::Sphere => {
+                params.sphere.diameter = self.diameter;
+                params.sphere.depth = self.depth;
+                params.sphere.balls = self.balls;
+                params.sphere.weight = self.weight;
+                params.sphere.cadence = self.cadence.unwrap_or_default();
+                params.sphere.spacing = self.row_spacing.unwrap_or_default();
+            }
+            Shape::Dome => {
+                params.dome.diameter = self.diameter;
+                params.dome.weight = self.weight;
+                params.dome.cadence = self.cadence.unwrap_or_default();
+                params.dome.base = self.base.unwrap_or_default();
+            }
+            Shape::Cylinder => {
+                params.cylinder.circumference = self.circumference;
+                params.cylinder.length = self.length;
+                params.rib.last_rows = self.rib_last;
+            }
+            Shape::Hat => {
+                params.hat.circumference = self.circumference;
+                params.hat.height = self.height;
+                params.hat.crown = self.crown.unwrap_or_default();
+            }
+            Shape::Panel => {
+                params.panel.width = self.width;
+                params.panel.length = self.length;
+                params.panel.blocks = panel::blocks_from_query(&self.blocks);
+            }
+            Shape::Swatch => params.swatch.width = self.width,
+        }
+        if matches!(shape, Shape::Cylinder | Shape::Hat) {
+            params.rib.rib = self.rib.unwrap_or_default();
+            params.rib.first_rows = self.rib_first;
+            params.cable = self.cable.unwrap_or_default();
+        }
+        params
+    }
+}
+
+// A list written with commas between its entries, empty entries kept in place.
+fn split_list(list: &str) -> Vec<String> {
+    match list {
+        "" => Vec::new(),
+        list => list.split(',').map(String::from).collect(),
+    }
+}
@@ -0,0 +1,149 @@
+use crate::colorwork::chart_view;
+use crate::export;
+use crate::form::issue_message;
+use crate::pairs;
+use crate::params::Params;
+use crate::pattern_view::{striped_pattern, Materials};
+use crate::shapes::{Route, Shape};
+use crate::share::ShareQuery;
+use crate::skill;
+use crate::storage;
+use crate::tally::Tally;
+use crate::validate::validate_gauge;
+use crate::web_share::ShareButtons;
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+// A pattern from a shared link laid out for reading or printing, without the form: the materials,
+// every row written out and the finishing, with a way into the generator to change it.
+#[function_component(SharedView)]
+pub fn shared_view() -> Html {
+    let query = use_location()
+        .and_then(|location| location.query::<ShareQuery>().ok())
+        .unwrap_or_default();
+    let shape = query.shape().unwrap_or(Shape::Sphere);
+    // Numbers are written the way the reader last chose in the generator:
+    let reader = Params {
+        locale: storage::load_session()
+            .map(|session| session.params.locale)
+            .unwrap_or_default(),
+        ..Params::default()
+    };
+    let shared = query.apply(shape, &reader);
+    // The reader can choose tally boxes for their own printout:
+    let tally = use_state(|| shared.tally);
+    let params = Params {
+        tally: *tally,
+        ..shared
+    };
+    let gauge = params.gauge();
+    let mut validation = validate_gauge(
+        params.stitches_per_unit().ok(),
+        params.rows_per_unit().ok(),
+        &params.units,
+        params.locale,
+    );
+    validation.merge(shape.validate(&params, gauge));
+    let design = gauge
+        .filter(|_| !validation.has_errors())
+        .and_then(|gauge| Some((shape.design(&params, gauge)?, gauge)));
+    let edit = html! {
+        <p class="shared-actions">
+            <Link<Route, ShareQuery> to={shape.route()} query={Some(ShareQuery::new(shape, &params))}>
+                {"Open in the generator"}
+            </Link<Route, ShareQuery>>
+        </p>
+    };
+    let Some((design, gauge)) = design else {
+        return html! {
+            <div class="shared-view">
+                <p>{"This link doesn't describe a pattern that can be worked out:"}</p>
+                { for validation.issues.iter().map(issue_message) }
+                { edit }
+            </div>
+        };
+    };
+
+    let pattern = striped_pattern(&design, &params);
+    let materials = Materials::new(&design, &pattern, &params, gauge);
+    let on_print = Callback::from(|_: MouseEvent| {
+        if let Some(window) = web_sys::window() {
+            let _ = window.print();
+        }
+    });
+    let on_tally_change = Callback::from(move |e: Event| {
+        let select_el: HtmlSelectElement = e.target_unchecked_into();
+        if let Some(chosen) = Tally::from_key(&select_el.value()) {
+            tally.set(chosen);
+        }
+    });
+    let on_download = {
+        let (design, params) = (design.clone(), params.clone());
+        Callback::from(move |_: MouseEvent| {
+            let text = export::pattern_text(shape, &design, &params, gauge);
+            export::download("pattern.txt", "text/plain", &text);
+        })
+    };
+
+    html! {
+        <div class="shared-view">
+            if let Some(heading) = params.metadata.heading() {
+                <div class="pattern-heading">
+                    { for heading.iter().map(|line| html! {<p>{line}</p>}) }
+                </div>
+            }
+            <p class="pattern-summary">{shape.summary(&params)}</p>
+            <p class="pattern-summary">{skill::describe(&pattern)}</p>
+            if let Some(chart) = &design.chart {
+                { chart_view(chart, &design.pattern, &params.palette) }
+            }
+            <h3>{"Materials"}</h3>
+            <ul>
+                <li>{&materials.yarn}</li>
+                { for materials.set.iter().map(|line| html! {<li>{line}</li>}) }
+                { for materials.held_double.iter().map(|line| html! {<li>{line}</li>}) }
+                <ul>{ for materials.by_color.iter().map(|line| html! {<li>{line}</li>}) }</ul>
+                { for materials.cost.iter().map(|line| html! {<li>{line}</li>}) }
+                { for materials.needles.iter().map(|line| html! {<li>{line}</li>}) }
+                <li>{format!(
+                    "Gauge: {} stitches and {} {}s per {}",
+                    params.locale.decimal(gauge.stitches_per_unit, 1),
+                    params.locale.decimal(gauge.rows_per_unit, 1),
+                    pattern.word().to_lowercase(),
+                    &params.units
+                )}</li>
+            </ul>
+            <h3>{"Abbreviations"}</h3>
+            <ul>{ for pattern.abbreviations.glossary(&pattern).iter().map(|line| html! {<li>{line}</li>}) }</ul>
+            <h3>{"Instructions"}</h3>
+            if pattern.two_at_a_time {
+                <ul>{ for pairs::setup(&pattern).iter().map(|line| html! {<li>{line}</li>}) }</ul>
+            }
+            <ul class="shared-rows">
+                { for (0..pattern.rows.len()).map(|i| html! {
+                    <li>{ params.tally.view(i, pattern.rows.len()) }{pattern.written_row(i)}</li>
+                }) }
+            </ul>
+            <h3>{"Finishing"}</h3>
+            <ul>
+                { for design.finishing.iter().map(|note| html! {<li>{note}</li>}) }
+                { for materials.stuffing.iter().map(|line| html! {<li>{line}</li>}) }
+            </ul>
+            if let Some(heading) = params.metadata.heading() {
+                <p class="pattern-license">{heading.last().cloned()}</p>
+            }
+            <p class="shared-actions">
+                <select aria-label="Tally boxes" onchange={on_tally_change}>
+                    { for Tally::ALL.iter().map(|t| html! {
+                        <option value={t.key()} selected={*t == params.tally}>{t.name()}</option>
+                    }) }
+                </select>
+                <button type="button" onclick={on_print}>{"Print"}</button>
+                <button type="button" onclick={on_download}>{"Download as text"}</button>
+                <ShareButtons {shape} design={design.clone()} params={params.clone()} {gauge} />
+            </p>
+            { edit }
+        </div>
+    }
+}
@@ -0,0 +1,111 @@
+use crate::gauge::Gauge;
+use crate::grading::sized_pattern;
+use crate::params::Params;
+use crate::parse::parse_length;
+use crate::shapes::Shape;
+use crate::units::Unit;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+// Enough sizes for any sensible set without generating hundreds of patterns.
+const MAX_SIZES: usize = 25;
+
+#[derive(Properties, PartialEq)]
+pub struct SizingTableProps {
+    pub shape: Shape,
+    pub params: Params,
+    pub gauge: Gauge,
+}
+
+// The cast on, widest row and total rows for a range of sizes at the same gauge, for planning a
+// set of pieces such as graded ornaments or a set of juggling balls.
+#[function_component(SizingTable)]
+pub fn sizing_table(props: &SizingTableProps) -> Html {
+    let (shape, params) = (props.shape, &props.params);
+    let units = params.units();
+    let [smallest, _, largest] = shape.preset_sizes();
+    let in_units = |inches: f64| Unit::convert(inches, Unit::Inch, units.unwrap_or(Unit::Inch));
+    let from = use_state(|| format!("{}", in_units(smallest).round()));
+    let to = use_state(|| format!("{}", in_units(largest).round()));
+    let step = use_state(|| format!("{}", in_units(1.0).round()));
+    let shown = use_state(|| false);
+    let on_input = |state: &UseStateHandle<String>| {
+        let state = state.clone();
+        Callback::from(move |e: InputEvent| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            state.set(input_el.value());
+        })
+    };
+    let on_show = {
+        let shown = shown.clone();
+        Callback::from(move |_: MouseEvent| shown.set(true))
+    };
+
+    let range = match (
+        parse_length(&from, units, params.locale),
+        parse_length(&to, units, params.locale),
+        parse_length(&step, units, params.locale),
+    ) {
+        (Ok(from), Ok(to), Ok(step)) if from > 0.0 && to >= from && step > 0.0 => {
+            let count = ((to - from) / step + 1e-9).floor() as usize + 1;
+            Ok((0..count.min(MAX_SIZES))
+                .map(|i| from + i as f64 * step)
+                .collect::<Vec<f64>>())
+        }
+        _ => Err("Enter a smallest and largest size and a step between them."),
+    };
+    let table = match (*shown, range) {
+        (false, _) => html! {},
+        (true, Err(message)) => html! {<p class="field-error">{message}</p>},
+        (true, Ok(sizes)) => {
+            let rows = sizes.iter().map(|size| {
+                let size_text = format!("{}", (size * 100.0).round() / 100.0);
+                let cells = match sized_pattern(shape, params, props.gauge, &size_text) {
+                    Ok(pattern) => html! {
+                        <>
+                            <td>{pattern.rows.first().map_or(0, |row| row.stitches)}</td>
+                            <td>{pattern.rows.iter().map(|row| row.stitches).max().unwrap_or(0)}</td>
+                            <td>{pattern.rows.len()}</td>
+                        </>
+                    },
+                    Err(message) => html! {<td colspan="3" class="field-warning">{message}</td>},
+                };
+                html! {
+                    <tr>
+                        <td>{params.locale.dual(*size, units, &params.units)}</td>
+                        {cells}
+                    </tr>
+                }
+            });
+            html! {
+                <table class="stitch-table">
+                    <thead>
+                        <tr>
+                            <th>{shape.size_name()}</th>
+                            <th>{"Cast on"}</th>
+                            <th>{"Most stitches"}</th>
+                            <th>{"Rows"}</th>
+                        </tr>
+                    </thead>
+                    <tbody>{ for rows }</tbody>
+                </table>
+            }
+        }
+    };
+
+    html! {
+        <div class="sizing-table">
+            <span>
+                <label>{format!("{} from ", shape.size_name())}</label>
+                <input aria-label={format!("{} from", shape.size_name())} type="text" size="4" value={(*from).clone()} oninput={on_input(&from)}/>
+                <label>{" to "}</label>
+                <input aria-label={format!("{} to", shape.size_name())} type="text" size="4" value={(*to).clone()} oninput={on_input(&to)}/>
+                <label>{" in steps of "}</label>
+                <input aria-label="In steps of" type="text" size="4" value={(*step).clone()} oninput={on_input(&step)}/>
+                {format!(" {} ", &params.units)}
+                <button type="button" onclick={on_show}>{"Make table"}</button>
+            </span>
+            {table}
+        </div>
+    }
+}
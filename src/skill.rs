@@ -0,0 +1,150 @@
+use crate::pattern::{Pattern, Shaping};
+
+// The techniques a pattern asks of the knitter, worked out from its rows.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Technique {
+    KnitPurl,
+    InTheRound,
+    Increases,
+    Decreases,
+    Stripes,
+    Texture,
+    Crochet,
+    Eyelets,
+    Bobbles,
+    Cables,
+    Intarsia,
+    Brioche,
+}
+
+// The Craft Yarn Council's levels, which yarn shops and pattern sites use.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SkillLevel {
+    Beginner,
+    Easy,
+    Intermediate,
+    Experienced,
+}
+
+impl Technique {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Technique::KnitPurl => "knit and purl",
+            Technique::InTheRound => "working in the round",
+            Technique::Increases => "increases",
+            Technique::Decreases => "decreases",
+            Technique::Stripes => "changing colors",
+            Technique::Texture => "textured stitches",
+            Technique::Crochet => "crochet",
+            Technique::Eyelets => "eyelets",
+            Technique::Bobbles => "bobbles",
+            Technique::Cables => "cables",
+            Technique::Intarsia => "intarsia",
+            Technique::Brioche => "two-color brioche",
+        }
+    }
+
+    pub fn level(&self) -> SkillLevel {
+        match self {
+            Technique::KnitPurl | Technique::Crochet => SkillLevel::Beginner,
+            Technique::Increases | Technique::Decreases | Technique::Stripes => SkillLevel::Easy,
+            Technique::InTheRound | Technique::Texture | Technique::Eyelets => SkillLevel::Easy,
+            Technique::Bobbles | Technique::Cables => SkillLevel::Intermediate,
+            Technique::Intarsia | Technique::Brioche => SkillLevel::Experienced,
+        }
+    }
+
+    // Every technique used somewhere in `pattern`, easiest first.
+    pub fn used_in(pattern: &Pattern) -> Vec<Technique> {
+        let mut used = vec![Technique::KnitPurl];
+        if pattern.rows.iter().any(|row| row.crochet.is_some()) {
+            used = vec![Technique::Crochet];
+        }
+        let mut add = |technique: Technique| {
+            if !used.contains(&technique) {
+                used.push(technique);
+            }
+        };
+        if !pattern.is_flat() {
+            add(Technique::InTheRound);
+        }
+        for row in &pattern.rows {
+            match row.shaping {
+                Shaping::CastOn | Shaping::Plain | Shaping::Purl | Shaping::Bordered { .. } => {}
+                Shaping::Texture { .. } => add(Technique::Texture),
+                Shaping::Brioche { .. } => add(Technique::Brioche),
+                Shaping::Eyelet => add(Technique::Eyelets),
+                Shaping::Cable { .. } => add(Technique::Cables),
+                Shaping::Decrease { .. }
+                | Shaping::DecreaseSpaced { .. }
+                | Shaping::DecreaseAt { .. } => add(Technique::Decreases),
+                Shaping::Amigurumi { decrease: true, .. } => add(Technique::Decreases),
+                Shaping::Alternate { .. }
+                | Shaping::Spaced { .. }
+                | Shaping::Even { .. }
+                | Shaping::Amigurumi { .. }
+                | Shaping::Ring { .. }
+                | Shaping::Single => add(Technique::Increases),
+            }
+            if !row.segments.is_empty() {
+                add(Technique::Intarsia);
+            }
+            if !row.bobbles.is_empty() {
+                add(Technique::Bobbles);
+            }
+        }
+        if pattern.rows.iter().any(|row| row.color != 0) {
+            add(Technique::Stripes);
+        }
+        used.sort_by_key(Technique::level);
+        used
+    }
+}
+
+impl SkillLevel {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SkillLevel::Beginner => "Beginner",
+            SkillLevel::Easy => "Easy",
+            SkillLevel::Intermediate => "Intermediate",
+            SkillLevel::Experienced => "Experienced",
+        }
+    }
+
+    // The level of the hardest technique `pattern` uses, one level up when it also asks for many
+    // different things at once.
+    pub fn of(pattern: &Pattern) -> SkillLevel {
+        let techniques = Technique::used_in(pattern);
+        let hardest = techniques
+            .iter()
+            .map(Technique::level)
+            .max()
+            .unwrap_or(SkillLevel::Beginner);
+        let demanding = techniques
+            .iter()
+            .filter(|t| t.level() >= SkillLevel::Easy)
+            .count();
+        match (hardest, demanding >= 5) {
+            (SkillLevel::Easy, true) => SkillLevel::Intermediate,
+            (hardest, _) => hardest,
+        }
+    }
+}
+
+// A line for the top of the pattern, such as "Skill level: Easy (working in the round,
+// increases, decreases)".
+pub fn describe(pattern: &Pattern) -> String {
+    let techniques: Vec<&str> = Technique::used_in(pattern)
+        .iter()
+        .filter(|t| t.level() > SkillLevel::Beginner)
+        .map(Technique::name)
+        .collect();
+    match techniques.is_empty() {
+        true => format!("Skill level: {}", SkillLevel::of(pattern).name()),
+        false => format!(
+            "Skill level: {} ({})",
+            SkillLevel::of(pattern).name(),
+            techniques.join(", ")
+        ),
+    }
+}
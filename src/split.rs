@@ -0,0 +1,73 @@
+use crate::pattern::Pattern;
+
+// One knitter's share of a pattern split between several: rows `start` up to but not including
+// `end`, by index.
+#[derive(Clone, Copy, PartialEq)]
+pub struct Chunk {
+    pub start: usize,
+    pub end: usize,
+}
+
+// The pattern cut into `knitters` runs of rows one after another, each with about the same number
+// of stitches to work. A chunk is never empty, so there are fewer when there are fewer rows.
+pub fn chunks(pattern: &Pattern, knitters: usize) -> Vec<Chunk> {
+    let knitters = knitters.clamp(1, pattern.rows.len().max(1));
+    let total = pattern.total_stitches().max(1) as f64;
+    let mut chunks = Vec::new();
+    let (mut start, mut worked) = (0, 0);
+    for (i, row) in pattern.rows.iter().enumerate() {
+        worked += row.worked();
+        let left = pattern.rows.len() - i - 1;
+        let share = (chunks.len() + 1) as f64 * total / knitters as f64;
+        let wanted = knitters - chunks.len() - 1;
+        if chunks.len() + 1 < knitters && (worked as f64 >= share || left == wanted) {
+            chunks.push(Chunk { start, end: i + 1 });
+            start = i + 1;
+        }
+    }
+    chunks.push(Chunk {
+        start,
+        end: pattern.rows.len(),
+    });
+    chunks
+}
+
+// What knitter `number` (from 1) of the chunks works and how to start.
+pub fn header(pattern: &Pattern, chunks: &[Chunk], number: usize) -> String {
+    let chunk = chunks[number - 1];
+    let from = pattern.label(chunk.start);
+    let to = pattern.label(chunk.end - 1);
+    let rows = match chunk.end - chunk.start {
+        1 => from,
+        _ => format!("{} to {}", from, to),
+    };
+    match chunk.start {
+        0 => format!(
+            "Section {} of {}: {}. Cast on as written.",
+            number,
+            chunks.len(),
+            rows
+        ),
+        start => format!(
+            "Section {} of {}: {}. Cast on {} stitches with a provisional cast on; they are joined to the live stitches of section {}.",
+            number,
+            chunks.len(),
+            rows,
+            pattern.rows[start - 1].stitches,
+            number - 1
+        ),
+    }
+}
+
+// How the sections come together once all are knitted.
+pub fn joining(pattern: &Pattern) -> String {
+    let joined = match pattern.is_flat() {
+        true => "",
+        false => ", all the way around",
+    };
+    format!(
+        "Leave each section's last {} on the needle. To join, undo the provisional cast on of the next section and graft its stitches to them{}, in order from section 1 up.",
+        pattern.word().to_lowercase(),
+        joined
+    )
+}
@@ -0,0 +1,60 @@
+use crate::storage::{self, Stats};
+use yew::prelude::*;
+
+// A tally of what the knitter has done with the app, read from this browser's storage each time
+// the panel is opened, since the counts change all over the app.
+#[function_component(StatsPanel)]
+pub fn stats_panel() -> Html {
+    let stats = use_state(storage::load_stats);
+    let on_toggle = {
+        let stats = stats.clone();
+        Callback::from(move |_: Event| stats.set(storage::load_stats()))
+    };
+    let on_reset = {
+        let stats = stats.clone();
+        Callback::from(move |_: MouseEvent| stats.set(storage::reset_stats()))
+    };
+
+    let body = match &*stats {
+        Stats {
+            generated: 0,
+            stitches_knit: 0,
+            ..
+        } => html! {
+            <p>{"Nothing yet: patterns you generate and rows you tick off are counted here."}</p>
+        },
+        stats => {
+            let shapes: Vec<String> = stats
+                .shapes
+                .iter()
+                .map(|(shape, count)| format!("{} {}", shape, count))
+                .collect();
+            html! {
+                <>
+                    <ul>
+                        <li>{format!("Patterns generated: {}", stats.generated)}</li>
+                        if !shapes.is_empty() {
+                            <li>{format!("By shape: {}", shapes.join(", "))}</li>
+                        }
+                        <li>{format!(
+                            "Knitted with the row tracker: {} rows, {} stitches",
+                            stats.rows_knit, stats.stitches_knit
+                        )}</li>
+                    </ul>
+                    <p class="field-note">
+                        {format!("Counted since {}. ", stats.since.get(..10).unwrap_or(&stats.since))}
+                        <button type="button" onclick={on_reset}>{"Reset"}</button>
+                    </p>
+                </>
+            }
+        }
+    };
+
+    html! {
+        <details class="panel" ontoggle={on_toggle}>
+            <summary><h3>{"Your stats"}</h3></summary>
+            { body }
+            <span class="field-note">{"Kept in this browser only; nothing is sent anywhere."}</span>
+        </details>
+    }
+}
@@ -0,0 +1,51 @@
+use crate::gauge::Gauge;
+use crate::locale::Locale;
+use crate::pattern::{color_name, Pattern};
+use crate::units::Unit;
+use yew::prelude::*;
+
+// The numbers behind the instructions, one line per row, for checking a pattern at a glance.
+pub fn stitch_table(
+    pattern: &Pattern,
+    gauge: Gauge,
+    units: Option<Unit>,
+    unit_label: &str,
+    locale: Locale,
+) -> Html {
+    let multicolor = pattern.rows.iter().any(|row| row.color != 0);
+    let rows = pattern.rows.iter().enumerate().map(|(i, row)| {
+        let change = match i {
+            0 => String::new(),
+            _ => format!("{:+}", row.stitches - pattern.rows[i - 1].stitches),
+        };
+        let circumference = f64::from(row.stitches) / gauge.stitches_per_unit;
+        html! {
+            <tr>
+                <td>{pattern.numbering.shown(row.number)}</td>
+                <td>{row.stitches}</td>
+                <td>{change}</td>
+                <td>{locale.dual(circumference, units, unit_label)}</td>
+                if multicolor {
+                    <td>{color_name(row.color)}</td>
+                }
+            </tr>
+        }
+    });
+
+    html! {
+        <table class="stitch-table">
+            <thead>
+                <tr>
+                    <th>{pattern.word()}</th>
+                    <th>{"Stitches"}</th>
+                    <th>{"Change"}</th>
+                    <th>{"Circumference"}</th>
+                    if multicolor {
+                        <th>{"Color"}</th>
+                    }
+                </tr>
+            </thead>
+            <tbody>{ for rows }</tbody>
+        </table>
+    }
+}
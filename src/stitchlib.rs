@@ -0,0 +1,159 @@
+use std::hash::{Hash, Hasher};
+
+// A stitch pattern as data: a repeat `width` stitches wide with one entry in `rows` for each row
+// of it, worked from the right side or around.
+pub struct StitchPattern {
+    pub key: &'static str,
+    pub name: &'static str,
+    pub width: i32,
+    pub rows: &'static [&'static str],
+    // Ratio of stitch gauge to row gauge in this stitch, as for `Fabric::ratio`.
+    pub ratio: f64,
+    // An all-over fabric that eyelets and bobbles can be worked into, unlike ribs and cables.
+    pub background: bool,
+}
+
+// Stitch patterns are told apart by key alone.
+impl PartialEq for StitchPattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Hash for StitchPattern {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key.hash(state);
+    }
+}
+
+impl StitchPattern {
+    // Row `row` of the repeat, worked all the way along.
+    pub fn written_row(&self, row: usize) -> String {
+        format!("*{} rep from * to end", self.rows[row % self.rows.len()])
+    }
+}
+
+pub static LIBRARY: [StitchPattern; 14] = [
+    StitchPattern {
+        key: "stockinette",
+        name: "Stockinette",
+        width: 1,
+        rows: &["k1"],
+        ratio: 0.75,
+        background: true,
+    },
+    StitchPattern {
+        key: "garter",
+        name: "Garter",
+        width: 1,
+        rows: &["k1", "p1"],
+        ratio: 0.5,
+        background: true,
+    },
+    StitchPattern {
+        key: "seed",
+        name: "Seed (moss)",
+        width: 2,
+        rows: &["k1, p1", "p1, k1"],
+        ratio: 0.6,
+        background: true,
+    },
+    // Worked flat, each row in two passes: the first color brioche knits, then the second brioche
+    // purls. Rows are counted by the Vs up one column, so they are much taller than plain rows.
+    StitchPattern {
+        key: "brioche",
+        name: "Two-color brioche",
+        width: 2,
+        rows: &["brk1, sl1yo", "sl1yo, brp1"],
+        ratio: 0.9,
+        background: false,
+    },
+    StitchPattern {
+        key: "sc",
+        name: "Single crochet",
+        width: 1,
+        rows: &["sc1"],
+        ratio: 1.0,
+        background: true,
+    },
+    // Taller crochet stitches: a half double is about half as tall again as a single crochet, and
+    // a double about twice as tall, so the same height takes fewer rounds.
+    StitchPattern {
+        key: "hdc",
+        name: "Half double crochet",
+        width: 1,
+        rows: &["hdc1"],
+        ratio: 1.5,
+        background: true,
+    },
+    StitchPattern {
+        key: "dc",
+        name: "Double crochet",
+        width: 1,
+        rows: &["dc1"],
+        ratio: 2.0,
+        background: true,
+    },
+    StitchPattern {
+        key: "k1p1",
+        name: "k1, p1 rib",
+        width: 2,
+        rows: &["k1, p1"],
+        ratio: 0.75,
+        background: false,
+    },
+    StitchPattern {
+        key: "k2p2",
+        name: "k2, p2 rib",
+        width: 4,
+        rows: &["k2, p2"],
+        ratio: 0.75,
+        background: false,
+    },
+    // CnB crosses n stitches to the right (half held at the back), CnF to the left (held at the
+    // front).
+    StitchPattern {
+        key: "c4b",
+        name: "4-stitch right twist",
+        width: 4,
+        rows: &["C4B", "k4", "k4", "k4"],
+        ratio: 0.75,
+        background: false,
+    },
+    StitchPattern {
+        key: "c4f",
+        name: "4-stitch left twist",
+        width: 4,
+        rows: &["C4F", "k4", "k4", "k4"],
+        ratio: 0.75,
+        background: false,
+    },
+    StitchPattern {
+        key: "rope",
+        name: "6-stitch rope",
+        width: 6,
+        rows: &["C6B", "k6", "k6", "k6", "k6", "k6"],
+        ratio: 0.75,
+        background: false,
+    },
+    StitchPattern {
+        key: "braid",
+        name: "9-stitch braid",
+        width: 9,
+        rows: &["C6B, k3", "k9", "k3, C6F", "k9"],
+        ratio: 0.75,
+        background: false,
+    },
+    StitchPattern {
+        key: "horseshoe",
+        name: "8-stitch horseshoe",
+        width: 8,
+        rows: &["C4B, C4F", "k8", "k8", "k8", "k8", "k8"],
+        ratio: 0.75,
+        background: false,
+    },
+];
+
+pub fn find(key: &str) -> Option<&'static StitchPattern> {
+    LIBRARY.iter().find(|stitch| stitch.key == key)
+}
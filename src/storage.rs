@@ -0,0 +1,306 @@
+use crate::needle_inventory::OwnedNeedle;
+use crate::params::Params;
+use crate::pattern::Pattern;
+use crate::presets::Preset;
+use crate::ravelry::RavelryAccount;
+use crate::shapes::Shape;
+use crate::theme::Theme;
+use gloo_storage::{LocalStorage, Storage};
+use log::warn;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use web_sys::js_sys::Date;
+
+const PROJECTS_KEY: &str = "knitting-pattern-generator:projects";
+const PRESETS_KEY: &str = "knitting-pattern-generator:presets";
+const PROGRESS_KEY: &str = "knitting-pattern-generator:progress";
+const RECENT_KEY: &str = "knitting-pattern-generator:recent";
+const SESSION_KEY: &str = "knitting-pattern-generator:session";
+const NOTES_KEY: &str = "knitting-pattern-generator:notes";
+const SPEED_KEY: &str = "knitting-pattern-generator:speed";
+const EXPLAIN_KEY: &str = "knitting-pattern-generator:explain";
+const THEME_KEY: &str = "knitting-pattern-generator:theme";
+const STATS_KEY: &str = "knitting-pattern-generator:stats";
+const NEEDLES_KEY: &str = "knitting-pattern-generator:needles";
+const RAVELRY_KEY: &str = "knitting-pattern-generator:ravelry";
+
+// Reads a value saved under `key`, falling back to the default if there is none or it can't be read.
+pub fn load<T: DeserializeOwned + Default>(key: &str) -> T {
+    LocalStorage::get(key).unwrap_or_default()
+}
+
+pub fn store<T: Serialize>(key: &str, value: &T) {
+    if let Err(err) = LocalStorage::set(key, value) {
+        warn!("Couldn't save {}: {}", key, err);
+    }
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Project {
+    pub name: String,
+    pub shape: Shape,
+    pub params: Params,
+    // Earlier versions saved under the same name, oldest first.
+    #[serde(default)]
+    pub revisions: Vec<Revision>,
+}
+
+// A version of a project that was saved over, with what changed in the pattern when it was.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Revision {
+    pub shape: Shape,
+    pub params: Params,
+    // When it was replaced, as an ISO 8601 date and time.
+    pub replaced: String,
+    pub changes: Vec<String>,
+}
+
+pub fn load_projects() -> Vec<Project> {
+    load(PROJECTS_KEY)
+}
+
+// Saves a project, replacing any existing project with the same name.
+pub fn save_project(project: Project) -> Vec<Project> {
+    let mut projects = load_projects();
+    projects.retain(|p| p.name != project.name);
+    projects.push(project);
+    projects.sort_by_key(|p| p.name.to_lowercase());
+    store(PROJECTS_KEY, &projects);
+    projects
+}
+
+pub fn delete_project(name: &str) -> Vec<Project> {
+    let mut projects = load_projects();
+    projects.retain(|p| p.name != name);
+    store(PROJECTS_KEY, &projects);
+    projects
+}
+
+// Refuses a name another project already has, rather than saving over it.
+pub fn rename_project(name: &str, new_name: &str) -> Result<Vec<Project>, String> {
+    let projects = load_projects();
+    if projects.iter().any(|p| p.name == new_name) {
+        return Err(format!(
+            "There is already a project called \"{}\"",
+            new_name
+        ));
+    }
+    Ok(match projects.into_iter().find(|p| p.name == name) {
+        Some(project) => {
+            delete_project(name);
+            save_project(Project {
+                name: new_name.to_string(),
+                ..project
+            })
+        }
+        None => load_projects(),
+    })
+}
+
+pub fn load_presets() -> Vec<Preset> {
+    load(PRESETS_KEY)
+}
+
+// Saves a preset, replacing any existing preset of the same name.
+pub fn save_preset(preset: Preset) -> Vec<Preset> {
+    let mut presets = load_presets();
+    presets.retain(|p| p.name != preset.name);
+    presets.push(preset);
+    presets.sort_by_key(|p| p.name.to_lowercase());
+    store(PRESETS_KEY, &presets);
+    presets
+}
+
+pub fn delete_preset(name: &str) -> Vec<Preset> {
+    let mut presets = load_presets();
+    presets.retain(|p| p.name != name);
+    store(PRESETS_KEY, &presets);
+    presets
+}
+
+// The form as it was last left, saved as it changes.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    pub shape: Shape,
+    pub params: Params,
+}
+
+pub fn load_session() -> Option<Session> {
+    load(SESSION_KEY)
+}
+
+pub fn save_session(session: &Session) {
+    store(SESSION_KEY, session);
+}
+
+// How many recently generated patterns are remembered.
+const RECENT_LIMIT: usize = 10;
+
+// A pattern generated recently, with a few numbers to recognise it by.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Recent {
+    pub session: Session,
+    pub rows: usize,
+    pub widest: i32,
+}
+
+pub fn load_recent() -> Vec<Recent> {
+    load(RECENT_KEY)
+}
+
+// Puts a pattern at the top of the recent list, dropping the oldest beyond the limit.
+pub fn add_recent(recent: Recent) -> Vec<Recent> {
+    let mut list = load_recent();
+    list.retain(|r| r.session != recent.session);
+    list.insert(0, recent);
+    list.truncate(RECENT_LIMIT);
+    store(RECENT_KEY, &list);
+    list
+}
+
+// The number of the last row finished in the pattern with this id, or 0 if it hasn't been started.
+pub fn load_progress(pattern_id: &str) -> usize {
+    let progress: HashMap<String, usize> = load(PROGRESS_KEY);
+    progress.get(pattern_id).copied().unwrap_or(0)
+}
+
+pub fn save_progress(pattern_id: &str, rows_done: usize) {
+    let mut progress: HashMap<String, usize> = load(PROGRESS_KEY);
+    if rows_done == 0 {
+        progress.remove(pattern_id);
+    } else {
+        progress.insert(pattern_id.to_string(), rows_done);
+    }
+    store(PROGRESS_KEY, &progress);
+}
+
+// Notes on rows of the pattern with this id, by row number.
+pub fn load_notes(pattern_id: &str) -> BTreeMap<usize, String> {
+    let notes: HashMap<String, BTreeMap<usize, String>> = load(NOTES_KEY);
+    notes.get(pattern_id).cloned().unwrap_or_default()
+}
+
+pub fn save_notes(pattern_id: &str, pattern_notes: &BTreeMap<usize, String>) {
+    let mut notes: HashMap<String, BTreeMap<usize, String>> = load(NOTES_KEY);
+    if pattern_notes.is_empty() {
+        notes.remove(pattern_id);
+    } else {
+        notes.insert(pattern_id.to_string(), pattern_notes.clone());
+    }
+    store(NOTES_KEY, &notes);
+}
+
+// How fast the knitter works, in stitches per minute, as typed.
+pub fn load_speed() -> String {
+    load(SPEED_KEY)
+}
+
+pub fn save_speed(speed: &str) {
+    store(SPEED_KEY, &speed);
+}
+
+// Whether rows are explained for beginners.
+pub fn load_explain() -> bool {
+    load(EXPLAIN_KEY)
+}
+
+pub fn save_explain(explain: bool) {
+    store(EXPLAIN_KEY, &explain);
+}
+
+// The needles the knitter owns.
+pub fn load_needles() -> Vec<OwnedNeedle> {
+    load(NEEDLES_KEY)
+}
+
+pub fn save_needles(needles: &[OwnedNeedle]) {
+    store(NEEDLES_KEY, &needles);
+}
+
+pub fn load_ravelry() -> RavelryAccount {
+    load(RAVELRY_KEY)
+}
+
+pub fn save_ravelry(account: &RavelryAccount) {
+    store(RAVELRY_KEY, account);
+}
+
+pub fn load_theme() -> Theme {
+    load(THEME_KEY)
+}
+
+pub fn save_theme(theme: Theme) {
+    store(THEME_KEY, &theme);
+}
+
+// What the knitter has done with the app, kept in this browser only and never sent anywhere.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Stats {
+    // When counting started, as an ISO 8601 date and time.
+    pub since: String,
+    pub generated: u32,
+    // Patterns generated, by shape name.
+    pub shapes: BTreeMap<String, u32>,
+    pub rows_knit: u64,
+    pub stitches_knit: u64,
+    // The furthest row ticked off in each pattern, by pattern id, so unticking and ticking a row
+    // again doesn't count it twice.
+    furthest: HashMap<String, usize>,
+    // The last pattern counted as generated, by pattern id, so coming back to it isn't counted
+    // again.
+    #[serde(default)]
+    last_generated: String,
+}
+
+pub fn load_stats() -> Stats {
+    load(STATS_KEY)
+}
+
+fn update_stats(change: impl FnOnce(&mut Stats)) -> Stats {
+    let mut stats = load_stats();
+    if stats.since.is_empty() {
+        stats.since = String::from(Date::new_0().to_iso_string());
+    }
+    change(&mut stats);
+    store(STATS_KEY, &stats);
+    stats
+}
+
+pub fn record_generated(shape: Shape, pattern_id: &str) -> Stats {
+    update_stats(|stats| {
+        if stats.last_generated != pattern_id {
+            stats.generated += 1;
+            *stats.shapes.entry(shape.name().to_string()).or_default() += 1;
+            stats.last_generated = pattern_id.to_string();
+        }
+    })
+}
+
+// Counts the rows of `pattern` up to row `done` that haven't been counted before. Starting the
+// pattern over counts its rows again, as for a second piece.
+pub fn record_knitting(pattern: &Pattern, done: usize) {
+    let id = pattern.id();
+    update_stats(|stats| {
+        let furthest = match done {
+            0 => stats.furthest.remove(&id),
+            _ => stats.furthest.get(&id).copied(),
+        }
+        .unwrap_or(0);
+        if done > furthest {
+            let rows = pattern
+                .rows
+                .iter()
+                .filter(|row| (furthest + 1..=done).contains(&row.number));
+            stats.rows_knit += rows.count() as u64;
+            stats.stitches_knit +=
+                (pattern.stitches_through(done) - pattern.stitches_through(furthest)) as u64;
+            stats.furthest.insert(id, done);
+        }
+    });
+}
+
+pub fn reset_stats() -> Stats {
+    store(STATS_KEY, &Stats::default());
+    Stats::default()
+}
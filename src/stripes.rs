@@ -0,0 +1,48 @@
+use crate::form::parse_count;
+use crate::pattern::color_name;
+use serde::{Deserialize, Serialize};
+
+// The most colors a stripe sequence can choose from.
+pub const MAX_COLORS: usize = 6;
+
+// One band of a stripe sequence: `rows` rows, as typed, worked in `color`.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Stripe {
+    pub color: usize,
+    pub rows: String,
+}
+
+// The bands of a sequence as (color, rows), leaving out any whose rows can't be read or are zero.
+pub fn bands(sequence: &[Stripe]) -> Vec<(usize, usize)> {
+    sequence
+        .iter()
+        .filter_map(|stripe| {
+            parse_count(&stripe.rows)
+                .filter(|rows| *rows > 0)
+                .map(|rows| (stripe.color, rows))
+        })
+        .collect()
+}
+
+// A sequence written as "A4,B2,A1" for links.
+pub fn to_query(sequence: &[Stripe]) -> String {
+    sequence
+        .iter()
+        .map(|stripe| format!("{}{}", color_name(stripe.color), stripe.rows))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+pub fn from_query(query: &str) -> Vec<Stripe> {
+    query
+        .split(',')
+        .filter_map(|band| {
+            let letter = band.chars().next()?;
+            let color = (b'A'..b'A' + MAX_COLORS as u8).position(|c| char::from(c) == letter)?;
+            Some(Stripe {
+                color,
+                rows: band[1..].to_string(),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,103 @@
+use crate::locale::Locale;
+use crate::units::Unit;
+use std::f64::consts::PI;
+
+// Polyester fiberfill packed firmly enough for a toy to hold its shape.
+const FIBERFILL_GRAMS_PER_CM3: f64 = 0.03;
+
+// Plastic pellets poured loose, as sold for weighting toys and beanbags.
+const PELLET_GRAMS_PER_CM3: f64 = 0.8;
+
+// Weighted pieces are left no more than this full of pellets, so juggling balls land soft without
+// bouncing or rolling away and pincushions still take a pin.
+const MOST_PELLETS: f64 = 0.9;
+
+pub fn sphere_volume(diameter: f64) -> f64 {
+    PI * diameter.powi(3) / 6.0
+}
+
+// `volume` is in cubic `units`.
+pub fn fiberfill_grams(volume: f64, units: Unit) -> f64 {
+    volume * Unit::convert(1.0, units, Unit::Centimeter).powi(3) * FIBERFILL_GRAMS_PER_CM3
+}
+
+// Weighting a piece of `volume` cubic units to `target` grams with pellets, less the yarn's own
+// `yarn_grams` when the yarn's weight is known, for each of `pieces` matching pieces.
+pub fn weighted(
+    volume: f64,
+    unit_label: &str,
+    target: f64,
+    yarn_grams: Option<f64>,
+    pieces: usize,
+    locale: Locale,
+) -> String {
+    let grams = |value: f64| locale.decimal(value, 0);
+    let pellets = target - yarn_grams.unwrap_or(0.0);
+    let (each, set) = match pieces {
+        1 => ("it", String::new()),
+        _ => (
+            "each one",
+            format!(
+                " ({} g for the set of {})",
+                grams((pellets * pieces as f64).ceil()),
+                pieces
+            ),
+        ),
+    };
+    // Without the yarn's weight, the knitted cover is weighed to find it:
+    let cover = match yarn_grams {
+        Some(_) => "",
+        None => " less what the empty cover weighs",
+    };
+    if pellets <= 0.0 {
+        return format!(
+            "The yarn alone weighs about {} g, as much as the {} g wanted, so stuff lightly with fiberfill",
+            grams(yarn_grams.unwrap_or(0.0).ceil()),
+            grams(target)
+        );
+    }
+    let Some(units) = Unit::from_label(unit_label) else {
+        return format!(
+            "Weight {} to {} g with about {} g of plastic pellets{} in a liner{}",
+            each,
+            grams(target),
+            grams(pellets.ceil()),
+            cover,
+            set
+        );
+    };
+    let space = volume * Unit::convert(1.0, units, Unit::Centimeter).powi(3);
+    let share = pellets / PELLET_GRAMS_PER_CM3 / space;
+    if share > MOST_PELLETS {
+        return format!(
+            "{} g is more than plastic pellets can make a piece this size weigh; it holds about {} g of them, so use steel shot or make it bigger",
+            grams(target),
+            grams((space * MOST_PELLETS * PELLET_GRAMS_PER_CM3).floor())
+        );
+    }
+    format!(
+        "Weight {} to {} g: pour about {} g of plastic pellets{} into a liner, such as the toe of a stocking or a small zip bag, close it and put it in before the last rounds{}. They fill about {}% of the inside, leaving it soft",
+        each,
+        grams(target),
+        grams(pellets.ceil()),
+        cover,
+        set,
+        locale.decimal(share * 100.0, 0)
+    )
+}
+
+pub fn describe(volume: f64, unit_label: &str, locale: Locale) -> String {
+    let volume_text = locale.decimal(volume, 0);
+    match Unit::from_label(unit_label) {
+        Some(units) => format!(
+            "Stuff firmly with about {} g of fiberfill (volume ≈ {} cubic {})",
+            locale.decimal(fiberfill_grams(volume, units).ceil(), 0),
+            volume_text,
+            units.abbreviation()
+        ),
+        None => format!(
+            "Stuff firmly (volume ≈ {} cubic {})",
+            volume_text, unit_label
+        ),
+    }
+}
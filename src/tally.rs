@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+use yew::prelude::*;
+
+// The most boxes in a tally grid: a row of them beside every fifth row, one for each of the five.
+const GRID: usize = 5;
+
+// Boxes printed beside the rows, for knitters working from paper to tick off as they go.
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum Tally {
+    #[default]
+    None,
+    // A box beside every row.
+    Boxes,
+    // Five boxes beside every fifth row, one for each row of the five.
+    Grid,
+}
+
+impl Tally {
+    pub const ALL: [Tally; 3] = [Tally::None, Tally::Boxes, Tally::Grid];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Tally::None => "none",
+            Tally::Boxes => "boxes",
+            Tally::Grid => "grid",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Tally::None => "No tally boxes",
+            Tally::Boxes => "A box beside every row",
+            Tally::Grid => "A tally grid every five rows",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Tally> {
+        Tally::ALL.into_iter().find(|t| t.key() == key)
+    }
+
+    // How many boxes go beside row `index` of `rows`; none beside the rows a grid already counts.
+    fn boxes(&self, index: usize, rows: usize) -> usize {
+        match self {
+            Tally::None => 0,
+            Tally::Boxes => 1,
+            Tally::Grid if index.is_multiple_of(GRID) => GRID.min(rows - index),
+            Tally::Grid => 0,
+        }
+    }
+
+    // The boxes beside row `index` in plain text, as "[ ] ", padded so the rows line up.
+    pub fn text(&self, index: usize, rows: usize) -> String {
+        let width = match self {
+            Tally::None => return String::new(),
+            Tally::Boxes => 1,
+            Tally::Grid => GRID,
+        };
+        let boxes = "[ ]".repeat(self.boxes(index, rows));
+        format!("{:<w$} ", boxes, w = 3 * width)
+    }
+
+    // The boxes beside row `index` for printing.
+    pub fn view(&self, index: usize, rows: usize) -> Html {
+        if *self == Tally::None {
+            return html! {};
+        }
+        html! {
+            <span class={classes!("tally", self.key())} aria-hidden="true">
+                { for (0..self.boxes(index, rows)).map(|_| html! {<span class="tally-box"></span>}) }
+            </span>
+        }
+    }
+}
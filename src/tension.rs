@@ -0,0 +1,131 @@
+use crate::gauge::Gauge;
+use crate::locale::Locale;
+use crate::parse::parse_length;
+use crate::pattern::{Pattern, Row};
+use crate::units::Unit;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct TensionWizardProps {
+    pub pattern: Pattern,
+    pub gauge: Gauge,
+    pub unit_label: String,
+    pub locale: Locale,
+    // The measured gauge and the rows already knitted, to keep while the rest is worked out again.
+    pub on_adjust: Callback<(Gauge, Vec<Row>)>,
+}
+
+// The gauge a piece was actually knitted at, from how long it is after `rows` rows and, when
+// measured, how wide it is across the last of them. Anything not measured is left as it was.
+pub fn measured_gauge(
+    pattern: &Pattern,
+    gauge: Gauge,
+    rows: usize,
+    length: f64,
+    width: Option<f64>,
+) -> Gauge {
+    Gauge {
+        stitches_per_unit: width.map_or(gauge.stitches_per_unit, |width| {
+            pattern.rows[rows - 1].stitches as f64 / width
+        }),
+        rows_per_unit: rows as f64 / length,
+    }
+}
+
+// For checking the gauge partway through: the knitter enters how far they have got and what the
+// piece measures, and the rest of the pattern is worked out again at the gauge they really knit.
+#[function_component(TensionWizard)]
+pub fn tension_wizard(props: &TensionWizardProps) -> Html {
+    let pattern = &props.pattern;
+    let units = Unit::from_label(&props.unit_label);
+    let rows = use_state(String::new);
+    let length = use_state(String::new);
+    let width = use_state(String::new);
+    let on_input = |state: &UseStateHandle<String>| {
+        let state = state.clone();
+        Callback::from(move |e: InputEvent| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            state.set(input_el.value());
+        })
+    };
+
+    let word = pattern.word().to_lowercase();
+    let locale = props.locale;
+    let knitted = rows
+        .trim()
+        .parse()
+        .ok()
+        .map(|shown| pattern.numbering.row(shown))
+        .filter(|number| (1..pattern.rows.len()).contains(number));
+    let measured = match (knitted, parse_length(&length, units, locale), width.trim()) {
+        (None, ..) => Err(format!(
+            "Enter how many {}s you have knitted, fewer than the pattern has.",
+            word
+        )),
+        (_, Err(_), _) => Err(String::from("Enter how long the piece is so far.")),
+        (Some(knitted), Ok(length), "") => Ok((knitted, length, None)),
+        (Some(knitted), Ok(length), width) => match parse_length(width, units, locale) {
+            Ok(width) => Ok((knitted, length, Some(width))),
+            Err(error) => Err(error.to_string()),
+        },
+    };
+    let result = match measured {
+        Ok((_, length, width)) if length <= 0.0 || width.is_some_and(|w| w <= 0.0) => {
+            html! {<p class="field-error">{"Measurements must be greater than 0."}</p>}
+        }
+        Ok((knitted, length, width)) => {
+            let measured = measured_gauge(pattern, props.gauge, knitted, length, width);
+            let (st_ratio, row_ratio) = (
+                measured.stitches_per_unit / props.gauge.stitches_per_unit,
+                measured.rows_per_unit / props.gauge.rows_per_unit,
+            );
+            if !(0.5..=2.0).contains(&st_ratio) || !(0.5..=2.0).contains(&row_ratio) {
+                html! {
+                    <p class="field-error">{"That is more than twice or less than half the pattern's gauge; check the measurements."}</p>
+                }
+            } else {
+                let on_adjust = {
+                    let (on_adjust, knit) =
+                        (props.on_adjust.clone(), pattern.rows[..knitted].to_vec());
+                    Callback::from(move |_: MouseEvent| on_adjust.emit((measured, knit.clone())))
+                };
+                html! {
+                    <>
+                        <p>{format!(
+                            "You are knitting {} stitches and {} {}s per {}; the pattern was worked out for {} and {}.",
+                            locale.decimal(measured.stitches_per_unit, 1), locale.decimal(measured.rows_per_unit, 1), word, props.unit_label,
+                            locale.decimal(props.gauge.stitches_per_unit, 1), locale.decimal(props.gauge.rows_per_unit, 1)
+                        )}</p>
+                        <button type="button" onclick={on_adjust}>
+                            {format!("Keep my {} {}s and work the rest at this gauge", rows.trim(), word)}
+                        </button>
+                    </>
+                }
+            }
+        }
+        Err(message) => html! {<p class="field-note">{message}</p>},
+    };
+    let across = match pattern.is_flat() {
+        true => "Width across the last one: ",
+        false => "Around the last one: ",
+    };
+
+    html! {
+        <div class="tension-wizard">
+            <span>
+                <label>{format!("{}s knitted so far: ", pattern.word())}</label>
+                <input aria-label={format!("{}s knitted so far", pattern.word())} type="text" size="4" value={(*rows).clone()} oninput={on_input(&rows)}/>
+            </span>
+            <span>
+                <label>{"Length of the piece: "}</label>
+                <input aria-label="Length of the piece" type="text" size="6" placeholder={props.unit_label.clone()} value={(*length).clone()} oninput={on_input(&length)}/>
+            </span>
+            <span>
+                <label>{across}</label>
+                <input aria-label={across.trim_end_matches([':', ' '])} type="text" size="6" placeholder="Optional" value={(*width).clone()} oninput={on_input(&width)}/>
+            </span>
+            { result }
+        </div>
+    }
+}
@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum Theme {
+    #[default]
+    Light,
+    Dark,
+    HighContrast,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 3] = [Theme::Light, Theme::Dark, Theme::HighContrast];
+
+    // Also the value of the `data-theme` attribute the stylesheet picks colors from.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Theme::Light => "light",
+            Theme::Dark => "dark",
+            Theme::HighContrast => "high-contrast",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Theme::Light => "Light",
+            Theme::Dark => "Dark",
+            Theme::HighContrast => "High contrast",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Theme> {
+        Theme::ALL.into_iter().find(|t| t.key() == key)
+    }
+}
+
+// The current theme and a way to change it, provided to the whole app.
+#[derive(Clone, PartialEq)]
+pub struct ThemeContext {
+    pub theme: Theme,
+    pub set_theme: Callback<Theme>,
+}
+
+#[function_component(ThemePicker)]
+pub fn theme_picker() -> Html {
+    let context = use_context::<ThemeContext>();
+    let Some(context) = context else {
+        return html! {};
+    };
+    let on_change = context.set_theme.reform(|e: Event| {
+        let select_el: HtmlSelectElement = e.target_unchecked_into();
+        Theme::from_key(&select_el.value()).unwrap_or_default()
+    });
+
+    html! {
+        <span class="theme-picker">
+            <label>{"Theme: "}</label>
+            <select aria-label="Theme" onchange={on_change}>
+                { for Theme::ALL.iter().map(|t| html! {
+                    <option value={t.key()} selected={*t == context.theme}>{t.name()}</option>
+                }) }
+            </select>
+        </span>
+    }
+}
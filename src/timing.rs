@@ -0,0 +1,30 @@
+use crate::gauge::Fabric;
+use crate::locale::Locale;
+use crate::parse::parse_number;
+use crate::pattern::Pattern;
+use crate::storage;
+
+// Such as "about 3 h 20 min".
+pub fn describe(stitches: i64, per_minute: f64) -> String {
+    let minutes = (stitches as f64 / per_minute).round() as i64;
+    format!("about {} h {} min", minutes / 60, minutes % 60)
+}
+
+// How long the whole pattern should take, at the pace saved in the row tracker or else a typical
+// one for the fabric.
+pub fn summary(pattern: &Pattern, fabric: Fabric, locale: Locale) -> String {
+    let saved = parse_number(&storage::load_speed(), locale)
+        .ok()
+        .filter(|speed| *speed > 0.0);
+    let speed = saved.unwrap_or_else(|| fabric.stitches_per_minute());
+    format!(
+        "Time to make: {} for {} stitches at {} stitches a minute{}",
+        describe(pattern.total_stitches(), speed),
+        pattern.total_stitches(),
+        locale.short(speed),
+        match saved {
+            Some(_) => "",
+            None => " (a typical pace; time yourself in the row tracker for your own)",
+        }
+    )
+}
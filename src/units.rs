@@ -0,0 +1,59 @@
+// Units of length that measurements can be entered in and converted between.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Unit {
+    Inch,
+    Centimeter,
+    Millimeter,
+}
+
+impl Unit {
+    pub fn from_label(label: &str) -> Option<Unit> {
+        match label.trim().to_lowercase().as_str() {
+            "in" | "in." | "inch" | "inches" | "\"" => Some(Unit::Inch),
+            "cm" | "centimeter" | "centimeters" | "centimetre" | "centimetres" => {
+                Some(Unit::Centimeter)
+            }
+            "mm" | "millimeter" | "millimeters" | "millimetre" | "millimetres" => {
+                Some(Unit::Millimeter)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn abbreviation(&self) -> &'static str {
+        match self {
+            Unit::Inch => "in",
+            Unit::Centimeter => "cm",
+            Unit::Millimeter => "mm",
+        }
+    }
+
+    // The unit yarn is sold by alongside this one, and how many of this unit make one of it.
+    pub fn yarn_unit(&self) -> (&'static str, f64) {
+        match self {
+            Unit::Inch => ("yd", 36.0),
+            Unit::Centimeter => ("m", 100.0),
+            Unit::Millimeter => ("m", 1000.0),
+        }
+    }
+
+    fn in_cm(&self) -> f64 {
+        match self {
+            Unit::Inch => 2.54,
+            Unit::Centimeter => 1.0,
+            Unit::Millimeter => 0.1,
+        }
+    }
+
+    pub fn convert(value: f64, from: Unit, to: Unit) -> f64 {
+        value * from.in_cm() / to.in_cm()
+    }
+
+    // The unit a reader on the other side of the metric divide would measure in.
+    pub fn other(&self) -> Unit {
+        match self {
+            Unit::Inch => Unit::Centimeter,
+            Unit::Centimeter | Unit::Millimeter => Unit::Inch,
+        }
+    }
+}
@@ -0,0 +1,345 @@
+use crate::locale::Locale;
+use crate::numbering::Numbering;
+use crate::pattern::Pattern;
+use crate::units::Unit;
+
+// Anything wider than this is almost certainly a typo in a dimension or the gauge.
+pub const MAX_STITCHES_PER_ROW: f64 = 1000.0;
+pub const MAX_ROWS: f64 = 1000.0;
+
+// Bobbles crowd each other out well before this; many more only slow down placing them.
+pub const MAX_BOBBLES_PER_100: f64 = 50.0;
+
+// Plausible gauges in stitches or rows per inch, from lace weight down to super bulky.
+pub const STITCHES_PER_INCH: (f64, f64) = (1.0, 16.0);
+pub const ROWS_PER_INCH: (f64, f64) = (1.0, 24.0);
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Field {
+    Diameter,
+    Depth,
+    Weight,
+    Circumference,
+    Length,
+    Width,
+    StitchesPerUnit,
+    RowsPerUnit,
+    BobbleDensity,
+    Repeat,
+    Pattern,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Issue {
+    pub field: Field,
+    pub severity: Severity,
+    pub message: String,
+}
+
+#[derive(Clone, Default, PartialEq)]
+pub struct Validation {
+    pub issues: Vec<Issue>,
+}
+
+impl Validation {
+    pub fn has_errors(&self) -> bool {
+        self.issues.iter().any(|i| i.severity == Severity::Error)
+    }
+
+    pub fn for_field(&self, field: Field) -> impl Iterator<Item = &Issue> {
+        self.issues.iter().filter(move |i| i.field == field)
+    }
+
+    pub fn merge(&mut self, other: Validation) {
+        self.issues.extend(other.issues);
+    }
+
+    pub fn error(&mut self, field: Field, message: String) {
+        self.issues.push(Issue {
+            field,
+            severity: Severity::Error,
+            message,
+        });
+    }
+
+    pub fn warning(&mut self, field: Field, message: String) {
+        self.issues.push(Issue {
+            field,
+            severity: Severity::Warning,
+            message,
+        });
+    }
+
+    pub fn require_positive(&mut self, field: Field, value: Option<f64>, what: &str) {
+        if value.is_some_and(|value| value <= 0.0) {
+            self.error(field, format!("{} must be greater than 0", what));
+        }
+    }
+
+    // Rejects patterns too big to be anything but a typo, before they are generated.
+    pub fn check_size(&mut self, widest: f64, total_rows: f64) {
+        if widest > MAX_STITCHES_PER_ROW {
+            self.error(
+                Field::Pattern,
+                format!(
+                    "The widest row would need about {:.0} stitches (the limit is {:.0}); check that the dimensions and stitch gauge use the same units",
+                    widest, MAX_STITCHES_PER_ROW
+                ),
+            );
+        }
+        if total_rows > MAX_ROWS {
+            self.error(
+                Field::Pattern,
+                format!(
+                    "The pattern would need about {:.0} rows (the limit is {:.0}); check the dimensions and row gauge",
+                    total_rows, MAX_ROWS
+                ),
+            );
+        }
+    }
+
+    // Warns of shaping that comes out badly however carefully it is worked: rows with more than one
+    // increase for every two stitches, which ruffle, or so few rows that the curve is a few coarse
+    // steps. The first increases off the cast on are left out, since a ring is meant to double.
+    pub fn check_shaping(&mut self, pattern: &Pattern, unit_label: &str) {
+        let rows = &pattern.rows;
+        let ring = (1..rows.len()).find(|i| rows[*i].stitches != rows[i - 1].stitches);
+        let steep: Vec<usize> = (1..rows.len())
+            .filter(|i| Some(*i) != ring)
+            .filter(|i| {
+                let (below, row) = (pattern.rows[i - 1].stitches, pattern.rows[*i].stitches);
+                2 * (row - below) > below
+            })
+            .collect();
+        if let Some(&first) = steep.first() {
+            let more = match steep.len() - 1 {
+                0 => String::new(),
+                1 => String::from(", as does 1 more row"),
+                more => format!(", as do {} more rows", more),
+            };
+            self.warning(
+                Field::Pattern,
+                format!(
+                    "{} goes from {} to {} stitches, more than one increase for every two stitches, and may ruffle{}. Try shaping more often, a finer row gauge, or check the gauge against a swatch.",
+                    pattern.label(first),
+                    pattern.rows[first - 1].stitches,
+                    pattern.rows[first].stitches,
+                    more
+                ),
+            );
+        }
+        if pattern.rows.len() < 6 {
+            self.warning(
+                Field::Pattern,
+                format!(
+                    "The whole pattern is only {} {}s, so the shape will come out in coarse steps; try a larger size or a finer gauge, with more stitches and rows per {}.",
+                    pattern.rows.len(),
+                    pattern.word().to_lowercase(),
+                    unit_label
+                ),
+            );
+        }
+    }
+
+    // Stitch counts typed in by hand must be workable: every row can at most double or halve. Rows
+    // are named as `numbering` names them on a piece worked `flat` or not.
+    pub fn check_counts(&mut self, counts: &[i32], numbering: Numbering, flat: bool) {
+        if counts.is_empty() {
+            self.error(
+                Field::Pattern,
+                String::from("The pattern needs at least one row"),
+            );
+        }
+        for (i, &count) in counts.iter().enumerate() {
+            let row = count_label(numbering, i + 1, flat);
+            let previous = i.checked_sub(1).map(|p| counts[p]).filter(|p| *p >= 1);
+            if count < 1 {
+                self.error(Field::Pattern, format!("{} needs at least 1 stitch", row));
+            } else if count as f64 > MAX_STITCHES_PER_ROW {
+                self.error(
+                    Field::Pattern,
+                    format!("{} has more than {:.0} stitches", row, MAX_STITCHES_PER_ROW),
+                );
+            } else if let Some(previous) = previous.filter(|p| count > 2 * p) {
+                self.error(
+                    Field::Pattern,
+                    format!(
+                        "{} can't grow from {} to {} stitches; a row can at most double",
+                        row, previous, count
+                    ),
+                );
+            } else if let Some(previous) = previous.filter(|p| 2 * count < *p) {
+                self.error(
+                    Field::Pattern,
+                    format!(
+                        "{} can't shrink from {} to {} stitches; a row can at most halve",
+                        row, previous, count
+                    ),
+                );
+            }
+        }
+    }
+
+    pub fn check_bobbles(&mut self, per_100: Option<f64>) {
+        if per_100.is_some_and(|density| !(0.0..=MAX_BOBBLES_PER_100).contains(&density)) {
+            self.error(
+                Field::BobbleDensity,
+                format!(
+                    "Bobbles per 100 stitches must be between 0 and {:.0}",
+                    MAX_BOBBLES_PER_100
+                ),
+            );
+        }
+    }
+
+    // A repeat wider than the widest row allowed can't be fitted into any cast on.
+    pub fn check_repeat(&mut self, repeat: Option<usize>) {
+        if repeat.is_some_and(|repeat| repeat as f64 > MAX_STITCHES_PER_ROW) {
+            self.error(
+                Field::Repeat,
+                format!(
+                    "The stitch pattern repeat can be at most {:.0} stitches",
+                    MAX_STITCHES_PER_ROW
+                ),
+            );
+        }
+    }
+
+    pub fn check_first_row(&mut self, stitches: Option<i32>) {
+        if stitches.is_none_or(|first| first < 1) {
+            self.error(
+                Field::Pattern,
+                String::from(
+                    "The first row would have no stitches; try a larger size or a finer row gauge",
+                ),
+            );
+        }
+    }
+
+    fn check_gauge(
+        &mut self,
+        field: Field,
+        per_unit: f64,
+        what: &str,
+        per_inch_range: (f64, f64),
+        unit_label: &str,
+        locale: Locale,
+    ) {
+        if per_unit <= 0.0 {
+            self.error(
+                field,
+                format!("{} per {} must be greater than 0", what, unit_label),
+            );
+            return;
+        }
+        // Plausibility can only be judged when we know what the units are:
+        if let Some(units) = Unit::from_label(unit_label) {
+            // Converting a length of 1 unit to inches gives how many units fit in an inch:
+            let units_per_inch = Unit::convert(1.0, Unit::Inch, units);
+            let (low, high) = (
+                per_inch_range.0 / units_per_inch,
+                per_inch_range.1 / units_per_inch,
+            );
+            if per_unit < low || per_unit > high {
+                self.warning(
+                    field,
+                    format!(
+                        "{} {}/{} is unusual (most yarns give {}–{}); double-check your swatch",
+                        locale.number(per_unit),
+                        what.to_lowercase(),
+                        unit_label,
+                        locale.decimal(low, 1),
+                        locale.decimal(high, 1)
+                    ),
+                );
+            }
+        }
+    }
+}
+
+// Row `number` of hand-typed counts, counted from 1 at the cast on, named the way the pattern
+// names it, such as "Round 3", or "The cast on" when it goes unnumbered.
+pub fn count_label(numbering: Numbering, number: usize, flat: bool) -> String {
+    match numbering.label(number, flat) {
+        label if label.is_empty() => String::from("The cast on"),
+        label => label,
+    }
+}
+
+// Checks whichever parts of the gauge could be parsed.
+pub fn validate_gauge(
+    stitches_per_unit: Option<f64>,
+    rows_per_unit: Option<f64>,
+    unit_label: &str,
+    locale: Locale,
+) -> Validation {
+    let mut validation = Validation::default();
+    if let Some(st) = stitches_per_unit {
+        validation.check_gauge(
+            Field::StitchesPerUnit,
+            st,
+            "Stitches",
+            STITCHES_PER_INCH,
+            unit_label,
+            locale,
+        );
+    }
+    if let Some(rows) = rows_per_unit {
+        validation.check_gauge(
+            Field::RowsPerUnit,
+            rows,
+            "Rows",
+            ROWS_PER_INCH,
+            unit_label,
+            locale,
+        );
+    }
+    validation
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::numbering::RowWord;
+
+    fn messages(counts: &[i32], numbering: Numbering, flat: bool) -> Vec<String> {
+        let mut validation = Validation::default();
+        validation.check_counts(counts, numbering, flat);
+        validation.issues.into_iter().map(|i| i.message).collect()
+    }
+
+    #[test]
+    fn counts_are_named_rows_or_rounds_as_the_piece_is_worked() {
+        let numbering = Numbering::default();
+        assert_eq!(
+            messages(&[6, 13], numbering, false),
+            ["Round 2 can't grow from 6 to 13 stitches; a row can at most double"]
+        );
+        assert_eq!(
+            messages(&[6, 0], numbering, true),
+            ["Row 2 needs at least 1 stitch"]
+        );
+    }
+
+    #[test]
+    fn counts_follow_the_numbering() {
+        let numbering = Numbering {
+            word: RowWord::Row,
+            skip_cast_on: true,
+            ..Numbering::default()
+        };
+        assert_eq!(
+            messages(&[0, 6, 2], numbering, false),
+            [
+                "The cast on needs at least 1 stitch",
+                "Row 2 can't shrink from 6 to 2 stitches; a row can at most halve"
+            ]
+        );
+    }
+}
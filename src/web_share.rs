@@ -0,0 +1,109 @@
+use crate::export;
+use crate::gauge::Gauge;
+use crate::params::Params;
+use crate::shapes::{Design, Shape};
+use crate::share::ShareQuery;
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::js_sys::Reflect;
+use web_sys::wasm_bindgen::{JsCast, JsValue};
+use web_sys::{DomException, Navigator, ShareData};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct ShareButtonsProps {
+    pub shape: Shape,
+    pub design: Design,
+    pub params: Params,
+    pub gauge: Gauge,
+}
+
+fn has_share(navigator: &Navigator) -> bool {
+    Reflect::has(navigator, &"share".into()).unwrap_or(false)
+}
+
+// Hands `data` to the phone's share sheet, or where there is none copies `copy` to the clipboard,
+// then says what happened through `status`. Closing the share sheet without sharing says nothing.
+fn share(data: ShareData, copy: String, copied: &'static str, status: UseStateHandle<String>) {
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+    let navigator = window.navigator();
+    let shared = has_share(&navigator).then(|| navigator.share_with_data(&data));
+    spawn_local(async move {
+        if let Some(shared) = shared {
+            match JsFuture::from(shared).await {
+                Ok(_) => return status.set(String::new()),
+                Err(err) if is_abort(&err) => return,
+                // Some browsers refuse to share long text; the clipboard still takes it:
+                Err(_) => {}
+            }
+        }
+        let written = JsFuture::from(navigator.clipboard().write_text(&copy)).await;
+        status.set(match written {
+            Ok(_) => copied.to_string(),
+            Err(_) => String::from("Couldn't share or copy it from this browser."),
+        });
+    });
+}
+
+fn is_abort(err: &JsValue) -> bool {
+    err.dyn_ref::<DomException>()
+        .is_some_and(|err| err.name() == "AbortError")
+}
+
+// Buttons to send the pattern straight to a messaging app through the browser's share sheet, as
+// a link to its read-only view or as the whole text. Browsers without one copy it instead.
+#[function_component(ShareButtons)]
+pub fn share_buttons(props: &ShareButtonsProps) -> Html {
+    let status = use_state(String::new);
+    let title = match props.params.metadata.title.trim() {
+        "" => format!("{} pattern", props.shape.name()),
+        title => title.to_string(),
+    };
+    let on_share_link = {
+        let (shape, params, title, status) = (
+            props.shape,
+            props.params.clone(),
+            title.clone(),
+            status.clone(),
+        );
+        Callback::from(move |_: MouseEvent| {
+            let Some(url) = ShareQuery::view_url(shape, &params) else {
+                return;
+            };
+            let data = ShareData::new();
+            data.set_title(&title);
+            data.set_url(&url);
+            share(data, url, "Link copied to the clipboard.", status.clone());
+        })
+    };
+    let on_share_text = {
+        let (shape, design, params, gauge) = (
+            props.shape,
+            props.design.clone(),
+            props.params.clone(),
+            props.gauge,
+        );
+        let status = status.clone();
+        Callback::from(move |_: MouseEvent| {
+            let text = export::pattern_text(shape, &design, &params, gauge);
+            let data = ShareData::new();
+            data.set_title(&title);
+            data.set_text(&text);
+            share(
+                data,
+                text,
+                "Pattern copied to the clipboard.",
+                status.clone(),
+            );
+        })
+    };
+
+    html! {
+        <span class="share-buttons">
+            <button type="button" onclick={on_share_link}>{"Share link"}</button>
+            <button type="button" onclick={on_share_text}>{"Share text"}</button>
+            <span class="field-note" role="status">{(*status).clone()}</span>
+        </span>
+    }
+}
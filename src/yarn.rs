@@ -0,0 +1,89 @@
+use crate::locale::Locale;
+use crate::units::Unit;
+
+// Extra yarn for the tails, seaming and the odd mistake.
+const ALLOWANCE: f64 = 1.1;
+
+// A stitch uses roughly twice its width plus twice its height in yarn, a loop up one side and
+// down the other, which is close to measured lengths for typical stockinette.
+pub fn yarn_per_stitch(stitches_per_unit: f64, rows_per_unit: f64) -> f64 {
+    2.0 * (1.0 / stitches_per_unit + 1.0 / rows_per_unit)
+}
+
+pub struct YarnEstimate {
+    pub length: f64,
+    pub unit: String,
+    pub grams: Option<f64>,
+}
+
+impl YarnEstimate {
+    // `length_per_100g` is in the same unit as the estimate (yards or meters).
+    pub fn new(
+        total_stitches: i64,
+        stitches_per_unit: f64,
+        rows_per_unit: f64,
+        unit_label: &str,
+        length_per_100g: Option<f64>,
+    ) -> YarnEstimate {
+        let length =
+            total_stitches as f64 * yarn_per_stitch(stitches_per_unit, rows_per_unit) * ALLOWANCE;
+        let (length, unit) = match Unit::from_label(unit_label) {
+            Some(units) => {
+                let (yarn_unit, per_yarn_unit) = units.yarn_unit();
+                (length / per_yarn_unit, String::from(yarn_unit))
+            }
+            None => (length, String::from(unit_label)),
+        };
+        YarnEstimate {
+            length,
+            unit,
+            grams: length_per_100g
+                .filter(|per_100g| *per_100g > 0.0)
+                .map(|per_100g| 100.0 * length / per_100g),
+        }
+    }
+
+    pub fn describe(&self, locale: Locale) -> String {
+        let length = locale.decimal(self.length.ceil(), 0);
+        match self.grams {
+            Some(grams) => format!(
+                "About {} {} of yarn (≈ {} g)",
+                length,
+                self.unit,
+                locale.decimal(grams.ceil(), 0)
+            ),
+            None => format!("About {} {} of yarn", length, self.unit),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stitch_takes_twice_its_width_and_twice_its_height() {
+        // 20 stitches and 28 rows to 10 cm: stitches 0.5 cm wide and 5/14 cm tall.
+        let per_stitch = yarn_per_stitch(2.0, 2.8);
+        assert!((per_stitch - 2.0 * (0.5 + 5.0 / 14.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn estimate_is_in_yarn_units_with_an_allowance() {
+        let estimate = YarnEstimate::new(1000, 2.0, 2.8, "cm", Some(200.0));
+        assert_eq!(estimate.unit, "m");
+        assert!((estimate.length - 1000.0 * yarn_per_stitch(2.0, 2.8) * 1.1 / 100.0).abs() < 1e-9);
+        assert!((estimate.grams.unwrap() - estimate.length / 2.0).abs() < 1e-9);
+        assert_eq!(
+            estimate.describe(Locale::default()),
+            "About 19 m of yarn (≈ 10 g)"
+        );
+    }
+
+    #[test]
+    fn estimate_without_a_weight_has_no_grams() {
+        let estimate = YarnEstimate::new(1000, 2.0, 2.8, "cm", None);
+        assert!(estimate.grams.is_none());
+        assert_eq!(estimate.describe(Locale::default()), "About 19 m of yarn");
+    }
+}
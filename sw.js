@@ -0,0 +1,60 @@
+// Keeps a copy of everything the app loads, so it still works without a connection once visited.
+// Requests go to the network first so updates are picked up, falling back to the copy when offline.
+const CACHE = "knitting-pattern-generator-v2";
+
+// The page and the files it links to, which Trunk names by their content. The page has already
+// loaded them by the time the worker takes control, so they are fetched again here to work offline
+// from the first visit.
+function precache() {
+  return caches.open(CACHE).then((cache) =>
+    fetch("./").then((response) => {
+      if (!response.ok) {
+        return undefined;
+      }
+      return response.text().then((html) => {
+        const links = [...html.matchAll(/(?:href|src)=["']([^"']+)["']|["']([^"'\s]+\.(?:js|wasm))["']/g)]
+          .map((match) => new URL(match[1] || match[2], self.registration.scope))
+          .filter((url) => url.origin === self.location.origin)
+          .map((url) => url.href);
+        const urls = new Set(["./", "index.html", ...links]);
+        // One missing file shouldn't keep the worker from installing:
+        return Promise.all([...urls].map((url) => cache.add(url).catch(() => undefined)));
+      });
+    })
+  );
+}
+
+self.addEventListener("install", (event) => {
+  event.waitUntil(precache().catch(() => undefined).then(() => self.skipWaiting()));
+});
+
+self.addEventListener("activate", (event) => {
+  event.waitUntil(
+    caches
+      .keys()
+      .then((keys) => Promise.all(keys.filter((key) => key !== CACHE).map((key) => caches.delete(key))))
+      .then(() => self.clients.claim())
+  );
+});
+
+self.addEventListener("fetch", (event) => {
+  if (event.request.method !== "GET") {
+    return;
+  }
+  event.respondWith(
+    fetch(event.request)
+      .then((response) => {
+        // Errors and responses from other sites, which can't be checked, aren't kept:
+        if (response.ok) {
+          const copy = response.clone();
+          caches.open(CACHE).then((cache) => cache.put(event.request, copy));
+        }
+        return response;
+      })
+      .catch(() =>
+        caches
+          .match(event.request, { ignoreSearch: true })
+          .then((cached) => cached || (event.request.mode === "navigate" ? caches.match("./") : undefined))
+      )
+  );
+});